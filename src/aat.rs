@@ -0,0 +1,463 @@
+//! Common types for Apple Advanced Typography tables.
+//!
+//! https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6Tables.html
+
+use core::cmp::Ordering;
+use core::num::NonZeroU16;
+
+use crate::GlyphId;
+use crate::parser::*;
+
+
+/// Predefined classes.
+///
+/// Each AAT state table has at least four classes.
+pub mod class {
+    #![allow(missing_docs)]
+    pub const END_OF_TEXT: u16 = 0;
+    pub const OUT_OF_BOUNDS: u16 = 1;
+    pub const DELETED_GLYPH: u16 = 2;
+    pub const END_OF_LINE: u16 = 3;
+}
+
+
+/// A binary search table used by the lookup table formats 2, 4 and 6.
+///
+/// Unlike `LazyArray16`, the unit size is stored in the table itself.
+#[derive(Clone, Copy)]
+struct BinarySearchTable<'a> {
+    data: &'a [u8],
+    unit_size: u16,
+    len: u16,
+}
+
+impl<'a> BinarySearchTable<'a> {
+    fn parse(s: &mut Stream<'a>) -> Option<Self> {
+        let unit_size: u16 = s.read()?;
+        let number_of_units: u16 = s.read()?;
+        s.advance(6); // searchRange (u16) + entrySelector (u16) + rangeShift (u16)
+
+        if unit_size == 0 {
+            return None;
+        }
+
+        let data = s.read_bytes(usize::from(unit_size) * usize::from(number_of_units))?;
+        let mut table = BinarySearchTable {
+            data,
+            unit_size,
+            len: number_of_units,
+        };
+
+        // 'The last entry may be a special 0xFFFF entry, that should be ignored.'
+        if let Some(last) = table.len.checked_sub(1).and_then(|idx| table.get(idx)) {
+            if Stream::read_at::<u16>(last, 0) == Some(0xFFFF) {
+                table.len -= 1;
+            }
+        }
+
+        Some(table)
+    }
+
+    #[inline]
+    fn get(&self, index: u16) -> Option<&'a [u8]> {
+        if index < self.len {
+            let start = usize::from(index) * usize::from(self.unit_size);
+            let end = start + usize::from(self.unit_size);
+            self.data.get(start..end)
+        } else {
+            None
+        }
+    }
+
+    fn binary_search_by<F>(&self, mut f: F) -> Option<&'a [u8]>
+        where F: FnMut(&[u8]) -> Option<Ordering>
+    {
+        // Based on `LazyArray16::binary_search_by`.
+
+        let mut size = self.len;
+        if size == 0 {
+            return None;
+        }
+
+        let mut base = 0;
+        while size > 1 {
+            let half = size / 2;
+            let mid = base + half;
+            let cmp = f(self.get(mid)?)?;
+            base = if cmp == Ordering::Greater { base } else { mid };
+            size -= half;
+        }
+
+        let value = self.get(base)?;
+        if f(value)? == Ordering::Equal { Some(value) } else { None }
+    }
+}
+
+
+/// An [AAT Lookup Table](
+/// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6Tables.html).
+///
+/// Maps glyphs to 16 or 32 bit values.
+#[derive(Clone, Copy)]
+pub(crate) struct Lookup<'a> {
+    data: &'a [u8],
+    format: u16,
+    number_of_glyphs: NonZeroU16,
+}
+
+impl<'a> Lookup<'a> {
+    pub fn parse(number_of_glyphs: NonZeroU16, data: &'a [u8]) -> Option<Self> {
+        let format: u16 = Stream::read_at(data, 0)?;
+        match format {
+            0 | 2 | 4 | 6 | 8 | 10 => Some(Lookup { data, format, number_of_glyphs }),
+            _ => None,
+        }
+    }
+
+    /// Returns a 16 bit value associated with a glyph.
+    #[inline]
+    pub fn value(&self, glyph_id: GlyphId) -> Option<u16> {
+        self.lookup(glyph_id, u16::SIZE).and_then(|n| core::convert::TryFrom::try_from(n).ok())
+    }
+
+    /// Returns a 32 bit value associated with a glyph.
+    ///
+    /// Used only by tables with *long* values.
+    #[inline]
+    pub fn value32(&self, glyph_id: GlyphId) -> Option<u32> {
+        self.lookup(glyph_id, u32::SIZE)
+    }
+
+    fn lookup(&self, glyph_id: GlyphId, value_size: usize) -> Option<u32> {
+        let mut s = Stream::new_at(self.data, u16::SIZE)?;
+        match self.format {
+            0 => {
+                // Simple array. One value per glyph.
+                if glyph_id.0 >= self.number_of_glyphs.get() {
+                    return None;
+                }
+
+                s.advance(usize::from(glyph_id.0) * value_size);
+                read_value(s.read_bytes(value_size)?)
+            }
+            2 => {
+                // Segment single.
+                let table = BinarySearchTable::parse(&mut s)?;
+                let segment = table.binary_search_by(|data| cmp_segment(data, glyph_id))?;
+                read_value(segment.get(4..4 + value_size)?)
+            }
+            4 => {
+                // Segment array.
+                let table = BinarySearchTable::parse(&mut s)?;
+                let segment = table.binary_search_by(|data| cmp_segment(data, glyph_id))?;
+                let first_glyph: u16 = Stream::read_at(segment, 2)?;
+                // The offset is from the start of the lookup table.
+                let offset = Stream::read_at::<Offset16>(segment, 4)?.to_usize();
+                let index = usize::from(glyph_id.0 - first_glyph);
+                let start = offset + index * value_size;
+                read_value(self.data.get(start..start + value_size)?)
+            }
+            6 => {
+                // Single table.
+                let table = BinarySearchTable::parse(&mut s)?;
+                let entry = table.binary_search_by(|data| {
+                    Some(Stream::read_at::<GlyphId>(data, 0)?.cmp(&glyph_id))
+                })?;
+                read_value(entry.get(2..2 + value_size)?)
+            }
+            8 => {
+                // Trimmed array.
+                let first_glyph: u16 = s.read()?;
+                let glyph_count: u16 = s.read()?;
+                let index = glyph_id.0.checked_sub(first_glyph)?;
+                if index >= glyph_count {
+                    return None;
+                }
+
+                s.advance(usize::from(index) * value_size);
+                read_value(s.read_bytes(value_size)?)
+            }
+            10 => {
+                // Extended trimmed array. Stores its own value size.
+                let unit_size = usize::from(s.read::<u16>()?);
+                let first_glyph: u16 = s.read()?;
+                let glyph_count: u16 = s.read()?;
+                let index = glyph_id.0.checked_sub(first_glyph)?;
+                if index >= glyph_count {
+                    return None;
+                }
+
+                s.advance(usize::from(index) * unit_size);
+                read_value(s.read_bytes(unit_size)?)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Debug for Lookup<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Lookup {{ format: {} }}", self.format)
+    }
+}
+
+/// Compares a glyph with a `LookupSegment`.
+///
+/// Segments are stored as `lastGlyph`, `firstGlyph`, `value`.
+#[inline]
+fn cmp_segment(data: &[u8], glyph_id: GlyphId) -> Option<Ordering> {
+    let last_glyph: u16 = Stream::read_at(data, 0)?;
+    let first_glyph: u16 = Stream::read_at(data, 2)?;
+    Some(if glyph_id.0 < first_glyph {
+        Ordering::Greater
+    } else if glyph_id.0 > last_glyph {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    })
+}
+
+#[inline]
+fn read_value(data: &[u8]) -> Option<u32> {
+    match data.len() {
+        1 => u8::parse(data).map(u32::from),
+        2 => u16::parse(data).map(u32::from),
+        4 => u32::parse(data),
+        _ => None,
+    }
+}
+
+
+/// An [Extended State Table](
+/// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6Tables.html).
+///
+/// `T` is the type of the per-entry data that follows `newState` and `flags`.
+#[derive(Clone, Copy)]
+pub(crate) struct ExtendedStateTable<'a, T> {
+    number_of_classes: u32,
+    lookup: Lookup<'a>,
+    state_array: &'a [u8],
+    entry_table: &'a [u8],
+    entry_type: core::marker::PhantomData<T>,
+}
+
+impl<'a, T: FromData> ExtendedStateTable<'a, T> {
+    /// Parses a state table header.
+    ///
+    /// All offsets are from the start of the `data`, which is also
+    /// the start of the state table header.
+    pub fn parse(number_of_glyphs: NonZeroU16, data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+
+        let number_of_classes: u32 = s.read()?;
+        let lookup_table_offset = s.read::<Offset32>()?.to_usize();
+        let state_array_offset = s.read::<Offset32>()?.to_usize();
+        let entry_table_offset = s.read::<Offset32>()?.to_usize();
+
+        Some(ExtendedStateTable {
+            number_of_classes,
+            lookup: Lookup::parse(number_of_glyphs, data.get(lookup_table_offset..)?)?,
+            // We don't know the actual data size and it's kinda expensive to calculate.
+            // So we are simply storing all the data past the offset.
+            // Despite the fact that they may overlap.
+            state_array: data.get(state_array_offset..)?,
+            entry_table: data.get(entry_table_offset..)?,
+            entry_type: core::marker::PhantomData,
+        })
+    }
+
+    /// Returns a glyph class.
+    #[inline]
+    pub fn class(&self, glyph_id: GlyphId) -> Option<u16> {
+        if glyph_id.0 == 0xFFFF {
+            return Some(class::DELETED_GLYPH);
+        }
+
+        self.lookup.value(glyph_id)
+    }
+
+    /// Returns a class entry.
+    #[inline]
+    pub fn entry(&self, state: u16, mut class: u16) -> Option<StateEntry<T>> {
+        if u32::from(class) >= self.number_of_classes {
+            class = class::OUT_OF_BOUNDS;
+        }
+
+        let state_idx =
+            usize::from(state) * usize::num_from(self.number_of_classes) + usize::from(class);
+
+        let entry_idx: u16 = Stream::read_at(self.state_array, state_idx * u16::SIZE)?;
+        Stream::read_at(self.entry_table, usize::from(entry_idx) * StateEntry::<T>::SIZE)
+    }
+}
+
+
+/// A state table entry.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct StateEntry<T> {
+    pub new_state: u16,
+    pub flags: u16,
+    pub extra: T,
+}
+
+impl<T: FromData> FromData for StateEntry<T> {
+    const SIZE: usize = 4 + T::SIZE;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(StateEntry {
+            new_state: s.read::<u16>()?,
+            flags: s.read::<u16>()?,
+            extra: s.read::<T>()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(data: &[u8], glyph_id: u16) -> Option<u16> {
+        Lookup::parse(NonZeroU16::new(10).unwrap(), data)?.value(GlyphId(glyph_id))
+    }
+
+    #[test]
+    fn format0() {
+        let data = &[
+            0x00, 0x00, // format: 0
+            0x00, 0x0A, // value [0]: 10
+            0x00, 0x14, // value [1]: 20
+        ];
+
+        assert_eq!(lookup(data, 0), Some(10));
+        assert_eq!(lookup(data, 1), Some(20));
+        assert_eq!(lookup(data, 2), None);
+        assert_eq!(lookup(data, 100), None);
+    }
+
+    #[test]
+    fn format2() {
+        let data = &[
+            0x00, 0x02, // format: 2
+            // Binary search header
+            0x00, 0x06, // unit size: 6
+            0x00, 0x03, // number of units: 3
+            0x00, 0x0C, // search range: 12
+            0x00, 0x01, // entry selector: 1
+            0x00, 0x06, // range shift: 6
+            // Segment [0]
+            0x00, 0x03, // last glyph: 3
+            0x00, 0x01, // first glyph: 1
+            0x00, 0x0A, // value: 10
+            // Segment [1]
+            0x00, 0x07, // last glyph: 7
+            0x00, 0x05, // first glyph: 5
+            0x00, 0x14, // value: 20
+            // Terminator
+            0xFF, 0xFF, // last glyph: 65535
+            0xFF, 0xFF, // first glyph: 65535
+            0x00, 0x00, // value: 0
+        ];
+
+        assert_eq!(lookup(data, 0), None);
+        assert_eq!(lookup(data, 1), Some(10));
+        assert_eq!(lookup(data, 3), Some(10));
+        assert_eq!(lookup(data, 4), None);
+        assert_eq!(lookup(data, 6), Some(20));
+        assert_eq!(lookup(data, 8), None);
+        assert_eq!(lookup(data, 0xFFFF), None);
+    }
+
+    #[test]
+    fn format4() {
+        let data = &[
+            0x00, 0x04, // format: 4
+            // Binary search header
+            0x00, 0x06, // unit size: 6
+            0x00, 0x01, // number of units: 1
+            0x00, 0x06, // search range: 6
+            0x00, 0x00, // entry selector: 0
+            0x00, 0x00, // range shift: 0
+            // Segment [0]
+            0x00, 0x03, // last glyph: 3
+            0x00, 0x02, // first glyph: 2
+            0x00, 0x12, // offset: 18
+            // Values
+            0x00, 0x0A, // value [0]: 10
+            0x00, 0x14, // value [1]: 20
+        ];
+
+        assert_eq!(lookup(data, 1), None);
+        assert_eq!(lookup(data, 2), Some(10));
+        assert_eq!(lookup(data, 3), Some(20));
+        assert_eq!(lookup(data, 4), None);
+    }
+
+    #[test]
+    fn format6() {
+        let data = &[
+            0x00, 0x06, // format: 6
+            // Binary search header
+            0x00, 0x04, // unit size: 4
+            0x00, 0x02, // number of units: 2
+            0x00, 0x08, // search range: 8
+            0x00, 0x01, // entry selector: 1
+            0x00, 0x00, // range shift: 0
+            // Entry [0]
+            0x00, 0x01, // glyph: 1
+            0x00, 0x0A, // value: 10
+            // Entry [1]
+            0x00, 0x05, // glyph: 5
+            0x00, 0x14, // value: 20
+        ];
+
+        assert_eq!(lookup(data, 0), None);
+        assert_eq!(lookup(data, 1), Some(10));
+        assert_eq!(lookup(data, 3), None);
+        assert_eq!(lookup(data, 5), Some(20));
+    }
+
+    #[test]
+    fn format8() {
+        let data = &[
+            0x00, 0x08, // format: 8
+            0x00, 0x02, // first glyph: 2
+            0x00, 0x02, // glyph count: 2
+            0x00, 0x0A, // value [0]: 10
+            0x00, 0x14, // value [1]: 20
+        ];
+
+        assert_eq!(lookup(data, 1), None);
+        assert_eq!(lookup(data, 2), Some(10));
+        assert_eq!(lookup(data, 3), Some(20));
+        assert_eq!(lookup(data, 4), None);
+    }
+
+    #[test]
+    fn format10() {
+        let data = &[
+            0x00, 0x0A, // format: 10
+            0x00, 0x01, // unit size: 1
+            0x00, 0x02, // first glyph: 2
+            0x00, 0x02, // glyph count: 2
+            0x0A, // value [0]: 10
+            0x14, // value [1]: 20
+        ];
+
+        assert_eq!(lookup(data, 1), None);
+        assert_eq!(lookup(data, 2), Some(10));
+        assert_eq!(lookup(data, 3), Some(20));
+        assert_eq!(lookup(data, 4), None);
+    }
+
+    #[test]
+    fn unknown_format() {
+        let data = &[
+            0x00, 0x01, // format: 1
+        ];
+
+        assert_eq!(lookup(data, 0), None);
+    }
+}
@@ -0,0 +1,357 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/otff#font-collections
+
+use crate::{Face, FaceParsingError, Magic, Tag, TableRecord, TableRecords};
+use crate::parser::{Stream, NumFrom, LazyArray16, LazyArray32, Offset32, Offset};
+
+
+/// A TrueType/OpenType font collection.
+///
+/// Faces of a collection can share tables,
+/// which can be detected via `FontCollection::faces_sharing`.
+#[derive(Clone, Copy)]
+pub struct FontCollection<'a> {
+    data: &'a [u8],
+    major_version: u16,
+    minor_version: u16,
+    offsets: LazyArray32<'a, Offset32>,
+    dsig: Option<&'a [u8]>,
+}
+
+impl<'a> FontCollection<'a> {
+    /// Parses a font collection header.
+    ///
+    /// Returns `FaceParsingError::UnknownMagic` when data is not a font collection.
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, FaceParsingError> {
+        let mut s = Stream::new(data);
+        let magic: Magic = s.read().ok_or(FaceParsingError::UnknownMagic)?;
+        if magic != Magic::FontCollection {
+            return Err(FaceParsingError::UnknownMagic);
+        }
+
+        let major_version: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let minor_version: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let number_of_faces: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let offsets = s.read_array32::<Offset32>(number_of_faces)
+            .ok_or(FaceParsingError::MalformedFont)?;
+
+        // Version 2.0 header has a digital signature record.
+        let mut dsig = None;
+        if major_version >= 2 {
+            let tag: Tag = s.read().ok_or(FaceParsingError::MalformedFont)?;
+            let length: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+            let offset: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+
+            // The tag is set to 0 when there is no signature.
+            // A malformed signature is treated as a missing one.
+            if tag == Tag::from_bytes(b"DSIG") && length != 0 {
+                let start = usize::num_from(offset);
+                let end = start.checked_add(usize::num_from(length));
+                dsig = end.and_then(|end| data.get(start..end));
+            }
+        }
+
+        Ok(FontCollection {
+            data,
+            major_version,
+            minor_version,
+            offsets,
+            dsig,
+        })
+    }
+
+    /// Returns the collection header version as `(major, minor)`.
+    #[inline]
+    pub fn version(&self) -> (u16, u16) {
+        (self.major_version, self.minor_version)
+    }
+
+    /// Returns the number of faces.
+    #[inline]
+    pub fn len(&self) -> u32 {
+        self.offsets.len()
+    }
+
+    /// Checks that the collection has no faces.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.len() == 0
+    }
+
+    /// Returns the `DSIG` table data referenced by a version 2.0 header.
+    ///
+    /// Returns `None` for version 1.0 headers or when the collection is not signed.
+    #[inline]
+    pub fn digital_signature(&self) -> Option<&'a [u8]> {
+        self.dsig
+    }
+
+    /// Returns a face by index.
+    ///
+    /// Returns `None` when the index is out of bounds or the face offset table is malformed.
+    pub fn face(&self, index: u32) -> Option<CollectionFace<'a>> {
+        let offset = self.offsets.get(index)?;
+        let sfnt_version: u32 = Stream::read_at(self.data, offset.to_usize())?;
+        let mut s = Stream::new_at(self.data, offset.to_usize())?;
+
+        // A face in a font collection can't be another collection.
+        if s.read::<Magic>()? == Magic::FontCollection {
+            return None;
+        }
+
+        let number_of_tables: u16 = s.read()?;
+        s.advance(6); // searchRange (u16) + entrySelector (u16) + rangeShift (u16)
+        let tables = s.read_array16::<TableRecord>(number_of_tables)?;
+
+        Some(CollectionFace {
+            data: self.data,
+            index,
+            offset: offset.0,
+            sfnt_version,
+            tables,
+        })
+    }
+
+    /// Returns an iterator over faces.
+    ///
+    /// Malformed faces are skipped.
+    #[inline]
+    pub fn faces(&self) -> CollectionFaces<'a> {
+        CollectionFaces {
+            collection: *self,
+            index: 0,
+        }
+    }
+
+    /// Returns an iterator over indices of faces that use the specified face's table.
+    ///
+    /// Tables are considered the same when they have the same offset and length.
+    /// The specified face is included as well.
+    ///
+    /// Returns `None` when the face doesn't have such table.
+    pub fn faces_sharing(&self, face: &CollectionFace<'a>, tag: Tag) -> Option<SharingFaces<'a>> {
+        let record = face.table_record(tag)?;
+        Some(SharingFaces {
+            faces: self.faces(),
+            record,
+        })
+    }
+}
+
+impl core::fmt::Debug for FontCollection<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "FontCollection {{ ... }}")
+    }
+}
+
+
+/// A face in a font collection.
+#[derive(Clone, Copy)]
+pub struct CollectionFace<'a> {
+    data: &'a [u8],
+    index: u32,
+    offset: u32,
+    sfnt_version: u32,
+    tables: LazyArray16<'a, TableRecord>,
+}
+
+impl<'a> CollectionFace<'a> {
+    /// Returns the face index in the collection.
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the face's offset table offset from the start of the collection.
+    #[inline]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Returns the face's sfnt version.
+    ///
+    /// Usually `0x00010000` or `0x4F54544F` (`OTTO`).
+    #[inline]
+    pub fn sfnt_version(&self) -> u32 {
+        self.sfnt_version
+    }
+
+    /// Returns the number of tables in the face's table directory.
+    #[inline]
+    pub fn number_of_tables(&self) -> u16 {
+        self.tables.len()
+    }
+
+    /// Checks that the face's table directory has a specified table.
+    #[inline]
+    pub fn has_table(&self, tag: Tag) -> bool {
+        self.table_record(tag).is_some()
+    }
+
+    /// Returns an iterator over the face's table records.
+    #[inline]
+    pub fn table_records(&self) -> TableRecords<'a> {
+        TableRecords { iter: self.tables.into_iter() }
+    }
+
+    /// Returns a table record by tag.
+    #[inline]
+    pub fn table_record(&self, tag: Tag) -> Option<TableRecord> {
+        self.tables.into_iter().find(|record| record.tag == tag)
+    }
+
+    /// Parses the face.
+    ///
+    /// A shorthand for `Face::from_slice(data, index)`.
+    #[inline]
+    pub fn parse(&self) -> Result<Face<'a>, FaceParsingError> {
+        Face::from_slice(self.data, self.index)
+    }
+}
+
+impl core::fmt::Debug for CollectionFace<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "CollectionFace {{ index: {}, offset: {} }}", self.index, self.offset)
+    }
+}
+
+
+/// An iterator over collection faces.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct CollectionFaces<'a> {
+    collection: FontCollection<'a>,
+    index: u32,
+}
+
+impl<'a> Iterator for CollectionFaces<'a> {
+    type Item = CollectionFace<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.collection.len() {
+            self.index += 1;
+            if let Some(face) = self.collection.face(self.index - 1) {
+                return Some(face);
+            }
+        }
+
+        None
+    }
+}
+
+
+/// An iterator over indices of faces that share a table.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct SharingFaces<'a> {
+    faces: CollectionFaces<'a>,
+    record: TableRecord,
+}
+
+impl<'a> Iterator for SharingFaces<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.record;
+        self.faces.find(|face| {
+            face.tables.into_iter().any(|r| r.offset == record.offset && r.length == record.length)
+        }).map(|face| face.index)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    fn collection() -> std::vec::Vec<u8> {
+        convert(&[
+            FontCollectionMagic,
+            UInt16(2), // major version
+            UInt16(0), // minor version
+            UInt32(2), // number of faces
+            UInt32(32), // offset [0]
+            UInt32(76), // offset [1]
+            Raw(b"DSIG"), // DSIG tag
+            UInt32(4), // DSIG length
+            UInt32(120), // DSIG offset
+            // Face [0]
+            TrueTypeMagic,
+            UInt16(2), // number of tables
+            UInt16(32), // search range
+            UInt16(1), // entry selector
+            UInt16(0), // range shift
+            Raw(b"glyf"), // tag
+            UInt32(0), // checksum
+            UInt32(124), // offset
+            UInt32(4), // length
+            Raw(b"name"), // tag
+            UInt32(0), // checksum
+            UInt32(128), // offset
+            UInt32(4), // length
+            // Face [1]
+            OpenTypeMagic,
+            UInt16(2), // number of tables
+            UInt16(32), // search range
+            UInt16(1), // entry selector
+            UInt16(0), // range shift
+            Raw(b"glyf"), // tag
+            UInt32(0), // checksum
+            UInt32(124), // offset
+            UInt32(4), // length
+            Raw(b"name"), // tag
+            UInt32(0), // checksum
+            UInt32(132), // offset
+            UInt32(4), // length
+            // DSIG
+            UInt32(1),
+            // Tables
+            UInt32(0),
+            UInt32(0),
+            UInt32(0),
+        ])
+    }
+
+    #[test]
+    fn faces() {
+        let data = collection();
+        let collection = FontCollection::from_slice(&data).unwrap();
+        assert_eq!(collection.version(), (2, 0));
+        assert_eq!(collection.len(), 2);
+        assert_eq!(collection.digital_signature(), Some(&[0, 0, 0, 1][..]));
+
+        let face = collection.face(1).unwrap();
+        assert_eq!(face.offset(), 76);
+        assert_eq!(face.sfnt_version(), 0x4F54544F);
+        assert_eq!(face.number_of_tables(), 2);
+        assert!(face.has_table(Tag::from_bytes(b"name")));
+        assert!(!face.has_table(Tag::from_bytes(b"head")));
+        assert_eq!(face.table_records().count(), 2);
+        assert_eq!(face.table_record(Tag::from_bytes(b"name")).map(|r| r.offset), Some(132));
+        assert_eq!(collection.faces().count(), 2);
+        assert!(collection.face(2).is_none());
+    }
+
+    #[test]
+    fn shared_tables() {
+        let data = collection();
+        let collection = FontCollection::from_slice(&data).unwrap();
+
+        let face = collection.face(0).unwrap();
+
+        let mut faces = collection.faces_sharing(&face, Tag::from_bytes(b"glyf")).unwrap();
+        assert_eq!(faces.next(), Some(0));
+        assert_eq!(faces.next(), Some(1));
+        assert_eq!(faces.next(), None);
+
+        assert_eq!(collection.faces_sharing(&face, Tag::from_bytes(b"name")).unwrap().count(), 1);
+        assert!(collection.faces_sharing(&face, Tag::from_bytes(b"head")).is_none());
+    }
+
+    #[test]
+    fn not_a_collection() {
+        assert_eq!(FontCollection::from_slice(&[0x00, 0x01, 0x00, 0x00]).unwrap_err(),
+                   FaceParsingError::UnknownMagic);
+    }
+}
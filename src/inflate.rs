@@ -0,0 +1,439 @@
+// A minimal zlib (RFC 1950) and DEFLATE (RFC 1951) decoder.
+//
+// Based on the `puff` decoder from zlib.
+// Favors simplicity over speed, since it's only used to unpack WOFF tables.
+
+use std::vec::Vec;
+
+
+const MAX_BITS: usize = 15;
+const MAX_LITERAL_CODES: usize = 286;
+const MAX_DISTANCE_CODES: usize = 30;
+const FIXED_LITERAL_CODES: usize = 288;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// The maximum DEFLATE compression ratio.
+///
+/// The longest match (258 bytes) is encoded with at least 2 bits.
+pub(crate) const MAX_COMPRESSION_RATIO: usize = 1032;
+
+const CODE_LENGTHS_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+
+/// Decompresses zlib data.
+///
+/// Returns `None` on malformed data, checksum mismatch
+/// or when the output doesn't fit into `max_len` bytes.
+///
+/// `max_len` is used only as a limit, so it can be untrusted.
+pub(crate) fn decompress_zlib(data: &[u8], max_len: usize) -> Option<Vec<u8>> {
+    let cmf = *data.first()?;
+    let flg = *data.get(1)?;
+
+    // Only DEFLATE with a window up to 32KiB is allowed.
+    if cmf & 0x0F != 8 || cmf >> 4 > 7 {
+        return None;
+    }
+
+    if (u16::from(cmf) << 8 | u16::from(flg)) % 31 != 0 {
+        return None;
+    }
+
+    // Preset dictionaries are not supported.
+    if flg & 0x20 != 0 {
+        return None;
+    }
+
+    let mut reader = BitReader::new(data.get(2..)?);
+    // Do not trust `max_len` and preallocate only what the data can actually expand to.
+    let capacity = core::cmp::min(max_len, data.len().saturating_mul(MAX_COMPRESSION_RATIO));
+    let mut out = Vec::with_capacity(capacity);
+    inflate(&mut reader, &mut out, max_len)?;
+
+    let end = reader.pos;
+    let checksum = data.get(2 + end..2 + end + 4)?;
+    let checksum = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    if checksum != adler32(&out) {
+        return None;
+    }
+
+    Some(out)
+}
+
+fn inflate(reader: &mut BitReader, out: &mut Vec<u8>, max_len: usize) -> Option<()> {
+    loop {
+        let is_last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => stored_block(reader, out, max_len)?,
+            1 => {
+                let (literals, distances) = fixed_codes()?;
+                codes_block(reader, out, max_len, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(reader)?;
+                codes_block(reader, out, max_len, &literals, &distances)?;
+            }
+            _ => return None,
+        }
+
+        if is_last {
+            // Discard the remaining bits of the last byte.
+            reader.align();
+            return Some(());
+        }
+    }
+}
+
+fn stored_block(reader: &mut BitReader, out: &mut Vec<u8>, max_len: usize) -> Option<()> {
+    reader.align();
+
+    let len = reader.read_u16()?;
+    let nlen = reader.read_u16()?;
+    if len != !nlen {
+        return None;
+    }
+
+    let len = usize::from(len);
+    if out.len() + len > max_len {
+        return None;
+    }
+
+    let data = reader.data.get(reader.pos..reader.pos + len)?;
+    out.extend_from_slice(data);
+    reader.pos += len;
+    Some(())
+}
+
+fn codes_block(
+    reader: &mut BitReader,
+    out: &mut Vec<u8>,
+    max_len: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Option<()> {
+    loop {
+        let symbol = literals.decode(reader)?;
+        if symbol < 256 {
+            if out.len() == max_len {
+                return None;
+            }
+
+            out.push(symbol as u8);
+        } else if symbol == 256 {
+            return Some(());
+        } else {
+            let symbol = usize::from(symbol - 257);
+            let len = usize::from(*LENGTH_BASE.get(symbol)?)
+                + reader.bits(LENGTH_EXTRA[symbol])? as usize;
+
+            let symbol = usize::from(distances.decode(reader)?);
+            let distance = usize::from(*DISTANCE_BASE.get(symbol)?)
+                + reader.bits(DISTANCE_EXTRA[symbol])? as usize;
+
+            if distance > out.len() || out.len() + len > max_len {
+                return None;
+            }
+
+            // Copy byte by byte, since ranges can overlap.
+            let start = out.len() - distance;
+            for i in 0..len {
+                let byte = out[start + i];
+                out.push(byte);
+            }
+        }
+    }
+}
+
+fn fixed_codes() -> Option<(Huffman, Huffman)> {
+    let mut lengths = [0u8; FIXED_LITERAL_CODES];
+    for (i, len) in lengths.iter_mut().enumerate() {
+        *len = match i {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+
+    let literals = Huffman::new(&lengths)?;
+    let distances = Huffman::new(&[5; MAX_DISTANCE_CODES])?;
+    Some((literals, distances))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Option<(Huffman, Huffman)> {
+    let literals_count = reader.bits(5)? as usize + 257;
+    let distances_count = reader.bits(5)? as usize + 1;
+    let code_lengths_count = reader.bits(4)? as usize + 4;
+    if literals_count > MAX_LITERAL_CODES || distances_count > MAX_DISTANCE_CODES {
+        return None;
+    }
+
+    let mut lengths = [0u8; MAX_LITERAL_CODES + MAX_DISTANCE_CODES];
+    for i in 0..code_lengths_count {
+        lengths[CODE_LENGTHS_ORDER[i]] = reader.bits(3)? as u8;
+    }
+
+    let code_lengths = Huffman::new(&lengths[..19])?;
+
+    let total = literals_count + distances_count;
+    let mut i = 0;
+    while i < total {
+        let symbol = code_lengths.decode(reader)?;
+        if symbol < 16 {
+            lengths[i] = symbol as u8;
+            i += 1;
+            continue;
+        }
+
+        let (value, repeat) = match symbol {
+            16 => {
+                // Repeat the previous length.
+                let prev = *lengths.get(i.checked_sub(1)?)?;
+                (prev, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+
+        if i + repeat > total {
+            return None;
+        }
+
+        for len in &mut lengths[i..i + repeat] {
+            *len = value;
+        }
+
+        i += repeat;
+    }
+
+    // The end-of-block code is required.
+    if lengths[256] == 0 {
+        return None;
+    }
+
+    let literals = Huffman::new(&lengths[..literals_count])?;
+    let distances = Huffman::new(&lengths[literals_count..total])?;
+    Some((literals, distances))
+}
+
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buffer: u32,
+    count: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            pos: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, n: u8) -> Option<u32> {
+        while self.count < n {
+            let byte = *self.data.get(self.pos)?;
+            self.pos += 1;
+            self.buffer |= u32::from(byte) << self.count;
+            self.count += 8;
+        }
+
+        let value = self.buffer & ((1u32 << n) - 1);
+        self.buffer >>= n;
+        self.count -= n;
+        Some(value)
+    }
+
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let data = self.data.get(self.pos..self.pos + 2)?;
+        self.pos += 2;
+        Some(u16::from_le_bytes([data[0], data[1]]))
+    }
+}
+
+
+// A canonical Huffman code.
+struct Huffman {
+    // The number of codes of each length.
+    counts: [u16; MAX_BITS + 1],
+    // Symbols ordered by their codes.
+    symbols: [u16; FIXED_LITERAL_CODES],
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Option<Self> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for len in lengths {
+            counts[usize::from(*len)] += 1;
+        }
+
+        // Reject over-subscribed codes. Incomplete codes are allowed.
+        let mut left = 1i32;
+        for count in &counts[1..] {
+            left <<= 1;
+            left -= i32::from(*count);
+            if left < 0 {
+                return None;
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = [0u16; FIXED_LITERAL_CODES];
+        for (symbol, len) in lengths.iter().enumerate() {
+            if *len != 0 {
+                let offset = &mut offsets[usize::from(*len)];
+                symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+
+        Some(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Option<u16> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+            let count = i32::from(*count);
+            if code - first < count {
+                return self.symbols.get((index + code - first) as usize).cloned();
+            }
+
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+
+        None
+    }
+}
+
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let mut a = 1u32;
+    let mut b = 0u32;
+    // 5552 is the largest chunk size that cannot overflow `b`.
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += u32::from(*byte);
+            b += a;
+        }
+
+        a %= MOD;
+        b %= MOD;
+    }
+
+    b << 16 | a
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored() {
+        let data = &[
+            0x78, 0x01, // header
+            0x01, // final stored block
+            0x05, 0x00, // length: 5
+            0xFA, 0xFF, // inverted length
+            0x68, 0x65, 0x6C, 0x6C, 0x6F, // hello
+            0x06, 0x2C, 0x02, 0x15, // Adler-32
+        ];
+
+        assert_eq!(decompress_zlib(data, 5).unwrap(), b"hello");
+        // Output doesn't fit.
+        assert!(decompress_zlib(data, 4).is_none());
+        // The limit is not used for preallocation.
+        assert_eq!(decompress_zlib(data, usize::MAX).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn fixed() {
+        // `hello hello hello` compressed with fixed codes and back-references.
+        let data = &[
+            0x78, 0xDA, 0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x40,
+            0x90, 0x00, 0x3A, 0x2E, 0x06, 0x7D,
+        ];
+
+        assert_eq!(decompress_zlib(data, 100).unwrap(), b"hello hello hello");
+    }
+
+    #[test]
+    fn dynamic() {
+        // Squares of 0..40 separated by commas, compressed with dynamic codes.
+        let data = &[
+            0x78, 0xDA, 0x15, 0x8D, 0xC1, 0x11, 0xC0, 0x30, 0x08, 0xC3,
+            0x16, 0xF2, 0x23, 0xA6, 0x0E, 0x09, 0xFB, 0x2F, 0x56, 0xE5,
+            0xE5, 0x03, 0x84, 0xBC, 0x64, 0x45, 0x23, 0xB7, 0x6A, 0xEB,
+            0x6B, 0x65, 0xD4, 0xD1, 0xB5, 0xBC, 0x96, 0x5C, 0x64, 0xC2,
+            0x15, 0x62, 0x40, 0x60, 0x6A, 0x93, 0x77, 0xF4, 0x55, 0xE0,
+            0xF9, 0x86, 0x4B, 0xC8, 0x1B, 0xED, 0x1A, 0xED, 0xD3, 0x6A,
+            0xB8, 0x26, 0x0F, 0xF3, 0x61, 0x7F, 0xB9, 0x0F, 0xDC, 0xF4,
+            0xF3, 0xF2, 0xE8, 0x85, 0xC1, 0x46, 0xE5, 0xE7, 0x74, 0x21,
+            0xF7, 0xF7, 0x5A, 0xF2, 0xEA, 0x36, 0xBD, 0x3F, 0x0E, 0x0A,
+            0x1E, 0x05,
+        ];
+
+        let mut expected = Vec::new();
+        for i in 0..40 {
+            expected.extend_from_slice(format!("{},", i * i).as_bytes());
+        }
+
+        assert_eq!(decompress_zlib(data, expected.len()).unwrap(), expected);
+    }
+
+    #[test]
+    fn bad_checksum() {
+        let data = &[
+            0x78, 0x01, // header
+            0x01, // final stored block
+            0x05, 0x00, // length: 5
+            0xFA, 0xFF, // inverted length
+            0x68, 0x65, 0x6C, 0x6C, 0x6F, // hello
+            0x00, 0x00, 0x00, 0x00, // Adler-32
+        ];
+
+        assert!(decompress_zlib(data, 5).is_none());
+    }
+}
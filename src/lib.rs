@@ -38,6 +38,7 @@ extern crate std;
 
 use core::fmt;
 use core::num::NonZeroU16;
+use core::ops::Range;
 
 macro_rules! try_opt_or {
     ($value:expr, $ret:expr) => {
@@ -48,24 +49,58 @@ macro_rules! try_opt_or {
     };
 }
 
+mod aat;
+mod collection;
 mod ggg;
 mod parser;
+mod rejected;
 mod tables;
 mod var_store;
 
+#[cfg(feature = "std")]
+mod inflate;
+
+#[cfg(feature = "std")]
+mod owned;
+
+#[cfg(feature = "std")]
+mod validation;
+
+#[cfg(feature = "std")]
+pub mod woff;
+
+#[cfg(feature = "woff2")]
+pub mod woff2;
+
 #[cfg(feature = "std")]
 mod writer;
 
 use tables::*;
-use parser::{Stream, FromData, NumFrom, TryNumFrom, LazyArray16, Offset32, Offset};
+use parser::{Stream, FromData, NumFrom, TryNumFrom, LazyArray16, LazyArrayIter16, Offset32, Offset};
 use parser::{i16_bound, f32_bound};
 use head::IndexToLocationFormat;
+pub use collection::{FontCollection, CollectionFace, CollectionFaces, SharingFaces};
+pub use feat::{FeatureName, FeatureNames, SettingName, SettingNames};
 pub use fvar::{VariationAxes, VariationAxis};
 pub use gdef::GlyphClass;
+pub use glyf::{GlyphPoints, GlyphPointsIter, GlyphPoint, ContourEndPoints, PhantomPoints};
+pub use glyf::{GlyphComponents, GlyphComponent, ComponentFlags, Transform};
 pub use ggg::*;
+pub use head::{HeadFlags, MacStyle};
+pub use lcar::LigatureCarets;
+pub use ltag::LanguageTags;
 pub use name::*;
-pub use os2::*;
-pub use tables::{cmap, kern};
+pub use opbd::OpticalBounds;
+pub use os2::{Weight, Width, ScriptMetrics};
+#[cfg(feature = "std")]
+pub use owned::OwnedFace;
+pub use rejected::{RejectedTable, RejectedTables, TableParsingError};
+use rejected::RejectedTablesList;
+pub use trak::Tracking;
+pub use vdmx::VerticalDeviceMetrics;
+#[cfg(feature = "std")]
+pub use validation::{ValidationIssue, ValidationReport};
+pub use tables::{cmap, kern, kerx, os2};
 
 
 /// A type-safe wrapper for glyph ID.
@@ -487,13 +522,19 @@ pub enum TableName {
 }
 
 
-#[derive(Clone, Copy)]
-struct TableRecord {
-    table_tag: Tag,
-    #[allow(dead_code)]
-    check_sum: u32,
-    offset: u32,
-    length: u32,
+/// A table record from an sfnt table directory.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TableRecord {
+    /// Table's tag.
+    pub tag: Tag,
+    /// Table's checksum.
+    pub check_sum: u32,
+    /// Table's offset from the start of the font data.
+    ///
+    /// In case of a font collection, from the start of the collection.
+    pub offset: u32,
+    /// Table's length.
+    pub length: u32,
 }
 
 impl FromData for TableRecord {
@@ -503,7 +544,7 @@ impl FromData for TableRecord {
     fn parse(data: &[u8]) -> Option<Self> {
         let mut s = Stream::new(data);
         Some(TableRecord {
-            table_tag: s.read::<Tag>()?,
+            tag: s.read::<Tag>()?,
             check_sum: s.read::<u32>()?,
             offset: s.read::<u32>()?,
             length: s.read::<u32>()?,
@@ -512,6 +553,123 @@ impl FromData for TableRecord {
 }
 
 
+impl TableRecord {
+    #[inline]
+    fn range(&self) -> Option<Range<usize>> {
+        let offset = usize::num_from(self.offset);
+        let end = offset.checked_add(usize::num_from(self.length))?;
+        Some(offset..end)
+    }
+}
+
+
+/// A validated table directory location and required tables.
+///
+/// Allows to create a `Face` from an already validated data without a parsing error.
+#[derive(Clone, Debug)]
+pub(crate) struct FaceTables {
+    sfnt_version: u32,
+    records_offset: usize,
+    number_of_records: u16,
+    head: Range<usize>,
+    hhea: Range<usize>,
+    number_of_glyphs: NonZeroU16,
+}
+
+impl FaceTables {
+    pub(crate) fn parse(data: &[u8], index: u32) -> Result<Self, FaceParsingError> {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/otff#organization-of-an-opentype-font
+
+        let mut s = Stream::new(data);
+
+        // Read **font** magic.
+        let magic: Magic = s.read().ok_or(FaceParsingError::UnknownMagic)?;
+        if magic == Magic::FontCollection {
+            s.skip::<u32>(); // version
+            let number_of_faces: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+            let offsets = s.read_array32::<Offset32>(number_of_faces)
+                .ok_or(FaceParsingError::MalformedFont)?;
+
+            let face_offset = offsets.get(index).ok_or(FaceParsingError::FaceIndexOutOfBounds)?;
+            // Face offset is from the start of the font data,
+            // so we have to adjust it to the current parser offset.
+            let face_offset = face_offset.to_usize().checked_sub(s.offset())
+                .ok_or(FaceParsingError::MalformedFont)?;
+            s.advance_checked(face_offset).ok_or(FaceParsingError::MalformedFont)?;
+
+            // Read **face** magic.
+            // Each face in a font collection also starts with a magic.
+            let magic: Magic = s.read().ok_or(FaceParsingError::UnknownMagic)?;
+            // And face in a font collection can't be another collection.
+            if magic == Magic::FontCollection {
+                return Err(FaceParsingError::UnknownMagic);
+            }
+        }
+
+        // The face magic is the sfnt version.
+        let sfnt_version: u32 = Stream::read_at(data, s.offset() - Magic::SIZE)
+            .ok_or(FaceParsingError::UnknownMagic)?;
+
+        let num_tables: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        s.advance(6); // searchRange (u16) + entrySelector (u16) + rangeShift (u16)
+        let records_offset = s.offset();
+        let tables = s.read_array16::<TableRecord>(num_tables)
+            .ok_or(FaceParsingError::MalformedFont)?;
+
+        let mut head = None;
+        let mut hhea = None;
+        let mut number_of_glyphs = None;
+        for table in tables {
+            let range = table.range().ok_or(FaceParsingError::MalformedFont)?;
+            match &table.tag.to_bytes() {
+                b"head" => {
+                    data.get(range.clone()).and_then(|data| head::parse(data))
+                        .ok_or(FaceParsingError::MalformedHeadTable)?;
+                    head = Some(range);
+                }
+                b"hhea" => {
+                    data.get(range.clone()).and_then(|data| hhea::parse(data))
+                        .ok_or(FaceParsingError::MalformedHheaTable)?;
+                    hhea = Some(range);
+                }
+                b"maxp" => {
+                    let n = data.get(range).and_then(|data| maxp::parse(data))
+                        .ok_or(FaceParsingError::MalformedMaxpTable)?;
+                    number_of_glyphs = Some(n);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(FaceTables {
+            sfnt_version,
+            records_offset,
+            number_of_records: num_tables,
+            head: head.ok_or(FaceParsingError::NoHeadTable)?,
+            hhea: hhea.ok_or(FaceParsingError::NoHheaTable)?,
+            number_of_glyphs: number_of_glyphs.ok_or(FaceParsingError::NoMaxpTable)?,
+        })
+    }
+}
+
+
+/// An iterator over table records.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct TableRecords<'a> {
+    iter: LazyArrayIter16<'a, TableRecord>,
+}
+
+impl<'a> Iterator for TableRecords<'a> {
+    type Item = TableRecord;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+
 const MAX_VAR_COORDS: u8 = 32;
 
 #[derive(Clone, Default)]
@@ -548,14 +706,26 @@ pub enum FaceParsingError {
     /// The face index is larger than the number of faces in the font.
     FaceIndexOutOfBounds,
 
-    /// The `head` table is missing or malformed.
+    /// The `head` table is missing.
     NoHeadTable,
 
-    /// The `hhea` table is missing or malformed.
+    /// The `hhea` table is missing.
     NoHheaTable,
 
-    /// The `maxp` table is missing or malformed.
+    /// The `maxp` table is missing.
     NoMaxpTable,
+
+    /// The `head` table is present, but malformed.
+    MalformedHeadTable,
+
+    /// The `hhea` table is present, but malformed.
+    MalformedHheaTable,
+
+    /// The `maxp` table is present, but malformed or has an unsupported version.
+    MalformedMaxpTable,
+
+    /// Failed to decompress a compressed font data, like a WOFF table.
+    DecompressionFailed,
 }
 
 impl core::fmt::Display for FaceParsingError {
@@ -564,9 +734,13 @@ impl core::fmt::Display for FaceParsingError {
             FaceParsingError::MalformedFont => write!(f, "malformed font"),
             FaceParsingError::UnknownMagic => write!(f, "unknown magic"),
             FaceParsingError::FaceIndexOutOfBounds => write!(f, "face index is out of bounds"),
-            FaceParsingError::NoHeadTable => write!(f, "the head table is missing or malformed"),
-            FaceParsingError::NoHheaTable => write!(f, "the hhea table is missing or malformed"),
-            FaceParsingError::NoMaxpTable => write!(f, "the maxp table is missing or malformed"),
+            FaceParsingError::NoHeadTable => write!(f, "the head table is missing"),
+            FaceParsingError::NoHheaTable => write!(f, "the hhea table is missing"),
+            FaceParsingError::NoMaxpTable => write!(f, "the maxp table is missing"),
+            FaceParsingError::MalformedHeadTable => write!(f, "the head table is malformed"),
+            FaceParsingError::MalformedHheaTable => write!(f, "the hhea table is malformed"),
+            FaceParsingError::MalformedMaxpTable => write!(f, "the maxp table is malformed"),
+            FaceParsingError::DecompressionFailed => write!(f, "failed to decompress font data"),
         }
     }
 }
@@ -579,37 +753,50 @@ impl std::error::Error for FaceParsingError {}
 #[derive(Clone)]
 pub struct Face<'a> {
     font_data: &'a [u8], // The input data. Used by Face::table_data.
+    sfnt_version: u32,
     table_records: LazyArray16<'a, TableRecord>,
+    ankr: Option<ankr::Table<'a>>,
     avar: Option<avar::Table<'a>>,
     cbdt: Option<&'a [u8]>,
     cblc: Option<&'a [u8]>,
     cff1: Option<cff1::Metadata<'a>>,
     cff2: Option<cff2::Metadata<'a>>,
     cmap: Option<cmap::Subtables<'a>>,
+    cmap_subtable: Option<cmap::Subtable<'a>>,
     colr: Option<colr::Table<'a>>,
     cpal: Option<cpal::Table<'a>>,
+    feat: Option<feat::FeatureNames<'a>>,
     fvar: Option<fvar::Table<'a>>,
     gdef: Option<gdef::Table<'a>>,
     glyf: Option<&'a [u8]>,
     gvar: Option<gvar::Table<'a>>,
+    hdmx: Option<hdmx::Table<'a>>,
     head: &'a [u8],
     hhea: &'a [u8],
     hmtx: Option<hmtx::Table<'a>>,
     hvar: Option<hvar::Table<'a>>,
     kern: Option<kern::Subtables<'a>>,
+    kerx: Option<kerx::Subtables<'a>>,
+    lcar: Option<lcar::Table<'a>>,
     loca: Option<loca::Table<'a>>,
+    ltag: Option<ltag::LanguageTags<'a>>,
+    ltsh: Option<ltsh::Table<'a>>,
     mvar: Option<mvar::Table<'a>>,
     name: Option<name::Names<'a>>,
+    opbd: Option<opbd::Table<'a>>,
     os_2: Option<os2::Table<'a>>,
     post: Option<post::Table<'a>>,
     vhea: Option<&'a [u8]>,
     vmtx: Option<hmtx::Table<'a>>,
     sbix: Option<&'a [u8]>,
     svg_: Option<&'a [u8]>,
+    trak: Option<trak::Table<'a>>,
+    vdmx: Option<vdmx::Table<'a>>,
     vorg: Option<vorg::Table<'a>>,
     vvar: Option<hvar::Table<'a>>,
     number_of_glyphs: NonZeroU16,
     coordinates: VarCoords,
+    rejected: RejectedTablesList,
 }
 
 impl<'a> Face<'a> {
@@ -625,155 +812,189 @@ impl<'a> Face<'a> {
     ///
     /// If an optional table has an invalid data it will be skipped.
     pub fn from_slice(data: &'a [u8], index: u32) -> Result<Self, FaceParsingError> {
-        // https://docs.microsoft.com/en-us/typography/opentype/spec/otff#organization-of-an-opentype-font
-
-        let mut s = Stream::new(data);
-
-        // Read **font** magic.
-        let magic: Magic = s.read().ok_or(FaceParsingError::UnknownMagic)?;
-        if magic == Magic::FontCollection {
-            s.skip::<u32>(); // version
-            let number_of_faces: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
-            let offsets = s.read_array32::<Offset32>(number_of_faces)
-                .ok_or(FaceParsingError::MalformedFont)?;
-
-            let face_offset = offsets.get(index).ok_or(FaceParsingError::FaceIndexOutOfBounds)?;
-            // Face offset is from the start of the font data,
-            // so we have to adjust it to the current parser offset.
-            let face_offset = face_offset.to_usize().checked_sub(s.offset())
-                .ok_or(FaceParsingError::MalformedFont)?;
-            s.advance_checked(face_offset).ok_or(FaceParsingError::MalformedFont)?;
-
-            // Read **face** magic.
-            // Each face in a font collection also starts with a magic.
-            let magic: Magic = s.read().ok_or(FaceParsingError::UnknownMagic)?;
-            // And face in a font collection can't be another collection.
-            if magic == Magic::FontCollection {
-                return Err(FaceParsingError::UnknownMagic);
-            }
-        }
+        let tables = FaceTables::parse(data, index)?;
+        Ok(Self::from_tables(data, &tables))
+    }
 
-        let num_tables: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
-        s.advance(6); // searchRange (u16) + entrySelector (u16) + rangeShift (u16)
-        let tables = s.read_array16::<TableRecord>(num_tables)
-            .ok_or(FaceParsingError::MalformedFont)?;
+    /// Creates a new `Face` from tables resolved by `FaceTables::parse` on the same data.
+    ///
+    /// Required tables were already validated, so only optional tables are parsed.
+    pub(crate) fn from_tables(data: &'a [u8], tables: &FaceTables) -> Self {
+        let table_records = Stream::new_at(data, tables.records_offset)
+            .and_then(|mut s| s.read_array16::<TableRecord>(tables.number_of_records))
+            .unwrap_or_default();
 
         let mut face = Face {
             font_data: data,
-            table_records: tables,
+            sfnt_version: tables.sfnt_version,
+            table_records,
+            ankr: None,
             avar: None,
             cbdt: None,
             cblc: None,
             cff1: None,
             cff2: None,
             cmap: None,
+            cmap_subtable: None,
             colr: None,
             cpal: None,
+            feat: None,
             fvar: None,
             gdef: None,
             glyf: None,
             gvar: None,
-            head: &[],
-            hhea: &[],
+            hdmx: None,
+            head: data.get(tables.head.clone()).unwrap_or_default(),
+            hhea: data.get(tables.hhea.clone()).unwrap_or_default(),
             hmtx: None,
             hvar: None,
             kern: None,
+            kerx: None,
+            lcar: None,
             loca: None,
+            ltag: None,
+            ltsh: None,
             mvar: None,
             name: None,
+            opbd: None,
             os_2: None,
             post: None,
             vhea: None,
             vmtx: None,
             sbix: None,
             svg_: None,
+            trak: None,
+            vdmx: None,
             vorg: None,
             vvar: None,
-            number_of_glyphs: NonZeroU16::new(1).unwrap(), // dummy
+            number_of_glyphs: tables.number_of_glyphs,
             coordinates: VarCoords::default(),
+            rejected: RejectedTablesList::default(),
         };
 
-        let mut number_of_glyphs = None;
         let mut hmtx = None;
         let mut vmtx = None;
         let mut loca = None;
-
-        for table in tables {
-            let offset = usize::num_from(table.offset);
-            let length = usize::num_from(table.length);
-            let end = offset.checked_add(length).ok_or(FaceParsingError::MalformedFont)?;
-            let range = offset..end;
-
-            match &table.table_tag.to_bytes() {
-                b"CBDT" => face.cbdt = data.get(range),
-                b"CBLC" => face.cblc = data.get(range),
-                b"CFF " => face.cff1 = data.get(range).and_then(|data| cff1::parse_metadata(data)),
-                b"CFF2" => face.cff2 = data.get(range).and_then(|data| cff2::parse_metadata(data)),
-                b"GDEF" => face.gdef = data.get(range).and_then(|data| gdef::Table::parse(data)),
-                b"HVAR" => face.hvar = data.get(range).and_then(|data| hvar::Table::parse(data)),
-                b"MVAR" => face.mvar = data.get(range).and_then(|data| mvar::Table::parse(data)),
-                b"OS/2" => face.os_2 = data.get(range).and_then(|data| os2::Table::parse(data)),
-                b"SVG " => face.svg_ = data.get(range),
-                b"VORG" => face.vorg = data.get(range).and_then(|data| vorg::Table::parse(data)),
-                b"VVAR" => face.vvar = data.get(range).and_then(|data| hvar::Table::parse(data)),
-                b"avar" => face.avar = data.get(range).and_then(|data| avar::Table::parse(data)),
-                b"cmap" => face.cmap = data.get(range).and_then(|data| cmap::parse(data)),
-                b"fvar" => face.fvar = data.get(range).and_then(|data| fvar::Table::parse(data)),
-                b"glyf" => face.glyf = data.get(range),
-                b"gvar" => face.gvar = data.get(range).and_then(|data| gvar::Table::parse(data)),
-                b"head" => face.head = data.get(range).and_then(|data| head::parse(data)).unwrap_or_default(),
-                b"hhea" => face.hhea = data.get(range).and_then(|data| hhea::parse(data)).unwrap_or_default(),
-                b"hmtx" => hmtx = data.get(range),
-                b"kern" => face.kern = data.get(range).and_then(|data| kern::parse(data)),
-                b"loca" => loca = data.get(range),
-                b"maxp" => number_of_glyphs = data.get(range).and_then(|data| maxp::parse(data)),
-                b"name" => face.name = data.get(range).and_then(|data| name::parse(data)),
-                b"post" => face.post = data.get(range).and_then(|data| post::Table::parse(data)),
-                b"sbix" => face.sbix = data.get(range),
-                b"vhea" => face.vhea = data.get(range).and_then(|data| vhea::parse(data)),
-                b"vmtx" => vmtx = data.get(range),
-                b"COLR" => face.colr = data.get(range).and_then(|data| colr::parse(data)),
-                b"CPAL" => face.cpal = data.get(range).and_then(|data| cpal::parse(data)),
+        let mut ankr = None;
+        let mut kerx = None;
+        let mut lcar = None;
+        let mut opbd = None;
+        let mut hdmx = None;
+
+        for table in table_records {
+            // Ranges were checked by `FaceTables::parse`.
+            let range = match table.range() {
+                Some(range) => range,
+                None => continue,
+            };
+
+            let tag = table.tag;
+            let rejected = &mut face.rejected;
+            match &tag.to_bytes() {
+                b"CBDT" => face.cbdt = rejected.data(tag, data.get(range)),
+                b"CBLC" => face.cblc = rejected.data(tag, data.get(range)),
+                b"CFF " => face.cff1 = rejected.parse(tag, data.get(range), cff1::parse_metadata),
+                b"CFF2" => face.cff2 = rejected.parse(tag, data.get(range), cff2::parse_metadata),
+                b"GDEF" => face.gdef = rejected.parse(tag, data.get(range), gdef::Table::parse),
+                b"HVAR" => face.hvar = rejected.parse(tag, data.get(range), hvar::Table::parse),
+                b"MVAR" => face.mvar = rejected.parse(tag, data.get(range), mvar::Table::parse),
+                b"OS/2" => face.os_2 = rejected.parse(tag, data.get(range), os2::Table::parse),
+                b"SVG " => face.svg_ = rejected.data(tag, data.get(range)),
+                b"LTSH" => face.ltsh = rejected.parse(tag, data.get(range), ltsh::Table::parse),
+                b"VDMX" => face.vdmx = rejected.parse(tag, data.get(range), vdmx::Table::parse),
+                b"VORG" => face.vorg = rejected.parse(tag, data.get(range), vorg::Table::parse),
+                b"VVAR" => face.vvar = rejected.parse(tag, data.get(range), hvar::Table::parse),
+                b"ankr" => ankr = rejected.data(tag, data.get(range)),
+                b"avar" => face.avar = rejected.parse(tag, data.get(range), avar::Table::parse),
+                b"cmap" => face.cmap = rejected.parse(tag, data.get(range), cmap::parse),
+                b"feat" => face.feat = rejected.parse(tag, data.get(range), feat::parse),
+                b"fvar" => face.fvar = rejected.parse(tag, data.get(range), fvar::Table::parse),
+                b"glyf" => face.glyf = rejected.data(tag, data.get(range)),
+                b"gvar" => face.gvar = rejected.parse(tag, data.get(range), gvar::Table::parse),
+                b"hdmx" => hdmx = rejected.data(tag, data.get(range)),
+                b"hmtx" => hmtx = rejected.data(tag, data.get(range)),
+                b"kern" => face.kern = rejected.parse(tag, data.get(range), kern::parse),
+                b"kerx" => kerx = rejected.data(tag, data.get(range)),
+                b"lcar" => lcar = rejected.data(tag, data.get(range)),
+                b"loca" => loca = rejected.data(tag, data.get(range)),
+                b"ltag" => face.ltag = rejected.parse(tag, data.get(range), ltag::parse),
+                b"name" => face.name = rejected.parse(tag, data.get(range), name::parse),
+                b"opbd" => opbd = rejected.data(tag, data.get(range)),
+                b"post" => face.post = rejected.parse(tag, data.get(range), post::Table::parse),
+                b"sbix" => face.sbix = rejected.data(tag, data.get(range)),
+                b"trak" => face.trak = rejected.parse(tag, data.get(range), trak::Table::parse),
+                b"vhea" => face.vhea = rejected.parse(tag, data.get(range), vhea::parse),
+                b"vmtx" => vmtx = rejected.data(tag, data.get(range)),
+                b"COLR" => face.colr = rejected.parse(tag, data.get(range), colr::parse),
+                b"CPAL" => face.cpal = rejected.parse(tag, data.get(range), cpal::parse),
                 _ => {}
             }
         }
 
-        if face.head.is_empty() {
-            return Err(FaceParsingError::NoHeadTable);
-        }
-
-        if face.hhea.is_empty() {
-            return Err(FaceParsingError::NoHheaTable);
-        }
-
-        face.number_of_glyphs = match number_of_glyphs {
-            Some(n) => n,
-            None => return Err(FaceParsingError::NoMaxpTable),
-        };
+        face.cmap_subtable = face.cmap.and_then(|subtables| subtables.best());
 
         if let Some(ref fvar) = face.fvar {
             face.coordinates.len = fvar.axes().count().min(MAX_VAR_COORDS as usize) as u8;
         }
 
+        let number_of_glyphs = face.number_of_glyphs;
+        let rejected = &mut face.rejected;
+
         if let Some(data) = hmtx {
-            if let Some(number_of_h_metrics) = hhea::number_of_h_metrics(face.hhea) {
-                face.hmtx = hmtx::Table::parse(data, number_of_h_metrics, face.number_of_glyphs);
+            face.hmtx = hhea::number_of_h_metrics(face.hhea)
+                .and_then(|n| hmtx::Table::parse(data, n, number_of_glyphs));
+            if face.hmtx.is_none() {
+                rejected.push(Tag::from_bytes(b"hmtx"), TableParsingError::InconsistentCount);
             }
         }
 
-        if let (Some(vhea), Some(data)) = (face.vhea, vmtx) {
-            if let Some(number_of_v_metrics) = vhea::num_of_long_ver_metrics(vhea) {
-                face.vmtx = hmtx::Table::parse(data, number_of_v_metrics, face.number_of_glyphs);
+        if let Some(data) = vmtx {
+            face.vmtx = face.vhea.and_then(vhea::num_of_long_ver_metrics)
+                .and_then(|n| hmtx::Table::parse(data, n, number_of_glyphs));
+            if face.vmtx.is_none() {
+                rejected.push(Tag::from_bytes(b"vmtx"), TableParsingError::InconsistentCount);
             }
         }
 
         if let Some(data) = loca {
-            if let Some(format) = head::index_to_loc_format(face.head) {
-                face.loca = loca::Table::parse(data, face.number_of_glyphs, format);
+            let tag = Tag::from_bytes(b"loca");
+            match head::index_to_loc_format(face.head) {
+                Some(format) => {
+                    face.loca = loca::Table::parse(data, face.number_of_glyphs, format);
+                    if face.loca.is_none() {
+                        rejected.push(tag, TableParsingError::InconsistentCount);
+                    }
+                }
+                None => rejected.push(tag, TableParsingError::UnsupportedFormat),
             }
         }
 
-        Ok(face)
+        if let Some(data) = ankr {
+            let table = ankr::Table::parse(face.number_of_glyphs, data);
+            face.ankr = rejected.check(Tag::from_bytes(b"ankr"), table);
+        }
+
+        if let Some(data) = kerx {
+            let table = kerx::parse(data, face.number_of_glyphs, face.ankr, face.loca, face.glyf);
+            face.kerx = rejected.check(Tag::from_bytes(b"kerx"), table);
+        }
+
+        if let Some(data) = lcar {
+            let table = lcar::Table::parse(face.number_of_glyphs, data);
+            face.lcar = rejected.check(Tag::from_bytes(b"lcar"), table);
+        }
+
+        if let Some(data) = opbd {
+            let table = opbd::Table::parse(face.number_of_glyphs, data);
+            face.opbd = rejected.check(Tag::from_bytes(b"opbd"), table);
+        }
+
+        if let Some(data) = hdmx {
+            let table = hdmx::Table::parse(face.number_of_glyphs, data);
+            face.hdmx = rejected.check(Tag::from_bytes(b"hdmx"), table);
+        }
+
+        face
     }
 
     /// Checks that face has a specified table.
@@ -816,13 +1037,52 @@ impl<'a> Face<'a> {
     ///
     /// Useful if you want to parse the data manually.
     pub fn table_data(&self, tag: Tag) -> Option<&'a [u8]> {
-        let (_, table) = self.table_records.binary_search_by(|record| record.table_tag.cmp(&tag))?;
+        let (_, table) = self.table_records.binary_search_by(|record| record.tag.cmp(&tag))?;
         let offset = usize::num_from(table.offset);
         let length = usize::num_from(table.length);
         let end = offset.checked_add(length)?;
         self.font_data.get(offset..end)
     }
 
+    /// Returns the face's sfnt version.
+    ///
+    /// `0x00010000` or `0x74727565` (`true`) for TrueType outlines
+    /// and `0x4F54544F` (`OTTO`) for CFF ones.
+    #[inline]
+    pub fn sfnt_version(&self) -> u32 {
+        self.sfnt_version
+    }
+
+    /// Validates the face data.
+    ///
+    /// Unlike `Face::from_slice`, which simply skips malformed optional tables,
+    /// reports table checksums, table ranges, `loca` offsets,
+    /// number of glyphs and `cmap` glyph IDs issues.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn validate(&self) -> ValidationReport {
+        validation::validate(self)
+    }
+
+    /// Returns an iterator over tables that are present in the face, but were rejected.
+    ///
+    /// `Face::from_slice` skips malformed optional tables.
+    /// This method allows to find out which tables were skipped and why.
+    /// Tables not supported by this library are never reported.
+    #[inline]
+    pub fn rejected_tables(&self) -> RejectedTables<'_> {
+        RejectedTables::new(&self.rejected)
+    }
+
+    /// Returns an iterator over all table records.
+    ///
+    /// Unlike `Face::has_table`, includes tables that are not supported by this library.
+    #[inline]
+    pub fn table_records(&self) -> TableRecords<'a> {
+        TableRecords { iter: self.table_records.into_iter() }
+    }
+
     /// Returns an iterator over [Name Records].
     ///
     /// An iterator can be empty.
@@ -833,6 +1093,36 @@ impl<'a> Face<'a> {
         self.name.unwrap_or_default()
     }
 
+    /// Returns the best matching name.
+    ///
+    /// `preferred_languages` is a list of [BCP 47](https://tools.ietf.org/html/bcp47)
+    /// language tags, like `["de-AT", "de"]`, from the most to the least preferred.
+    /// When none of them are present, English and then any other language are used.
+    ///
+    /// Within the same language, Windows Unicode names are preferred
+    /// over Unicode platform names and then over Macintosh Roman names.
+    ///
+    /// For `NameId::Family` and `NameId::Subfamily` the typographic family and subfamily
+    /// names are returned when present.
+    ///
+    /// Returns `None` when there are no names with the requested ID.
+    #[inline]
+    pub fn name(&self, id: NameId, preferred_languages: &[&str]) -> Option<Name<'a>> {
+        name::find(self.name?, id, preferred_languages)
+    }
+
+    /// Returns the [OS/2 and Windows Metrics Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/os2).
+    ///
+    /// Provides an access to fields that are not exposed by `Face` directly,
+    /// like embedding permissions, Unicode ranges and PANOSE classification.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn os2_table(&self) -> Option<os2::Table<'a>> {
+        self.os_2
+    }
+
     /// Checks that face is marked as *Regular*.
     ///
     /// Returns `false` when OS/2 table is not present.
@@ -1051,6 +1341,57 @@ impl<'a> Face<'a> {
         head::units_per_em(self.head)
     }
 
+    /// Returns face's revision set by the font manufacturer.
+    #[inline]
+    pub fn font_revision(&self) -> f32 {
+        head::font_revision(self.head).unwrap_or(0.0)
+    }
+
+    /// Returns face's creation time as a Unix timestamp.
+    ///
+    /// Returns `None` when the time cannot be represented.
+    #[inline]
+    pub fn created_timestamp(&self) -> Option<i64> {
+        head::created(self.head)
+    }
+
+    /// Returns face's modification time as a Unix timestamp.
+    ///
+    /// Returns `None` when the time cannot be represented.
+    #[inline]
+    pub fn modified_timestamp(&self) -> Option<i64> {
+        head::modified(self.head)
+    }
+
+    /// Returns `head` table flags.
+    #[inline]
+    pub fn head_flags(&self) -> HeadFlags {
+        head::flags(self.head)
+    }
+
+    /// Returns `head` table `macStyle` bits.
+    ///
+    /// Unlike `Face::is_bold` and `Face::is_italic`, which rely on the OS/2 table,
+    /// this is the style used by macOS.
+    #[inline]
+    pub fn mac_style(&self) -> MacStyle {
+        head::mac_style(self.head)
+    }
+
+    /// Returns the smallest readable size in pixels.
+    #[inline]
+    pub fn lowest_recommended_ppem(&self) -> u16 {
+        head::lowest_rec_ppem(self.head)
+    }
+
+    /// Returns the deprecated `head.fontDirectionHint`.
+    ///
+    /// Should be set to 2.
+    #[inline]
+    pub fn font_direction_hint(&self) -> i16 {
+        head::font_direction_hint(self.head)
+    }
+
     /// Returns face's x height.
     ///
     /// This method is affected by variation axes.
@@ -1158,26 +1499,71 @@ impl<'a> Face<'a> {
         self.cmap.unwrap_or_default()
     }
 
+    /// Returns the subtable used by `Face::glyph_index`.
+    ///
+    /// Selected once during parsing. Preferred subtables, in order:
+    ///
+    /// - Windows Unicode full repertoire (3, 10) with format 12.
+    /// - Unicode full repertoire (0, 4) and other Unicode platform subtables with format 12.
+    /// - Windows Unicode BMP (3, 1).
+    /// - Other Unicode platform subtables.
+    /// - Windows Symbol (3, 0).
+    ///
+    /// Mixed Coverage (8) and Unicode Variation Sequences (14) subtables are never selected.
+    #[inline]
+    pub fn best_character_mapping_subtable(&self) -> Option<cmap::Subtable<'a>> {
+        self.cmap_subtable
+    }
+
     /// Resolves a Glyph ID for a code point.
     ///
     /// Returns `None` instead of `0` when glyph is not found.
     ///
+    /// Uses the subtable returned by `Face::best_character_mapping_subtable`.
     /// All subtable formats except Mixed Coverage (8) are supported.
     ///
+    /// For symbol fonts, code points in the U+0000..U+00FF range that are not mapped
+    /// are looked up at U+F000 + code point.
+    ///
     /// If you need a more low-level control, prefer `Face::character_mapping_subtables`.
     #[inline]
     pub fn glyph_index(&self, c: char) -> Option<GlyphId> {
-        for encoding in self.character_mapping_subtables() {
-            if !encoding.is_unicode() {
-                continue;
-            }
+        self.cmap_subtable?.glyph_index_with_symbol_fallback(u32::from(c))
+    }
 
-            if let Some(id) = encoding.glyph_index(u32::from(c)) {
-                return Some(id);
-            }
-        }
+    /// Checks that face is a symbol font.
+    ///
+    /// Symbol fonts use the Windows Symbol (3, 0) character mapping subtable.
+    #[inline]
+    pub fn is_symbol_font(&self) -> bool {
+        self.cmap_subtable.map(|subtable| subtable.is_symbol()).unwrap_or(false)
+    }
 
-        None
+    /// Creates a glyph index cache for the subtable used by `Face::glyph_index`.
+    ///
+    /// Makes lookups for BMP code points much faster,
+    /// so it's useful when mapping large amounts of text.
+    /// Since creation is relatively slow, the result should be reused.
+    ///
+    /// Returns `None` when there are no suitable subtables.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn glyph_index_cache(&self) -> Option<cmap::GlyphIndexCache<'a>> {
+        self.cmap_subtable.map(cmap::GlyphIndexCache::new)
+    }
+
+    /// Builds a reverse character map.
+    ///
+    /// Allows to find code points mapped to a glyph.
+    /// Since building is relatively slow, the result should be cached.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn reverse_character_map(&self) -> cmap::ReverseMap {
+        cmap::ReverseMap::new(self.character_mapping_subtables())
     }
 
     /// Resolves a variation of a Glyph ID from two code points.
@@ -1202,15 +1588,18 @@ impl<'a> Face<'a> {
     /// Returns glyph's horizontal advance.
     ///
     /// This method is affected by variation axes.
+    /// When `HVAR` is not present, `gvar` phantom points are used instead.
     #[inline]
     pub fn glyph_hor_advance(&self, glyph_id: GlyphId) -> Option<u16> {
         let mut advance = self.hmtx?.advance(glyph_id)? as f32;
 
         if self.is_variable() {
-            // Ignore variation offset when `hvar` is not set.
+            // We can't use `round()` in `no_std`, so this is the next best thing.
             if let Some(hvar_data) = self.hvar {
-                // We can't use `round()` in `no_std`, so this is the next best thing.
                 advance += hvar::glyph_advance_offset(hvar_data, glyph_id, self.coords())? + 0.5;
+            } else if let Some(points) = self.glyph_phantom_points(glyph_id) {
+                // Use `gvar` phantom points when `hvar` is not set.
+                advance = points.right - points.left + 0.5;
             }
         }
 
@@ -1220,15 +1609,18 @@ impl<'a> Face<'a> {
     /// Returns glyph's vertical advance.
     ///
     /// This method is affected by variation axes.
+    /// When `VVAR` is not present, `gvar` phantom points are used instead.
     #[inline]
     pub fn glyph_ver_advance(&self, glyph_id: GlyphId) -> Option<u16> {
         let mut advance = self.vmtx?.advance(glyph_id)? as f32;
 
         if self.is_variable() {
-            // Ignore variation offset when `vvar` is not set.
+            // We can't use `round()` in `no_std`, so this is the next best thing.
             if let Some(vvar_data) = self.vvar {
-                // We can't use `round()` in `no_std`, so this is the next best thing.
                 advance += hvar::glyph_advance_offset(vvar_data, glyph_id, self.coords())? + 0.5;
+            } else if let Some(points) = self.glyph_phantom_points(glyph_id) {
+                // Use `gvar` phantom points when `vvar` is not set.
+                advance = points.top - points.bottom + 0.5;
             }
         }
 
@@ -1238,15 +1630,20 @@ impl<'a> Face<'a> {
     /// Returns glyph's horizontal side bearing.
     ///
     /// This method is affected by variation axes.
+    /// When `HVAR` is not present, `gvar` phantom points are used instead.
     #[inline]
     pub fn glyph_hor_side_bearing(&self, glyph_id: GlyphId) -> Option<i16> {
         let mut bearing = self.hmtx?.side_bearing(glyph_id)? as f32;
 
         if self.is_variable() {
-            // Ignore variation offset when `hvar` is not set.
+            // We can't use `round()` in `no_std`, so this is the next best thing.
             if let Some(hvar_data) = self.hvar {
-                // We can't use `round()` in `no_std`, so this is the next best thing.
                 bearing += hvar::glyph_side_bearing_offset(hvar_data, glyph_id, self.coords())? + 0.5;
+            } else if let Some(points) = self.glyph_phantom_points(glyph_id) {
+                // Use `gvar` phantom points when `hvar` is not set.
+                // The side bearing is relative to the varied bounding box.
+                let x_min = self.glyph_bounding_box(glyph_id).map(|rect| rect.x_min).unwrap_or(0);
+                bearing = f32::from(x_min) - points.left + 0.5;
             }
         }
 
@@ -1256,15 +1653,20 @@ impl<'a> Face<'a> {
     /// Returns glyph's vertical side bearing.
     ///
     /// This method is affected by variation axes.
+    /// When `VVAR` is not present, `gvar` phantom points are used instead.
     #[inline]
     pub fn glyph_ver_side_bearing(&self, glyph_id: GlyphId) -> Option<i16> {
         let mut bearing = self.vmtx?.side_bearing(glyph_id)? as f32;
 
         if self.is_variable() {
-            // Ignore variation offset when `vvar` is not set.
+            // We can't use `round()` in `no_std`, so this is the next best thing.
             if let Some(vvar_data) = self.vvar {
-                // We can't use `round()` in `no_std`, so this is the next best thing.
                 bearing += hvar::glyph_side_bearing_offset(vvar_data, glyph_id, self.coords())? + 0.5;
+            } else if let Some(points) = self.glyph_phantom_points(glyph_id) {
+                // Use `gvar` phantom points when `vvar` is not set.
+                // The side bearing is relative to the varied bounding box.
+                let y_max = self.glyph_bounding_box(glyph_id).map(|rect| rect.y_max).unwrap_or(0);
+                bearing = points.top - f32::from(y_max) + 0.5;
             }
         }
 
@@ -1277,6 +1679,43 @@ impl<'a> Face<'a> {
         self.vorg.map(|vorg| vorg.glyph_y_origin(glyph_id))
     }
 
+    /// Returns glyph's advance in pixels for a specified size according to
+    /// [Horizontal Device Metrics Table](https://docs.microsoft.com/en-us/typography/opentype/spec/hdmx).
+    ///
+    /// Only sizes stored in the table are available.
+    ///
+    /// Returns `None` when `hdmx` table is not present or doesn't have such size.
+    #[inline]
+    pub fn glyph_device_advance(&self, glyph_id: GlyphId, pixels_per_em: u16) -> Option<u8> {
+        self.hdmx?.glyph_advance(glyph_id, pixels_per_em)
+    }
+
+    /// Returns a size in pixels starting from which glyph's advance scales linearly according to
+    /// [Linear Threshold Table](https://docs.microsoft.com/en-us/typography/opentype/spec/ltsh).
+    ///
+    /// Returns `None` when `LTSH` table is not present.
+    #[inline]
+    pub fn glyph_linearity_threshold(&self, glyph_id: GlyphId) -> Option<u8> {
+        self.ltsh?.glyph_threshold(glyph_id)
+    }
+
+    /// Returns vertical metrics for a specified size and device aspect ratio according to
+    /// [Vertical Device Metrics Table](https://docs.microsoft.com/en-us/typography/opentype/spec/vdmx).
+    ///
+    /// The aspect ratio is `x_ratio:y_ratio`, like 1:1 for square pixels.
+    ///
+    /// Returns `None` when `VDMX` table is not present or doesn't have
+    /// such size for the first matching aspect ratio.
+    #[inline]
+    pub fn vertical_device_metrics(
+        &self,
+        pixels_per_em: u16,
+        x_ratio: u8,
+        y_ratio: u8,
+    ) -> Option<VerticalDeviceMetrics> {
+        self.vdmx?.metrics(pixels_per_em, x_ratio, y_ratio)
+    }
+
     /// Returns glyph's name.
     ///
     /// Uses the `post` and `CFF` tables as sources.
@@ -1343,6 +1782,70 @@ impl<'a> Face<'a> {
         self.kern.unwrap_or_default()
     }
 
+    /// Returns a iterator over
+    /// [extended kerning](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html)
+    /// subtables.
+    ///
+    /// Anchor points are resolved via the `ankr` table
+    /// and control points via the `glyf` table.
+    pub fn extended_kerning_subtables(&self) -> kerx::Subtables<'a> {
+        self.kerx.unwrap_or_default()
+    }
+
+    /// Returns tracking for a specified point size and track.
+    ///
+    /// Uses the [Apple Tracking Table](
+    /// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6trak.html).
+    ///
+    /// `track` is usually -1 (tight), 0 (normal) or 1 (loose), but any value is allowed.
+    /// Values between tracks and point sizes are interpolated.
+    /// Point sizes outside the table range are extrapolated, while tracks are clamped.
+    ///
+    /// Returns `None` when `trak` table is not present.
+    #[inline]
+    pub fn tracking(&self, point_size: f32, track: f32) -> Option<Tracking> {
+        self.trak.map(|table| table.tracking(point_size, track))
+    }
+
+    /// Returns an iterator over
+    /// [feature names](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6feat.html).
+    ///
+    /// Names itself are stored in the `name` table and can be resolved
+    /// via `FeatureName::name` and `SettingName::name`.
+    pub fn feature_names(&self) -> FeatureNames<'a> {
+        self.feat.unwrap_or_default()
+    }
+
+    /// Returns an iterator over
+    /// [language tags](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6ltag.html).
+    pub fn language_tags(&self) -> LanguageTags<'a> {
+        self.ltag.unwrap_or_default()
+    }
+
+    /// Returns glyph's optical bounds.
+    ///
+    /// Uses the [Apple Optical Bounds Table](
+    /// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6opbd.html).
+    ///
+    /// Control points are resolved via the `glyf` table.
+    ///
+    /// Returns `None` when `opbd` table is not present or glyph has no optical bounds.
+    pub fn glyph_optical_bounds(&self, glyph_id: GlyphId) -> Option<OpticalBounds> {
+        self.opbd?.glyph_bounds(glyph_id, glyf::GlyphOutlines::new(self.loca, self.glyf))
+    }
+
+    /// Returns an iterator over glyph's ligature caret positions.
+    ///
+    /// Uses the [Apple Ligature Caret Table](
+    /// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6lcar.html).
+    ///
+    /// Control points are resolved via the `glyf` table.
+    ///
+    /// Returns `None` when `lcar` table is not present or glyph is not a ligature.
+    pub fn glyph_ligature_carets(&self, glyph_id: GlyphId) -> Option<LigatureCarets<'a>> {
+        self.lcar?.glyph_carets(glyph_id, glyf::GlyphOutlines::new(self.loca, self.glyf))
+    }
+
     /// Outlines a glyph and returns its tight bounding box.
     ///
     /// **Warning**: since `ttf-parser` is a pull parser,
@@ -1418,6 +1921,37 @@ impl<'a> Face<'a> {
         None
     }
 
+    /// Returns raw points of a simple TrueType glyph.
+    ///
+    /// Unlike `outline_glyph()`, provides points as they are stored in the `glyf` table,
+    /// along with contour end points, instructions and phantom points.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when glyph is not a simple `glyf` glyph or on error.
+    #[inline]
+    pub fn glyph_points(&self, glyph_id: GlyphId) -> Option<GlyphPoints<'a>> {
+        let phantom_points = self.default_phantom_points(glyph_id);
+        let variation = match self.gvar {
+            Some(ref gvar_table) if self.has_non_default_variation_coordinates() => {
+                Some((gvar_table, self.coords()))
+            }
+            _ => None,
+        };
+
+        glyf::glyph_points(self.loca?, self.glyf?, glyph_id, phantom_points, variation)
+    }
+
+    /// Returns an iterator over components of a composite TrueType glyph.
+    ///
+    /// Components are not resolved recursively.
+    ///
+    /// Returns `None` when glyph is not a composite `glyf` glyph.
+    #[inline]
+    pub fn glyph_components(&self, glyph_id: GlyphId) -> Option<GlyphComponents<'a>> {
+        glyf::glyph_components(self.loca?, self.glyf?, glyph_id)
+    }
+
     /// Get color layer information for a given glyph id.
     #[inline]
     pub fn colr_layers(
@@ -1596,6 +2130,53 @@ impl<'a> Face<'a> {
     fn coords(&self) -> &[NormalizedCoordinate] {
         self.coordinates.as_slice()
     }
+
+    /// Returns glyph phantom points with `gvar` deltas applied.
+    ///
+    /// Returns `None` when `gvar` table is not present
+    /// or when variation coordinates are default,
+    /// so `hmtx` and `vmtx` values are used as is.
+    fn glyph_phantom_points(&self, glyph_id: GlyphId) -> Option<glyf::PhantomPoints> {
+        if !self.has_non_default_variation_coordinates() {
+            return None;
+        }
+
+        let points = self.default_phantom_points(glyph_id);
+        gvar::phantom_points(self.loca?, self.glyf?, self.gvar.as_ref()?, self.coords(), glyph_id, points)
+    }
+
+    /// Returns glyph phantom points without variations applied.
+    ///
+    /// When `vmtx` is not present, the vertical metrics are based on `hhea`.
+    fn default_phantom_points(&self, glyph_id: GlyphId) -> glyf::PhantomPoints {
+        // Empty glyphs have a zero bounding box.
+        let bbox = match (self.loca, self.glyf) {
+            (Some(loca), Some(glyf_table)) => glyf::glyph_bbox(loca, glyf_table, glyph_id),
+            _ => None,
+        };
+        let (x_min, y_max) = bbox.map(|rect| (rect.x_min, rect.y_max)).unwrap_or((0, 0));
+
+        let hor_advance = self.hmtx.and_then(|hmtx| hmtx.advance(glyph_id)).unwrap_or(0);
+        let hor_bearing = self.hmtx.and_then(|hmtx| hmtx.side_bearing(glyph_id)).unwrap_or(0);
+        let left = f32::from(x_min) - f32::from(hor_bearing);
+
+        let (top, bottom) = match self.vmtx {
+            Some(vmtx) => {
+                let ver_advance = vmtx.advance(glyph_id).unwrap_or(0);
+                let ver_bearing = vmtx.side_bearing(glyph_id).unwrap_or(0);
+                let top = f32::from(y_max) + f32::from(ver_bearing);
+                (top, top - f32::from(ver_advance))
+            }
+            None => (f32::from(hhea::ascender(self.hhea)), f32::from(hhea::descender(self.hhea))),
+        };
+
+        glyf::PhantomPoints {
+            left,
+            right: left + f32::from(hor_advance),
+            top,
+            bottom,
+        }
+    }
 }
 
 impl fmt::Debug for Face<'_> {
@@ -1607,6 +2188,8 @@ impl fmt::Debug for Face<'_> {
 /// Returns the number of fonts stored in a TrueType font collection.
 ///
 /// Returns `None` if a provided data is not a TrueType font collection.
+///
+/// Use `FontCollection` to access collection faces and tables shared between them.
 #[inline]
 pub fn fonts_in_collection(data: &[u8]) -> Option<u32> {
     let mut s = Stream::new(data);
@@ -1622,6 +2205,9 @@ pub fn fonts_in_collection(data: &[u8]) -> Option<u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::vec::Vec;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
 
     #[test]
     fn empty_font() {
@@ -1643,6 +2229,72 @@ mod tests {
                    FaceParsingError::NoHeadTable);
     }
 
+    #[test]
+    fn malformed_head_table() {
+        let data = convert(&[
+            TrueTypeMagic,
+            UInt16(1), // number of tables
+            UInt16(16), // search range
+            UInt16(0), // entry selector
+            UInt16(0), // range shift
+            Raw(b"head"), // tag
+            UInt32(0), // checksum
+            UInt32(28), // offset
+            UInt32(4), // length
+            // head
+            UInt32(0),
+        ]);
+
+        assert_eq!(Face::from_slice(&data, 0).unwrap_err(),
+                   FaceParsingError::MalformedHeadTable);
+    }
+
+    #[test]
+    fn table_records() {
+        let data = convert(&[
+            OpenTypeMagic,
+            UInt16(4), // number of tables
+            UInt16(64), // search range
+            UInt16(2), // entry selector
+            UInt16(0), // range shift
+            Raw(b"GSUB"), // tag
+            UInt32(1), // checksum
+            UInt32(76), // offset
+            UInt32(4), // length
+            Raw(b"head"), // tag
+            UInt32(0), // checksum
+            UInt32(80), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(134), // offset
+            UInt32(36), // length
+            Raw(b"maxp"), // tag
+            UInt32(0), // checksum
+            UInt32(170), // offset
+            UInt32(6), // length
+            // GSUB
+            UInt32(0),
+            // head
+            Raw(&[0; 54]),
+            // hhea
+            Raw(&[0; 36]),
+            // maxp
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ]);
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.sfnt_version(), 0x4F54544F);
+        assert_eq!(face.table_records().count(), 4);
+        assert_eq!(face.table_records().next(), Some(TableRecord {
+            tag: Tag::from_bytes(b"GSUB"),
+            check_sum: 1,
+            offset: 76,
+            length: 4,
+        }));
+    }
+
     #[test]
     fn tables_count_overflow() {
         let data = &[
@@ -1699,4 +2351,178 @@ mod tests {
         assert_eq!(Face::from_slice(data, std::u32::MAX).unwrap_err(),
                    FaceParsingError::FaceIndexOutOfBounds);
     }
+
+    // Builds a TrueType font from a list of sorted tables.
+    fn font(tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut data = convert(&[
+            TrueTypeMagic,
+            UInt16(tables.len() as u16), // number of tables
+            UInt16(0), // search range
+            UInt16(0), // entry selector
+            UInt16(0), // range shift
+        ]);
+
+        let mut offset = data.len() + tables.len() * 16;
+        for (tag, table) in tables {
+            data.extend_from_slice(&tag[..]);
+            data.extend_from_slice(&convert(&[
+                UInt32(0), // checksum
+                UInt32(offset as u32), // offset
+                UInt32(table.len() as u32), // length
+            ]));
+            offset += table.len();
+        }
+
+        for (_, table) in tables {
+            data.extend_from_slice(table);
+        }
+
+        data
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn glyph_index_cache_zero_glyph() {
+        let mut hhea = vec![0; 36];
+        hhea[34..36].copy_from_slice(&[0, 1]); // number of h metrics
+
+        let data = font(&[
+            (b"cmap", convert(&[
+                UInt16(0), // version
+                UInt16(1), // number of tables
+                UInt16(3), // platform ID
+                UInt16(0), // encoding ID
+                UInt32(12), // offset
+                // Format 12
+                UInt16(12), // format
+                UInt16(0), // reserved
+                UInt32(52), // length
+                UInt32(0), // language
+                UInt32(3), // number of groups
+                // 'A' is mapped to 0 and 'B' to 1.
+                UInt32(0x41), // start char code
+                UInt32(0x42), // end char code
+                UInt32(0), // start glyph ID
+                UInt32(0xF041), // start char code
+                UInt32(0xF041), // end char code
+                UInt32(3), // start glyph ID
+                UInt32(0x1F600), // start char code
+                UInt32(0x1F600), // end char code
+                UInt32(0), // start glyph ID
+            ])),
+            (b"head", vec![0; 54]),
+            (b"hhea", hhea),
+            (b"maxp", convert(&[
+                UInt32(0x00005000), // version
+                UInt16(4), // number of glyphs
+            ])),
+        ]);
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.glyph_index('A'), Some(GlyphId(3)));
+        assert_eq!(face.glyph_index('B'), Some(GlyphId(1)));
+        assert_eq!(face.glyph_index('\u{1F600}'), None);
+
+        let cache = face.glyph_index_cache().unwrap();
+        for c in 0..0x20000 {
+            if let Some(c) = core::char::from_u32(c) {
+                assert_eq!(cache.glyph_index(u32::from(c)), face.glyph_index(c));
+            }
+        }
+    }
+
+    #[test]
+    fn gvar_phantom_points_metrics() {
+        // Zero index to location format means short `loca` offsets.
+        let head = vec![0; 54];
+
+        let mut hhea = vec![0; 36];
+        hhea[34..36].copy_from_slice(&[0, 1]); // number of h metrics
+
+        let data = font(&[
+            (b"fvar", convert(&[
+                UInt32(0x00010000), // version
+                UInt16(16), // axes array offset
+                UInt16(2), // reserved
+                UInt16(1), // axis count
+                UInt16(20), // axis size
+                UInt16(0), // instance count
+                UInt16(8), // instance size
+                // Axis [0]
+                Raw(b"wght"), // tag
+                Int32(100 << 16), // min value
+                Int32(400 << 16), // default value
+                Int32(900 << 16), // max value
+                UInt16(0), // flags
+                UInt16(256), // name ID
+            ])),
+            (b"glyf", convert(&[
+                Int16(1), // number of contours
+                Int16(100), // x min
+                Int16(0), // y min
+                Int16(400), // x max
+                Int16(500), // y max
+                UInt16(2), // end point [0]
+                UInt16(0), // instructions length
+                UInt8(1), UInt8(1), UInt8(1), // flags: on curve
+                Int16(100), Int16(300), Int16(-150), // x coordinates
+                Int16(0), Int16(0), Int16(500), // y coordinates
+                UInt8(0), // padding
+            ])),
+            (b"gvar", convert(&[
+                UInt32(0x00010000), // version
+                UInt16(1), // axis count
+                UInt16(0), // shared tuple count
+                UInt32(24), // shared tuples offset
+                UInt16(1), // glyph count
+                UInt16(0), // flags
+                UInt32(24), // glyph variation data array offset
+                UInt16(0), // offset [0]
+                UInt16(10), // offset [1]
+                // Glyph variation data [0]
+                UInt16(1), // tuple variation count
+                UInt16(10), // data offset
+                // Tuple variation header [0]
+                UInt16(10), // variation data size
+                UInt16(0xA000), // tuple index: embedded peak tuple, private point numbers
+                Int16(0x4000), // peak: 1.0
+                // Serialized data
+                UInt8(0), // point numbers: all points
+                UInt8(0x06), // x deltas control: 7 bytes
+                Raw(&[0, 100, 50, 0xEC, 100, 0, 0]), // 3 points, then -20 and 100 for left and right
+                UInt8(0x86), // y deltas control: 7 zeros
+            ])),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", convert(&[
+                UInt16(500), // advance [0]
+                Int16(100), // side bearing [0]
+            ])),
+            (b"loca", convert(&[
+                UInt16(0), // offset [0]
+                UInt16(15), // offset [1]
+            ])),
+            (b"maxp", convert(&[
+                UInt32(0x00005000), // version
+                UInt16(1), // number of glyphs
+            ])),
+        ]);
+
+        let mut face = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.rejected_tables().next(), None);
+        assert_eq!(face.glyph_hor_advance(GlyphId(0)), Some(500));
+        assert_eq!(face.glyph_hor_side_bearing(GlyphId(0)), Some(100));
+
+        face.set_variation(Tag::from_bytes(b"wght"), 900.0).unwrap();
+        assert_eq!(face.glyph_hor_advance(GlyphId(0)), Some(620));
+        assert_eq!(face.glyph_hor_side_bearing(GlyphId(0)), Some(120));
+
+        face.set_variation(Tag::from_bytes(b"wght"), 650.0).unwrap();
+        assert_eq!(face.glyph_hor_advance(GlyphId(0)), Some(560));
+        assert_eq!(face.glyph_hor_side_bearing(GlyphId(0)), Some(110));
+
+        face.set_variation(Tag::from_bytes(b"wght"), 400.0).unwrap();
+        assert_eq!(face.glyph_hor_advance(GlyphId(0)), Some(500));
+        assert_eq!(face.glyph_hor_side_bearing(GlyphId(0)), Some(100));
+    }
 }
@@ -0,0 +1,186 @@
+use std::sync::Arc;
+use std::vec::Vec;
+
+use crate::{Face, FaceParsingError, FaceTables, Tag, NormalizedCoordinate, VarCoords};
+
+
+/// A font face that owns its data.
+///
+/// Unlike `Face`, can be stored in long-lived caches and shared between threads.
+/// Cloning is cheap, since the font data is reference counted.
+///
+/// Variation coordinates are stored per owner, so each clone can have its own.
+///
+/// Requires the `std` feature.
+#[derive(Clone)]
+pub struct OwnedFace {
+    data: Arc<[u8]>,
+    index: u32,
+    tables: FaceTables,
+    coordinates: VarCoords,
+}
+
+impl OwnedFace {
+    /// Creates a new `OwnedFace` from a font data.
+    ///
+    /// The data is validated using `Face::from_slice`.
+    #[inline]
+    pub fn from_vec(data: Vec<u8>, index: u32) -> Result<Self, FaceParsingError> {
+        Self::from_arc(Arc::from(data), index)
+    }
+
+    /// Creates a new `OwnedFace` from a shared font data.
+    ///
+    /// The data is validated using `Face::from_slice`.
+    pub fn from_arc(data: Arc<[u8]>, index: u32) -> Result<Self, FaceParsingError> {
+        let tables = FaceTables::parse(&data, index)?;
+        let coordinates = Face::from_tables(&data, &tables).coordinates;
+        Ok(OwnedFace {
+            data,
+            index,
+            tables,
+            coordinates,
+        })
+    }
+
+    /// Returns the font data.
+    #[inline]
+    pub fn data(&self) -> &Arc<[u8]> {
+        &self.data
+    }
+
+    /// Returns the face index in the font data.
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns a `Face` that borrows the owned data.
+    ///
+    /// Variation coordinates set via `OwnedFace::set_variation` are applied.
+    ///
+    /// The table directory and required tables are resolved by the constructor,
+    /// but optional tables are parsed on each call, so it's better to reuse the returned value.
+    /// `Face` cannot be stored inside `OwnedFace`, since it borrows the data.
+    pub fn as_face(&self) -> Face<'_> {
+        let mut face = Face::from_tables(&self.data, &self.tables);
+        face.coordinates = self.coordinates.clone();
+        face
+    }
+
+    /// Sets a variation axis coordinate.
+    ///
+    /// Affects only this owner and not its clones.
+    ///
+    /// Returns `None` when face is not variable or doesn't have such axis.
+    pub fn set_variation(&mut self, axis: Tag, value: f32) -> Option<()> {
+        let mut face = self.as_face();
+        face.set_variation(axis, value)?;
+        self.coordinates = face.coordinates;
+        Some(())
+    }
+
+    /// Returns the current normalized variation coordinates.
+    #[inline]
+    pub fn variation_coordinates(&self) -> &[NormalizedCoordinate] {
+        self.coordinates.as_slice()
+    }
+}
+
+impl core::fmt::Debug for OwnedFace {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "OwnedFace {{ index: {}, len: {} }}", self.index, self.data.len())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    fn variable_face() -> Vec<u8> {
+        convert(&[
+            TrueTypeMagic,
+            UInt16(4), // number of tables
+            UInt16(64), // search range
+            UInt16(2), // entry selector
+            UInt16(0), // range shift
+            Raw(b"fvar"), // tag
+            UInt32(0), // checksum
+            UInt32(76), // offset
+            UInt32(36), // length
+            Raw(b"head"), // tag
+            UInt32(0), // checksum
+            UInt32(112), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(166), // offset
+            UInt32(36), // length
+            Raw(b"maxp"), // tag
+            UInt32(0), // checksum
+            UInt32(202), // offset
+            UInt32(6), // length
+            // fvar
+            UInt32(0x00010000), // version
+            UInt16(16), // axes array offset
+            UInt16(2), // reserved
+            UInt16(1), // axis count
+            UInt16(20), // axis size
+            UInt16(0), // instance count
+            UInt16(4), // instance size
+            Raw(b"wght"), // axis tag
+            UInt32(100 << 16), // min value
+            UInt32(400 << 16), // default value
+            UInt32(900 << 16), // max value
+            UInt16(0), // flags
+            UInt16(256), // axis name id
+            // head
+            Raw(&[0; 54]),
+            // hhea
+            Raw(&[0; 36]),
+            // maxp
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ])
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn send_sync() {
+        assert_send_sync::<OwnedFace>();
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(OwnedFace::from_vec(vec![0x00, 0x01], 0).unwrap_err(),
+                   FaceParsingError::UnknownMagic);
+    }
+
+    #[test]
+    fn as_face() {
+        let data = variable_face();
+        let owned = OwnedFace::from_vec(data.clone(), 0).unwrap();
+        let face = owned.as_face();
+        let face2 = Face::from_slice(&data, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), face2.number_of_glyphs());
+        assert_eq!(face.table_records().count(), 4);
+        assert!(face.is_variable());
+        assert!(owned.as_face().table_data(Tag::from_bytes(b"head")).is_some());
+    }
+
+    #[test]
+    fn variations() {
+        let mut face = OwnedFace::from_vec(variable_face(), 0).unwrap();
+        let face2 = face.clone();
+        assert!(Arc::ptr_eq(face.data(), face2.data()));
+
+        face.set_variation(Tag::from_bytes(b"wght"), 900.0).unwrap();
+        assert!(face.as_face().has_non_default_variation_coordinates());
+        assert!(!face2.as_face().has_non_default_variation_coordinates());
+        assert!(face.set_variation(Tag::from_bytes(b"wdth"), 100.0).is_none());
+    }
+}
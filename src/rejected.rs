@@ -0,0 +1,301 @@
+use crate::Tag;
+
+
+/// A reason why a table was rejected.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TableParsingError {
+    /// The table range is out of the font data bounds.
+    OutOfBounds,
+
+    /// The table version is not supported.
+    UnsupportedVersion,
+
+    /// The table format is not supported.
+    UnsupportedFormat,
+
+    /// The table size doesn't match a number of records from other tables,
+    /// like `maxp.numGlyphs` or `hhea.numberOfHMetrics`.
+    ///
+    /// Also set when the table that stores such number is missing.
+    InconsistentCount,
+
+    /// The table data is malformed.
+    Malformed,
+}
+
+impl core::fmt::Display for TableParsingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TableParsingError::OutOfBounds => write!(f, "table is out of bounds"),
+            TableParsingError::UnsupportedVersion => write!(f, "unsupported table version"),
+            TableParsingError::UnsupportedFormat => write!(f, "unsupported table format"),
+            TableParsingError::InconsistentCount => write!(f, "inconsistent number of records"),
+            TableParsingError::Malformed => write!(f, "malformed table"),
+        }
+    }
+}
+
+
+/// A table that is present in the face, but was rejected.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RejectedTable {
+    /// A table tag.
+    pub tag: Tag,
+    /// A rejection reason.
+    pub error: TableParsingError,
+}
+
+
+// Tables that `Face::from_slice` can reject, sorted by tag.
+const TABLES: [&[u8; 4]; 36] = [
+    b"CBDT", b"CBLC", b"CFF ", b"CFF2", b"COLR", b"CPAL", b"GDEF", b"HVAR",
+    b"LTSH", b"MVAR", b"OS/2", b"SVG ", b"VDMX", b"VORG", b"VVAR", b"ankr",
+    b"avar", b"cmap", b"feat", b"fvar", b"glyf", b"gvar", b"hdmx", b"hmtx",
+    b"kern", b"kerx", b"lcar", b"loca", b"ltag", b"name", b"opbd", b"post",
+    b"sbix", b"trak", b"vhea", b"vmtx",
+];
+
+/// A list of tables rejected by `Face::from_slice`.
+///
+/// Stores a single reason per table from `TABLES`, so `Face` stays small.
+#[derive(Clone, Copy)]
+pub(crate) struct RejectedTablesList {
+    errors: [Option<TableParsingError>; TABLES.len()],
+}
+
+impl Default for RejectedTablesList {
+    #[inline]
+    fn default() -> Self {
+        RejectedTablesList {
+            errors: [None; TABLES.len()],
+        }
+    }
+}
+
+impl RejectedTablesList {
+    /// Records a rejected table.
+    ///
+    /// Does nothing for tables that are not in `TABLES`.
+    #[inline]
+    pub fn push(&mut self, tag: Tag, error: TableParsingError) {
+        self.set(tag, Some(error));
+    }
+
+    // When a font has duplicated table records, only the last one is used,
+    // so a successfully parsed table resets the previous error.
+    #[inline]
+    fn set(&mut self, tag: Tag, error: Option<TableParsingError>) {
+        let tag = tag.to_bytes();
+        if let Some(idx) = TABLES.iter().position(|t| **t == tag) {
+            self.errors[idx] = error;
+        }
+    }
+
+    /// Returns the table data or records it as out of bounds.
+    #[inline]
+    pub fn data<'a>(&mut self, tag: Tag, data: Option<&'a [u8]>) -> Option<&'a [u8]> {
+        match data {
+            Some(_) => self.set(tag, None),
+            None => self.push(tag, TableParsingError::OutOfBounds),
+        }
+
+        data
+    }
+
+    /// Parses a table and records it on error.
+    #[inline]
+    pub fn parse<'a, T, R: ParsingResult<T>>(
+        &mut self,
+        tag: Tag,
+        data: Option<&'a [u8]>,
+        f: impl FnOnce(&'a [u8]) -> R,
+    ) -> Option<T> {
+        let result = f(self.data(tag, data)?).into_result();
+        self.check(tag, result)
+    }
+
+    /// Records a table on error.
+    #[inline]
+    pub fn check<T>(&mut self, tag: Tag, result: Result<T, TableParsingError>) -> Option<T> {
+        match result {
+            Ok(v) => {
+                self.set(tag, None);
+                Some(v)
+            }
+            Err(e) => {
+                self.push(tag, e);
+                None
+            }
+        }
+    }
+}
+
+
+/// A table parser result.
+///
+/// Not all parsers can tell why a table is invalid,
+/// so `None` is treated as a malformed table.
+pub(crate) trait ParsingResult<T> {
+    fn into_result(self) -> Result<T, TableParsingError>;
+}
+
+impl<T> ParsingResult<T> for Result<T, TableParsingError> {
+    #[inline]
+    fn into_result(self) -> Result<T, TableParsingError> {
+        self
+    }
+}
+
+impl<T> ParsingResult<T> for Option<T> {
+    #[inline]
+    fn into_result(self) -> Result<T, TableParsingError> {
+        self.ok_or(TableParsingError::Malformed)
+    }
+}
+
+
+/// An iterator over rejected tables.
+///
+/// Created by `Face::rejected_tables`.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct RejectedTables<'a> {
+    list: &'a RejectedTablesList,
+    index: usize,
+}
+
+impl<'a> RejectedTables<'a> {
+    #[inline]
+    pub(crate) fn new(list: &'a RejectedTablesList) -> Self {
+        RejectedTables {
+            list,
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for RejectedTables<'a> {
+    type Item = RejectedTable;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(error) = self.list.errors.get(self.index).cloned() {
+            let tag = TABLES[self.index];
+            self.index += 1;
+            if let Some(error) = error {
+                return Some(RejectedTable { tag: Tag::from_bytes(tag), error });
+            }
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Face;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn rejected_tables() {
+        let data = convert(&[
+            TrueTypeMagic,
+            UInt16(6), // number of tables
+            UInt16(64), // search range
+            UInt16(2), // entry selector
+            UInt16(32), // range shift
+            Raw(b"GSUB"), // tag
+            UInt32(0), // checksum
+            UInt32(108), // offset
+            UInt32(4), // length
+            Raw(b"fvar"), // tag
+            UInt32(0), // checksum
+            UInt32(108), // offset
+            UInt32(4), // length
+            Raw(b"head"), // tag
+            UInt32(0), // checksum
+            UInt32(112), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(166), // offset
+            UInt32(36), // length
+            Raw(b"hmtx"), // tag
+            UInt32(0), // checksum
+            UInt32(202), // offset
+            UInt32(2), // length
+            Raw(b"maxp"), // tag
+            UInt32(0), // checksum
+            UInt32(204), // offset
+            UInt32(6), // length
+            // fvar
+            UInt32(0x00020000), // version
+            // head
+            Raw(&[0; 54]),
+            // hhea
+            Raw(&[0; 34]),
+            UInt16(1), // number of h metrics
+            // hmtx
+            UInt16(0), // advance
+            // maxp
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ]);
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        let mut tables = face.rejected_tables();
+        assert_eq!(tables.next(), Some(RejectedTable {
+            tag: Tag::from_bytes(b"fvar"),
+            error: TableParsingError::UnsupportedVersion,
+        }));
+        assert_eq!(tables.next(), Some(RejectedTable {
+            tag: Tag::from_bytes(b"hmtx"),
+            error: TableParsingError::InconsistentCount,
+        }));
+        assert_eq!(tables.next(), None);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let data = convert(&[
+            TrueTypeMagic,
+            UInt16(4), // number of tables
+            UInt16(64), // search range
+            UInt16(2), // entry selector
+            UInt16(0), // range shift
+            Raw(b"cmap"), // tag
+            UInt32(0), // checksum
+            UInt32(1000), // offset
+            UInt32(4), // length
+            Raw(b"head"), // tag
+            UInt32(0), // checksum
+            UInt32(76), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(130), // offset
+            UInt32(36), // length
+            Raw(b"maxp"), // tag
+            UInt32(0), // checksum
+            UInt32(166), // offset
+            UInt32(6), // length
+            // head
+            Raw(&[0; 54]),
+            // hhea
+            Raw(&[0; 36]),
+            // maxp
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ]);
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        let mut tables = face.rejected_tables();
+        assert_eq!(tables.next(), Some(RejectedTable {
+            tag: Tag::from_bytes(b"cmap"),
+            error: TableParsingError::OutOfBounds,
+        }));
+        assert_eq!(tables.next(), None);
+    }
+}
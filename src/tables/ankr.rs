@@ -0,0 +1,136 @@
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6ankr.html
+
+use core::num::NonZeroU16;
+
+use crate::GlyphId;
+use crate::aat;
+use crate::parser::{Stream, FromData, Offset32, Offset};
+use crate::TableParsingError;
+
+
+/// An anchor point.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Point {
+    /// X coordinate.
+    pub x: i16,
+    /// Y coordinate.
+    pub y: i16,
+}
+
+impl FromData for Point {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(Point {
+            x: s.read::<i16>()?,
+            y: s.read::<i16>()?,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    lookup: aat::Lookup<'a>,
+    // Ignore the actual data size, since offsets are relative to the start of the data.
+    glyphs_data: &'a [u8],
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(number_of_glyphs: NonZeroU16, data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let mut s = Stream::new(data);
+
+        let version: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+        if version != 0 {
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        Self::parse_impl(number_of_glyphs, data, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(number_of_glyphs: NonZeroU16, data: &'a [u8], mut s: Stream<'a>) -> Option<Self> {
+        s.skip::<u16>(); // reserved
+        let lookup_table_offset = s.read::<Offset32>()?.to_usize();
+        let glyphs_data_offset = s.read::<Offset32>()?.to_usize();
+
+        Some(Table {
+            lookup: aat::Lookup::parse(number_of_glyphs, data.get(lookup_table_offset..)?)?,
+            glyphs_data: data.get(glyphs_data_offset..)?,
+        })
+    }
+
+    /// Returns a glyph's anchor point by index.
+    pub fn point(&self, glyph_id: GlyphId, index: u16) -> Option<Point> {
+        let offset = self.lookup.value(glyph_id)?;
+        let mut s = Stream::new_at(self.glyphs_data, usize::from(offset))?;
+        let number_of_points: u32 = s.read()?;
+        if u32::from(index) >= number_of_points {
+            return None;
+        }
+
+        s.advance(usize::from(index) * Point::SIZE);
+        s.read()
+    }
+}
+
+impl core::fmt::Debug for Table<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Table {{ ... }}")
+    }
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::vec::Vec;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    pub(crate) fn table_data() -> Vec<u8> {
+        convert(&[
+            UInt16(0), // version
+            UInt16(0), // reserved
+            UInt32(12), // lookup table offset
+            UInt32(22), // glyphs data offset
+            // Lookup table
+            UInt16(8), // format: trimmed array
+            UInt16(1), // first glyph
+            UInt16(2), // glyph count
+            UInt16(0), // offset [0]
+            UInt16(8), // offset [1]
+            // Glyph 1 data
+            UInt32(1), // number of points
+            Int16(100), Int16(200), // point [0]
+            // Glyph 2 data
+            UInt32(2), // number of points
+            Int16(0), Int16(0), // point [0]
+            Int16(30), Int16(50), // point [1]
+        ])
+    }
+
+    #[test]
+    fn point() {
+        let data = table_data();
+        let table = Table::parse(NonZeroU16::new(3).unwrap(), &data).unwrap();
+        assert_eq!(table.point(GlyphId(1), 0), Some(Point { x: 100, y: 200 }));
+        assert_eq!(table.point(GlyphId(1), 1), None);
+        assert_eq!(table.point(GlyphId(2), 0), Some(Point { x: 0, y: 0 }));
+        assert_eq!(table.point(GlyphId(2), 1), Some(Point { x: 30, y: 50 }));
+        assert_eq!(table.point(GlyphId(0), 0), None);
+        assert_eq!(table.point(GlyphId(3), 0), None);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let data = convert(&[
+            UInt16(1), // version
+            UInt16(0), // reserved
+        ]);
+
+        let table = Table::parse(NonZeroU16::new(1).unwrap(), &data);
+        assert_eq!(table.err(), Some(TableParsingError::UnsupportedVersion));
+    }
+}
@@ -5,6 +5,7 @@ use core::num::NonZeroU16;
 
 use crate::NormalizedCoordinate;
 use crate::parser::{Stream, FromData, LazyArray16};
+use crate::TableParsingError;
 
 
 #[derive(Clone, Copy)]
@@ -14,14 +15,18 @@ pub(crate) struct Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
 
-        let version: u32 = s.read()?;
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
         if version != 0x00010000 {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
         }
 
+        Self::parse_impl(s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(mut s: Stream<'a>) -> Option<Self> {
         s.skip::<u16>(); // reserved
         // TODO: check that `axisCount` is the same as in `fvar`?
         let axis_count: u16 = s.read()?;
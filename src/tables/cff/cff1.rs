@@ -8,6 +8,7 @@ use core::ops::Range;
 
 use crate::{GlyphId, OutlineBuilder, Rect, BBox};
 use crate::parser::{Stream, LazyArray16, NumFrom, TryNumFrom};
+use crate::TableParsingError;
 use super::{Builder, IsEven, CFFError, StringId, calc_subroutine_bias, conv_subroutine_index};
 use super::argstack::ArgumentsStack;
 use super::charset::{STANDARD_ENCODING, Charset, parse_charset};
@@ -113,19 +114,27 @@ pub struct CIDMetadata<'a> {
     fd_select: FDSelect<'a>,
 }
 
-pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
+pub(crate) fn parse_metadata(data: &[u8]) -> Result<Metadata, TableParsingError> {
     let mut s = Stream::new(data);
 
     // Parse Header.
-    let major: u8 = s.read()?;
+    let major: u8 = s.read().ok_or(TableParsingError::Malformed)?;
     s.skip::<u8>(); // minor
-    let header_size: u8 = s.read()?;
+    let header_size: u8 = s.read().ok_or(TableParsingError::Malformed)?;
     s.skip::<u8>(); // Absolute offset
 
     if major != 1 {
-        return None;
+        return Err(TableParsingError::UnsupportedVersion);
     }
 
+    parse_metadata_impl(data, header_size, s).ok_or(TableParsingError::Malformed)
+}
+
+fn parse_metadata_impl<'a>(
+    data: &'a [u8],
+    header_size: u8,
+    mut s: Stream<'a>,
+) -> Option<Metadata<'a>> {
     // Jump to Name INDEX. It's not necessarily right after the header.
     if header_size > 4 {
         s.advance(usize::from(header_size) - 4);
@@ -333,6 +342,13 @@ fn parse_cid_local_subrs<'a>(
     parse_index::<u16>(&mut s)
 }
 
+// 'The number of glyphs is the value of the count field in the CharStrings INDEX.'
+#[cfg(feature = "std")]
+#[inline]
+pub fn number_of_glyphs(metadata: &Metadata) -> u32 {
+    metadata.char_strings.len()
+}
+
 pub fn glyph_name<'a>(metadata: &'a Metadata, glyph_id: GlyphId) -> Option<&'a str> {
     match metadata.kind {
         FontKind::SID(_) => {
@@ -915,7 +931,7 @@ mod tests {
             UInt8(0), // absolute offset
         ]);
 
-        assert!(parse_metadata(&data).is_none());
+        assert_eq!(parse_metadata(&data).err(), Some(TableParsingError::UnsupportedVersion));
     }
 
     #[test]
@@ -1556,7 +1572,7 @@ mod tests {
             UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
         ]);
 
-        assert!(parse_metadata(&data).is_none());
+        assert!(parse_metadata(&data).is_err());
     }
 
     #[test]
@@ -1582,7 +1598,7 @@ mod tests {
             UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
         ]);
 
-        assert!(parse_metadata(&data).is_none());
+        assert!(parse_metadata(&data).is_err());
     }
 
     // TODO: return from main
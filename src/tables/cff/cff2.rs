@@ -7,6 +7,7 @@ use core::ops::Range;
 use crate::{GlyphId, OutlineBuilder, Rect, BBox, NormalizedCoordinate};
 use crate::parser::{Stream, NumFrom, TryNumFrom};
 use crate::var_store::*;
+use crate::TableParsingError;
 use super::{Builder, CFFError, calc_subroutine_bias, conv_subroutine_index};
 use super::argstack::ArgumentsStack;
 use super::charstring::CharStringParser;
@@ -82,19 +83,28 @@ pub struct Metadata<'a> {
     item_variation_store: ItemVariationStore<'a>,
 }
 
-pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
+pub(crate) fn parse_metadata(data: &[u8]) -> Result<Metadata, TableParsingError> {
     let mut s = Stream::new(data);
 
     // Parse Header.
-    let major: u8 = s.read()?;
+    let major: u8 = s.read().ok_or(TableParsingError::Malformed)?;
     s.skip::<u8>(); // minor
-    let header_size: u8 = s.read()?;
-    let top_dict_length: u16 = s.read()?;
+    let header_size: u8 = s.read().ok_or(TableParsingError::Malformed)?;
+    let top_dict_length: u16 = s.read().ok_or(TableParsingError::Malformed)?;
 
     if major != 2 {
-        return None;
+        return Err(TableParsingError::UnsupportedVersion);
     }
 
+    parse_metadata_impl(data, header_size, top_dict_length, s).ok_or(TableParsingError::Malformed)
+}
+
+fn parse_metadata_impl<'a>(
+    data: &'a [u8],
+    header_size: u8,
+    top_dict_length: u16,
+    mut s: Stream<'a>,
+) -> Option<Metadata<'a>> {
     // Jump to Top DICT. It's not necessarily right after the header.
     if header_size > 5 {
         s.advance(usize::from(header_size) - 5);
@@ -0,0 +1,87 @@
+use std::boxed::Box;
+use std::vec::Vec;
+
+use crate::GlyphId;
+use super::Subtable;
+
+type Page = Box<[u16; 256]>;
+
+
+/// A subtable with a precomputed Basic Multilingual Plane lookup.
+///
+/// BMP code points are resolved via a page table, which is much faster
+/// than a segments search in format 4 subtables.
+/// Other code points are resolved via the subtable itself.
+///
+/// For symbol subtables, Latin-1 code points are remapped to U+F000 + code point
+/// the same way as in `Face::glyph_index`.
+///
+/// Requires the `std` feature.
+#[derive(Clone)]
+pub struct GlyphIndexCache<'a> {
+    subtable: Subtable<'a>,
+    // 256 pages of 256 glyphs. Only pages with mapped code points are allocated.
+    pages: Vec<Option<Page>>,
+}
+
+impl<'a> GlyphIndexCache<'a> {
+    /// Creates a new cache from a subtable.
+    ///
+    /// Resolves all BMP code points defined by the subtable,
+    /// so this is a relatively slow operation.
+    pub fn new(subtable: Subtable<'a>) -> Self {
+        let mut pages: Vec<Option<Page>> = vec![None; 256];
+        subtable.codepoints(|c| {
+            if c > 0xFFFF {
+                return;
+            }
+
+            if let Some(id) = subtable.glyph_index(c) {
+                let page = pages[(c >> 8) as usize].get_or_insert_with(|| Box::new([0; 256]));
+                page[(c & 0xFF) as usize] = id.0;
+            }
+        });
+
+        if subtable.is_symbol() {
+            let symbols = pages[0xF0].clone();
+            if let Some(symbols) = symbols {
+                let page = pages[0].get_or_insert_with(|| Box::new([0; 256]));
+                for (id, symbol_id) in page.iter_mut().zip(symbols.iter()) {
+                    if *id == 0 {
+                        *id = *symbol_id;
+                    }
+                }
+            }
+        }
+
+        GlyphIndexCache { subtable, pages }
+    }
+
+    /// Returns the underlying subtable.
+    #[inline]
+    pub fn subtable(&self) -> Subtable<'a> {
+        self.subtable
+    }
+
+    /// Maps a character to a glyph ID.
+    ///
+    /// Returns the same result as `Face::glyph_index`.
+    #[inline]
+    pub fn glyph_index(&self, c: u32) -> Option<GlyphId> {
+        if c > 0xFFFF {
+            return self.subtable.glyph_index(c).filter(|id| id.0 != 0);
+        }
+
+        let page = self.pages[(c >> 8) as usize].as_ref()?;
+        match page[(c & 0xFF) as usize] {
+            0 => None,
+            id => Some(GlyphId(id)),
+        }
+    }
+}
+
+impl core::fmt::Debug for GlyphIndexCache<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "GlyphIndexCache {{ subtable: {:?} }}", self.subtable)
+    }
+}
@@ -4,6 +4,8 @@ use core::convert::TryFrom;
 
 use crate::parser::{Stream, FromData};
 
+const MAX_CODE_POINT: u32 = 0x10FFFF;
+
 #[derive(Clone, Copy)]
 pub struct SequentialMapGroup {
     pub start_char_code: u32,
@@ -52,10 +54,23 @@ pub fn codepoints(data: &[u8], mut f: impl FnMut(u32)) -> Option<()> {
     s.skip::<u32>(); // language
     let count: u32 = s.read()?;
     let groups = s.read_array32::<SequentialMapGroup>(count)?;
+
+    // Groups must be sorted and must not overlap, so each code point is visited only once.
+    // Code points above U+10FFFF are ignored, otherwise a single malformed group
+    // can make us iterate over the whole `u32` range.
+    let mut min_code_point = 0;
     for group in groups {
-        for code_point in group.start_char_code..=group.end_char_code {
+        let start = core::cmp::max(group.start_char_code, min_code_point);
+        let end = core::cmp::min(group.end_char_code, MAX_CODE_POINT);
+        if start > end {
+            continue;
+        }
+
+        for code_point in start..=end {
             f(code_point);
         }
+
+        min_code_point = end + 1;
     }
 
     Some(())
@@ -1,7 +1,7 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences
 
 use crate::GlyphId;
-use crate::parser::{Stream, FromData, Offset, Offset32, U24};
+use crate::parser::{Stream, FromData, LazyArray32, LazyArrayIter32, Offset, Offset32, U24};
 
 #[derive(Clone, Copy)]
 struct VariationSelectorRecord {
@@ -86,6 +86,158 @@ pub enum GlyphVariationResult {
 }
 
 
+/// An iterator over variation selector records.
+///
+/// Created by `Subtable::variation_selectors`.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct VariationSelectors<'a> {
+    data: &'a [u8],
+    records: LazyArrayIter32<'a, VariationSelectorRecord>,
+}
+
+impl Default for VariationSelectors<'_> {
+    #[inline]
+    fn default() -> Self {
+        VariationSelectors {
+            data: &[],
+            records: LazyArray32::default().into_iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for VariationSelectors<'a> {
+    type Item = VariationSelector<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.records.next()?;
+        Some(VariationSelector { data: self.data, record })
+    }
+}
+
+
+/// A variation selector record.
+#[derive(Clone, Copy)]
+pub struct VariationSelector<'a> {
+    data: &'a [u8],
+    record: VariationSelectorRecord,
+}
+
+impl<'a> VariationSelector<'a> {
+    /// Returns the variation selector code point.
+    #[inline]
+    pub fn selector(&self) -> u32 {
+        self.record.var_selector
+    }
+
+    /// Returns an iterator over code point ranges that use default glyphs
+    /// with this selector.
+    ///
+    /// Default glyphs should be resolved via `Subtable::glyph_index` or `Face::glyph_index`.
+    pub fn default_ranges(&self) -> DefaultUvsRanges<'a> {
+        let ranges = self.record.default_uvs_offset
+            .and_then(|offset| self.data.get(offset.to_usize()..))
+            .and_then(|data| {
+                let mut s = Stream::new(data);
+                let count: u32 = s.read()?;
+                s.read_array32::<UnicodeRangeRecord>(count)
+            })
+            .unwrap_or_default();
+
+        DefaultUvsRanges { ranges: ranges.into_iter() }
+    }
+
+    /// Returns an iterator over code points that are mapped to non-default glyphs
+    /// with this selector.
+    pub fn non_default_mappings(&self) -> NonDefaultUvsMappings<'a> {
+        let mappings = self.record.non_default_uvs_offset
+            .and_then(|offset| self.data.get(offset.to_usize()..))
+            .and_then(|data| {
+                let mut s = Stream::new(data);
+                let count: u32 = s.read()?;
+                s.read_array32::<UVSMappingRecord>(count)
+            })
+            .unwrap_or_default();
+
+        NonDefaultUvsMappings { mappings: mappings.into_iter() }
+    }
+}
+
+impl core::fmt::Debug for VariationSelector<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "VariationSelector({:#X})", self.selector())
+    }
+}
+
+
+/// An iterator over default UVS code point ranges.
+///
+/// Created by `VariationSelector::default_ranges`.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct DefaultUvsRanges<'a> {
+    ranges: LazyArrayIter32<'a, UnicodeRangeRecord>,
+}
+
+impl<'a> Iterator for DefaultUvsRanges<'a> {
+    type Item = core::ops::RangeInclusive<u32>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.ranges.next()?;
+        // Never overflows, since `start_unicode_value` is actually u24.
+        let end = range.start_unicode_value + u32::from(range.additional_count);
+        Some(range.start_unicode_value..=end)
+    }
+}
+
+
+/// A non-default UVS mapping.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct UvsMapping {
+    /// A base code point.
+    pub code_point: u32,
+    /// A glyph ID.
+    pub glyph_id: GlyphId,
+}
+
+
+/// An iterator over non-default UVS mappings.
+///
+/// Created by `VariationSelector::non_default_mappings`.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct NonDefaultUvsMappings<'a> {
+    mappings: LazyArrayIter32<'a, UVSMappingRecord>,
+}
+
+impl<'a> Iterator for NonDefaultUvsMappings<'a> {
+    type Item = UvsMapping;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mapping = self.mappings.next()?;
+        Some(UvsMapping {
+            code_point: mapping.unicode_value,
+            glyph_id: mapping.glyph_id,
+        })
+    }
+}
+
+
+pub fn variation_selectors(data: &[u8]) -> Option<VariationSelectors<'_>> {
+    let mut s = Stream::new(data);
+    s.skip::<u16>(); // format
+    s.skip::<u32>(); // length
+    let count: u32 = s.read()?;
+    let records = s.read_array32::<VariationSelectorRecord>(count)?;
+    Some(VariationSelectors {
+        data,
+        records: records.into_iter(),
+    })
+}
+
 pub fn parse(data: &[u8], c: u32, variation: u32) -> Option<GlyphVariationResult> {
     let mut s = Stream::new(data);
     s.skip::<u16>(); // format
@@ -118,3 +270,63 @@ pub fn parse(data: &[u8], c: u32, variation: u32) -> Option<GlyphVariationResult
 
     None
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = &[
+        0x00, 0x0E, // format: 14
+        0x00, 0x00, 0x00, 0x36, // length: 54
+        0x00, 0x00, 0x00, 0x02, // number of variation selector records: 2
+        // Variation selector record [0]
+        0x00, 0xFE, 0x00, // variation selector: U+FE00
+        0x00, 0x00, 0x00, 0x20, // default UVS offset: 32
+        0x00, 0x00, 0x00, 0x00, // non-default UVS offset: 0
+        // Variation selector record [1]
+        0x0E, 0x01, 0x00, // variation selector: U+E0100
+        0x00, 0x00, 0x00, 0x00, // default UVS offset: 0
+        0x00, 0x00, 0x00, 0x28, // non-default UVS offset: 40
+        // Default UVS
+        0x00, 0x00, 0x00, 0x01, // number of Unicode value ranges: 1
+        0x00, 0x00, 0x41, // start Unicode value: U+0041
+        0x02, // additional count: 2
+        // Non-default UVS
+        0x00, 0x00, 0x00, 0x02, // number of UVS mappings: 2
+        0x00, 0x4E, 0x00, // Unicode value: U+4E00
+        0x00, 0x05, // glyph ID: 5
+        0x00, 0x4E, 0x01, // Unicode value: U+4E01
+        0x00, 0x06, // glyph ID: 6
+    ];
+
+    #[test]
+    fn variation_selectors() {
+        let mut records = super::variation_selectors(DATA).unwrap();
+
+        let record = records.next().unwrap();
+        assert_eq!(record.selector(), 0xFE00);
+        let mut ranges = record.default_ranges();
+        assert_eq!(ranges.next(), Some(0x41..=0x43));
+        assert_eq!(ranges.next(), None);
+        assert_eq!(record.non_default_mappings().next(), None);
+
+        let record = records.next().unwrap();
+        assert_eq!(record.selector(), 0xE0100);
+        assert_eq!(record.default_ranges().next(), None);
+        let mut mappings = record.non_default_mappings();
+        assert_eq!(mappings.next(), Some(UvsMapping { code_point: 0x4E00, glyph_id: GlyphId(5) }));
+        assert_eq!(mappings.next(), Some(UvsMapping { code_point: 0x4E01, glyph_id: GlyphId(6) }));
+        assert_eq!(mappings.next(), None);
+
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn glyph_variation() {
+        assert_eq!(parse(DATA, 0x42, 0xFE00), Some(GlyphVariationResult::UseDefault));
+        assert_eq!(parse(DATA, 0x4E01, 0xE0100), Some(GlyphVariationResult::Found(GlyphId(6))));
+        assert_eq!(parse(DATA, 0x4E02, 0xE0100), None);
+        assert_eq!(parse(DATA, 0x41, 0xFE01), None);
+    }
+}
@@ -23,8 +23,17 @@ mod format10;
 mod format12;
 mod format13;
 mod format14;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
+mod reverse;
 
-pub use format14::GlyphVariationResult;
+pub use format14::{GlyphVariationResult, VariationSelectors, VariationSelector};
+pub use format14::{DefaultUvsRanges, NonDefaultUvsMappings, UvsMapping};
+#[cfg(feature = "std")]
+pub use cache::GlyphIndexCache;
+#[cfg(feature = "std")]
+pub use reverse::{ReverseMap, VariationSequence};
 
 
 /// An iterator over
@@ -38,6 +47,52 @@ pub struct Subtables<'a> {
     index: u16,
 }
 
+impl<'a> Subtables<'a> {
+    /// Returns the most suitable subtable for mapping Unicode code points.
+    pub(crate) fn best(&self) -> Option<Subtable<'a>> {
+        let mut best: Option<(u8, Subtable)> = None;
+        for subtable in *self {
+            let rank = match subtable_rank(&subtable) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            if best.as_ref().map(|(best_rank, _)| rank < *best_rank).unwrap_or(true) {
+                best = Some((rank, subtable));
+            }
+        }
+
+        best.map(|(_, subtable)| subtable)
+    }
+}
+
+// A lower rank is better.
+fn subtable_rank(subtable: &Subtable) -> Option<u8> {
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
+    const WINDOWS_SYMBOL_ENCODING_ID: u16 = 0;
+    const WINDOWS_UNICODE_BMP_ENCODING_ID: u16 = 1;
+    const WINDOWS_UNICODE_FULL_REPERTOIRE_ENCODING_ID: u16 = 10;
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#unicode-encoding-ids
+    const UNICODE_FULL_REPERTOIRE_ENCODING_ID: u16 = 4;
+
+    let is_full = match subtable.format {
+        Format::SegmentedCoverage => true,
+        // Not supported by `Subtable::glyph_index`.
+        Format::MixedCoverage | Format::UnicodeVariationSequences => return None,
+        _ => false,
+    };
+
+    match (subtable.platform_id, subtable.encoding_id) {
+        (PlatformId::Windows, WINDOWS_UNICODE_FULL_REPERTOIRE_ENCODING_ID) if is_full => Some(0),
+        (PlatformId::Unicode, UNICODE_FULL_REPERTOIRE_ENCODING_ID) if is_full => Some(1),
+        (PlatformId::Unicode, _) if is_full => Some(2),
+        (PlatformId::Windows, WINDOWS_UNICODE_BMP_ENCODING_ID) => Some(3),
+        (PlatformId::Unicode, _) => Some(4),
+        (PlatformId::Windows, WINDOWS_SYMBOL_ENCODING_ID) => Some(5),
+        _ => None,
+    }
+}
+
 impl<'a> Iterator for Subtables<'a> {
     type Item = Subtable<'a>;
 
@@ -73,6 +128,7 @@ impl<'a> Iterator for Subtables<'a> {
 
 
 /// A character encoding subtable.
+#[derive(Clone, Copy)]
 pub struct Subtable<'a> {
     platform_id: PlatformId,
     encoding_id: u16,
@@ -120,6 +176,17 @@ impl<'a> Subtable<'a> {
         }
     }
 
+    /// Checks that the current encoding is Windows Symbol.
+    ///
+    /// Symbol fonts usually map their glyphs to the U+F020..U+F0FF range.
+    #[inline]
+    pub fn is_symbol(&self) -> bool {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
+        const WINDOWS_SYMBOL_ENCODING_ID: u16 = 0;
+
+        self.platform_id == PlatformId::Windows && self.encoding_id == WINDOWS_SYMBOL_ENCODING_ID
+    }
+
     /// Maps a character to a glyph ID.
     ///
     /// This is a low-level method and unlike `Face::glyph_index` it doesn't
@@ -167,6 +234,21 @@ impl<'a> Subtable<'a> {
         glyph.map(GlyphId)
     }
 
+    /// Like `glyph_index`, but also tries U+F000 + `c` for Latin-1 code points in symbol subtables.
+    ///
+    /// The same fallback is used by FreeType and HarfBuzz.
+    ///
+    /// Glyph 0 is treated as unmapped, so the fallback is used for it too.
+    #[inline]
+    pub(crate) fn glyph_index_with_symbol_fallback(&self, c: u32) -> Option<GlyphId> {
+        let glyph_index = |c| self.glyph_index(c).filter(|id| id.0 != 0);
+        match glyph_index(c) {
+            Some(id) => Some(id),
+            None if self.is_symbol() && c <= 0xFF => glyph_index(0xF000 + c),
+            None => None,
+        }
+    }
+
     /// Resolves a variation of a glyph ID from two code points.
     ///
     /// Returns `None`:
@@ -181,6 +263,18 @@ impl<'a> Subtable<'a> {
         }
     }
 
+    /// Returns an iterator over variation selector records.
+    ///
+    /// An iterator is empty when format is not `UnicodeVariationSequences`.
+    #[inline]
+    pub fn variation_selectors(&self) -> VariationSelectors<'a> {
+        if self.format == Format::UnicodeVariationSequences {
+            format14::variation_selectors(self.subtable_data).unwrap_or_default()
+        } else {
+            VariationSelectors::default()
+        }
+    }
+
     /// Calls `f` for all codepoints contained in this subtable.
     ///
     /// This is a low-level method and it doesn't check that the current
@@ -191,6 +285,9 @@ impl<'a> Subtable<'a> {
     /// `None` because this method finds all codepoints which were _defined_ in
     /// this subtable. The subtable may still map them to glyph ID `0`.
     ///
+    /// For `SegmentedCoverage` and `ManyToOneRangeMappings` formats,
+    /// only code points up to U+10FFFF are listed and each code point is listed once.
+    ///
     /// Returns without doing anything:
     /// - when format is `MixedCoverage`, since it's not supported.
     /// - when format is `UnicodeVariationSequences`, since it's not supported.
@@ -309,3 +406,156 @@ pub(crate) fn parse(data: &[u8]) -> Option<Subtables> {
         index: 0,
     })
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    // Maps U+0041 to glyph 1.
+    const FORMAT_4: &[u8] = &[
+        0x00, 0x04, // format: 4
+        0x00, 0x20, // subtable size: 32
+        0x00, 0x00, // language ID: 0
+        0x00, 0x04, // 2 x segCount: 4
+        0x00, 0x02, // search range: 2
+        0x00, 0x00, // entry selector: 0
+        0x00, 0x02, // range shift: 2
+        // End character codes
+        0x00, 0x41, // char code [0]: 65
+        0xFF, 0xFF, // char code [1]: 65535
+        0x00, 0x00, // reserved: 0
+        // Start character codes
+        0x00, 0x41, // char code [0]: 65
+        0xFF, 0xFF, // char code [1]: 65535
+        // Deltas
+        0xFF, 0xC0, // delta [0]: -64
+        0x00, 0x01, // delta [1]: 1
+        // Offsets into Glyph index array
+        0x00, 0x00, // offset [0]: 0
+        0x00, 0x00, // offset [1]: 0
+    ];
+
+    fn best(data: &[u8]) -> Option<(PlatformId, u16)> {
+        parse(data)?.best().map(|s| (s.platform_id(), s.encoding_id()))
+    }
+
+    #[test]
+    fn best_subtable() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(3), // number of tables
+            UInt16(0), // platform ID
+            UInt16(3), // encoding ID
+            UInt32(28), // offset
+            UInt16(3), // platform ID
+            UInt16(1), // encoding ID
+            UInt32(28), // offset
+            UInt16(3), // platform ID
+            UInt16(10), // encoding ID
+            UInt32(60), // offset
+            Raw(FORMAT_4),
+            // Format 12
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(28), // length
+            UInt32(0), // language
+            UInt32(1), // number of groups
+            UInt32(0x1F600), // start char code
+            UInt32(0x1F600), // end char code
+            UInt32(2), // glyph ID
+        ]);
+        assert_eq!(best(&data), Some((PlatformId::Windows, 10)));
+
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(2), // number of tables
+            UInt16(0), // platform ID
+            UInt16(3), // encoding ID
+            UInt32(20), // offset
+            UInt16(3), // platform ID
+            UInt16(1), // encoding ID
+            UInt32(20), // offset
+            Raw(FORMAT_4),
+        ]);
+        assert_eq!(best(&data), Some((PlatformId::Windows, 1)));
+
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(2), // number of tables
+            UInt16(1), // platform ID
+            UInt16(0), // encoding ID
+            UInt32(20), // offset
+            UInt16(3), // platform ID
+            UInt16(0), // encoding ID
+            UInt32(20), // offset
+            Raw(FORMAT_4),
+        ]);
+        assert_eq!(best(&data), Some((PlatformId::Windows, 0)));
+
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(1), // number of tables
+            UInt16(1), // platform ID
+            UInt16(0), // encoding ID
+            UInt32(12), // offset
+            Raw(FORMAT_4),
+        ]);
+        assert_eq!(best(&data), None);
+    }
+
+    #[test]
+    fn symbol_fallback() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(1), // number of tables
+            UInt16(3), // platform ID
+            UInt16(0), // encoding ID
+            UInt32(12), // offset
+            // Format 12
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(28), // length
+            UInt32(0), // language
+            UInt32(1), // number of groups
+            UInt32(0xF041), // start char code
+            UInt32(0xF041), // end char code
+            UInt32(1), // glyph ID
+        ]);
+
+        let subtable = parse(&data).unwrap().best().unwrap();
+        assert!(subtable.is_symbol());
+        assert_eq!(subtable.glyph_index(0x41), None);
+        assert_eq!(subtable.glyph_index_with_symbol_fallback(0x41), Some(GlyphId(1)));
+        assert_eq!(subtable.glyph_index_with_symbol_fallback(0xF041), Some(GlyphId(1)));
+        assert_eq!(subtable.glyph_index_with_symbol_fallback(0x141), None);
+
+        #[cfg(feature = "std")]
+        assert_eq!(GlyphIndexCache::new(subtable).glyph_index(0x41), Some(GlyphId(1)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn glyph_index_cache() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(1), // number of tables
+            UInt16(3), // platform ID
+            UInt16(1), // encoding ID
+            UInt32(12), // offset
+            Raw(FORMAT_4),
+        ]);
+
+        let subtable = parse(&data).unwrap().best().unwrap();
+        let cache = GlyphIndexCache::new(subtable);
+        for c in 0..0x10000 {
+            let id = subtable.glyph_index(c).filter(|id| id.0 != 0);
+            assert_eq!(cache.glyph_index(c), id);
+        }
+
+        assert_eq!(cache.glyph_index(0x41), Some(GlyphId(1)));
+        assert_eq!(cache.glyph_index(0x1F600), None);
+    }
+}
@@ -0,0 +1,212 @@
+use std::vec::Vec;
+
+use crate::GlyphId;
+use super::{Subtables, Format};
+
+/// A Unicode variation sequence.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VariationSequence {
+    /// A base code point.
+    pub code_point: u32,
+    /// A variation selector.
+    pub selector: u32,
+}
+
+
+/// A reverse character map. Maps glyphs to code points.
+///
+/// Built from the subtable selected by `Face::best_character_mapping_subtable`
+/// and the Unicode Variation Sequences subtable.
+///
+/// Requires the `std` feature.
+#[derive(Clone, Debug)]
+pub struct ReverseMap {
+    // Sorted by glyph ID.
+    code_points: GlyphMap<u32>,
+    variations: GlyphMap<VariationSequence>,
+}
+
+impl ReverseMap {
+    /// Builds a reverse character map.
+    ///
+    /// Iterates over all code points in a subtable, so this is a relatively slow operation.
+    pub fn new(subtables: Subtables) -> Self {
+        let mut code_points = Vec::new();
+        let subtable = subtables.best();
+        if let Some(ref subtable) = subtable {
+            subtable.codepoints(|c| {
+                if let Some(id) = subtable.glyph_index(c) {
+                    code_points.push((id, c));
+                }
+            });
+        }
+
+        let mut variations = Vec::new();
+        let uvs = subtables.into_iter().find(|e| e.format() == Format::UnicodeVariationSequences);
+        if let Some(uvs) = uvs {
+            for record in uvs.variation_selectors() {
+                let selector = record.selector();
+
+                // Default sequences use a glyph from the base subtable.
+                if let Some(ref subtable) = subtable {
+                    for c in record.default_ranges().flatten() {
+                        if let Some(id) = subtable.glyph_index(c) {
+                            variations.push((id, VariationSequence { code_point: c, selector }));
+                        }
+                    }
+                }
+
+                for mapping in record.non_default_mappings() {
+                    let sequence = VariationSequence { code_point: mapping.code_point, selector };
+                    variations.push((mapping.glyph_id, sequence));
+                }
+            }
+        }
+
+        code_points.sort_by_key(|&(id, c)| (id, c));
+        variations.sort_by_key(|&(id, v)| (id, v.code_point, v.selector));
+
+        ReverseMap {
+            code_points: GlyphMap::new(code_points),
+            variations: GlyphMap::new(variations),
+        }
+    }
+
+    /// Returns sorted code points that are mapped to a glyph.
+    ///
+    /// Can be empty.
+    #[inline]
+    pub fn code_points(&self, glyph_id: GlyphId) -> &[u32] {
+        self.code_points.get(glyph_id)
+    }
+
+    /// Returns variation sequences that are mapped to a glyph.
+    ///
+    /// Can be empty.
+    #[inline]
+    pub fn variation_sequences(&self, glyph_id: GlyphId) -> &[VariationSequence] {
+        self.variations.get(glyph_id)
+    }
+}
+
+
+#[derive(Clone, Debug)]
+struct GlyphMap<T> {
+    glyphs: Vec<GlyphId>,
+    values: Vec<T>,
+}
+
+impl<T: PartialEq> GlyphMap<T> {
+    fn new(mut items: Vec<(GlyphId, T)>) -> Self {
+        items.dedup();
+        let mut glyphs = Vec::with_capacity(items.len());
+        let mut values = Vec::with_capacity(items.len());
+        for (id, value) in items {
+            glyphs.push(id);
+            values.push(value);
+        }
+
+        GlyphMap { glyphs, values }
+    }
+
+    fn get(&self, glyph_id: GlyphId) -> &[T] {
+        use core::cmp::Ordering;
+
+        // Lower bound search. Never returns `Ok`, since we never return `Equal`.
+        let start = self.glyphs.binary_search_by(|id| {
+            if *id < glyph_id { Ordering::Less } else { Ordering::Greater }
+        }).unwrap_or_else(|i| i);
+
+        let len = self.glyphs[start..].iter().take_while(|id| **id == glyph_id).count();
+        &self.values[start..start + len]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn reverse_map() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(2), // number of tables
+            UInt16(0), // platform ID
+            UInt16(5), // encoding ID
+            UInt32(20), // offset
+            UInt16(0), // platform ID
+            UInt16(6), // encoding ID
+            UInt32(58), // offset
+            // Format 14
+            UInt16(14), // format
+            UInt32(38), // length
+            UInt32(1), // number of variation selector records
+            UInt8(0), UInt16(0xFE00), // variation selector
+            UInt32(21), // default UVS offset
+            UInt32(29), // non-default UVS offset
+            // Default UVS
+            UInt32(1), // number of Unicode value ranges
+            UInt8(0), UInt16(0x41), // start Unicode value
+            UInt8(0), // additional count
+            // Non-default UVS
+            UInt32(1), // number of UVS mappings
+            UInt8(0), UInt16(0x61), // Unicode value
+            UInt16(3), // glyph ID
+            // Format 13
+            UInt16(13), // format
+            UInt16(0), // reserved
+            UInt32(40), // length
+            UInt32(0), // language
+            UInt32(2), // number of groups
+            UInt32(0x41), // start char code
+            UInt32(0x43), // end char code
+            UInt32(1), // glyph ID
+            UInt32(0x61), // start char code
+            UInt32(0x61), // end char code
+            UInt32(2), // glyph ID
+        ]);
+
+        let map = ReverseMap::new(crate::tables::cmap::parse(&data).unwrap());
+        let code_points = |id| map.code_points(GlyphId(id));
+        let variations = |id| map.variation_sequences(GlyphId(id));
+
+        assert_eq!(code_points(0), &[]);
+        assert_eq!(code_points(1), &[0x41, 0x42, 0x43]);
+        assert_eq!(code_points(2), &[0x61]);
+        assert_eq!(code_points(3), &[]);
+        assert_eq!(variations(1), &[VariationSequence { code_point: 0x41, selector: 0xFE00 }]);
+        assert_eq!(variations(2), &[]);
+        assert_eq!(variations(3), &[VariationSequence { code_point: 0x61, selector: 0xFE00 }]);
+    }
+
+    #[test]
+    fn oversized_group() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(1), // number of tables
+            UInt16(3), // platform ID
+            UInt16(10), // encoding ID
+            UInt32(12), // offset
+            // Format 12
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(40), // length
+            UInt32(0), // language
+            UInt32(2), // number of groups
+            UInt32(0), // start char code
+            UInt32(0xFFFFFFFF), // end char code
+            UInt32(0), // glyph ID
+            UInt32(0), // start char code
+            UInt32(0xFFFFFFFF), // end char code
+            UInt32(1), // glyph ID
+        ]);
+
+        // Only the first group is used and only up to U+10FFFF.
+        let map = ReverseMap::new(crate::tables::cmap::parse(&data).unwrap());
+        assert_eq!(map.code_points(GlyphId(0x41)), &[0x41]);
+        assert_eq!(map.code_points(GlyphId(0xFFFF)), &[0xFFFF]);
+    }
+}
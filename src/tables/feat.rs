@@ -0,0 +1,269 @@
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6feat.html
+
+use crate::{Name, Names};
+use crate::parser::{Stream, FromData, LazyArray16, LazyArrayIter16, Offset32, Offset};
+use crate::TableParsingError;
+
+
+#[derive(Clone, Copy)]
+struct FeatureNameRecord {
+    feature: u16,
+    setting_table_records_count: u16,
+    setting_table_offset: Offset32,
+    flags: u8,
+    default_setting_index: u8,
+    name_index: u16,
+}
+
+impl FromData for FeatureNameRecord {
+    const SIZE: usize = 12;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(FeatureNameRecord {
+            feature: s.read::<u16>()?,
+            setting_table_records_count: s.read::<u16>()?,
+            setting_table_offset: s.read::<Offset32>()?,
+            flags: s.read::<u8>()?,
+            default_setting_index: s.read::<u8>()?,
+            name_index: s.read::<u16>()?,
+        })
+    }
+}
+
+
+/// A setting name.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SettingName {
+    /// The setting.
+    pub setting: u16,
+    /// The `name` table index for the setting's name.
+    pub name_index: u16,
+}
+
+impl SettingName {
+    /// Resolves the setting's name.
+    ///
+    /// Prefers Unicode names.
+    #[inline]
+    pub fn name<'a>(&self, names: Names<'a>) -> Option<Name<'a>> {
+        find_name(names, self.name_index)
+    }
+}
+
+impl FromData for SettingName {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(SettingName {
+            setting: s.read::<u16>()?,
+            name_index: s.read::<u16>()?,
+        })
+    }
+}
+
+
+/// Feature names.
+#[derive(Clone, Copy, Debug)]
+pub struct FeatureName<'a> {
+    /// The feature's ID.
+    pub feature: u16,
+    settings: LazyArray16<'a, SettingName>,
+    /// The index of the default setting in the `setting_names`.
+    pub default_setting_index: u8,
+    /// The feature's exclusive settings.
+    ///
+    /// If set, the feature settings are mutually exclusive.
+    pub exclusive: bool,
+    /// The `name` table index for the feature's name.
+    pub name_index: u16,
+}
+
+impl<'a> FeatureName<'a> {
+    /// Returns an iterator over the feature's setting names.
+    #[inline]
+    pub fn setting_names(&self) -> SettingNames<'a> {
+        SettingNames { iter: self.settings.into_iter() }
+    }
+
+    /// Returns the default setting.
+    #[inline]
+    pub fn default_setting(&self) -> Option<SettingName> {
+        self.settings.get(u16::from(self.default_setting_index))
+    }
+
+    /// Resolves the feature's name.
+    ///
+    /// Prefers Unicode names.
+    #[inline]
+    pub fn name<'b>(&self, names: Names<'b>) -> Option<Name<'b>> {
+        find_name(names, self.name_index)
+    }
+}
+
+
+/// An iterator over setting names.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct SettingNames<'a> {
+    iter: LazyArrayIter16<'a, SettingName>,
+}
+
+impl<'a> Iterator for SettingNames<'a> {
+    type Item = SettingName;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+
+/// An iterator over feature names.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct FeatureNames<'a> {
+    data: &'a [u8],
+    records: LazyArray16<'a, FeatureNameRecord>,
+    index: u16,
+}
+
+impl<'a> FeatureNames<'a> {
+    /// Returns feature names by a feature ID.
+    pub fn find(&self, feature: u16) -> Option<FeatureName<'a>> {
+        let (_, record) = self.records.binary_search_by(|r| r.feature.cmp(&feature))?;
+        self.resolve(record)
+    }
+
+    fn resolve(&self, record: FeatureNameRecord) -> Option<FeatureName<'a>> {
+        let mut s = Stream::new_at(self.data, record.setting_table_offset.to_usize())?;
+        let settings = s.read_array16::<SettingName>(record.setting_table_records_count)?;
+
+        // 'If bit 14 is set, then the low-order byte contains the index of the default setting.
+        // Otherwise, the default setting is the first one.'
+        let default_setting_index = if record.flags & 0x40 != 0 {
+            record.default_setting_index
+        } else {
+            0
+        };
+
+        Some(FeatureName {
+            feature: record.feature,
+            settings,
+            default_setting_index,
+            exclusive: record.flags & 0x80 != 0,
+            name_index: record.name_index,
+        })
+    }
+}
+
+impl<'a> Iterator for FeatureNames<'a> {
+    type Item = FeatureName<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.records.get(self.index)?;
+        self.index += 1;
+        self.resolve(record)
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        usize::from(self.records.len())
+    }
+}
+
+
+pub(crate) fn parse(data: &[u8]) -> Result<FeatureNames<'_>, TableParsingError> {
+    let mut s = Stream::new(data);
+
+    let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
+    if version != 0x00010000 {
+        return Err(TableParsingError::UnsupportedVersion);
+    }
+
+    parse_impl(data, s).ok_or(TableParsingError::Malformed)
+}
+
+fn parse_impl<'a>(data: &'a [u8], mut s: Stream<'a>) -> Option<FeatureNames<'a>> {
+    let count: u16 = s.read()?;
+    s.advance(6); // reserved (u16) + reserved (u32)
+    let records = s.read_array16::<FeatureNameRecord>(count)?;
+
+    Some(FeatureNames {
+        data,
+        records,
+        index: 0,
+    })
+}
+
+fn find_name(names: Names, name_index: u16) -> Option<Name> {
+    let mut fallback = None;
+    for name in names.filter(|name| name.name_id() == name_index) {
+        if name.is_unicode() {
+            return Some(name);
+        }
+
+        if fallback.is_none() {
+            fallback = Some(name);
+        }
+    }
+
+    fallback
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x02, // number of features: 2
+            0x00, 0x00, // reserved
+            0x00, 0x00, 0x00, 0x00, // reserved
+            // Feature [0]
+            0x00, 0x01, // feature: 1 (ligatures)
+            0x00, 0x02, // number of settings: 2
+            0x00, 0x00, 0x00, 0x24, // setting table offset: 36
+            0x00, 0x00, // flags: 0
+            0x01, 0x00, // name index: 256
+            // Feature [1]
+            0x00, 0x03, // feature: 3 (letter case)
+            0x00, 0x02, // number of settings: 2
+            0x00, 0x00, 0x00, 0x2C, // setting table offset: 44
+            0xC0, 0x01, // flags: exclusive, default is 1
+            0x01, 0x01, // name index: 257
+            // Setting names [0]
+            0x00, 0x00, // setting: 0
+            0x01, 0x02, // name index: 258
+            0x00, 0x02, // setting: 2
+            0x01, 0x03, // name index: 259
+            // Setting names [1]
+            0x00, 0x00, // setting: 0
+            0x01, 0x04, // name index: 260
+            0x00, 0x01, // setting: 1
+            0x01, 0x05, // name index: 261
+        ];
+
+        let names = parse(data).unwrap();
+        assert_eq!(names.count(), 2);
+
+        let feature = names.find(1).unwrap();
+        assert!(!feature.exclusive);
+        assert_eq!(feature.name_index, 256);
+        assert_eq!(feature.default_setting(), Some(SettingName { setting: 0, name_index: 258 }));
+
+        let feature = names.find(3).unwrap();
+        assert!(feature.exclusive);
+        assert_eq!(feature.setting_names().count(), 2);
+        assert_eq!(feature.default_setting(), Some(SettingName { setting: 1, name_index: 261 }));
+
+        assert!(names.find(2).is_none());
+    }
+}
@@ -4,6 +4,7 @@ use core::num::NonZeroU16;
 
 use crate::{Tag, NormalizedCoordinate};
 use crate::parser::{Stream, FromData, Fixed, Offset16, Offset, LazyArray16, LazyArrayIter16, f32_bound};
+use crate::TableParsingError;
 
 
 /// A [variation axis](https://docs.microsoft.com/en-us/typography/opentype/spec/fvar#variationaxisrecord).
@@ -46,13 +47,17 @@ pub(crate) struct Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
-        let version: u32 = s.read()?;
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
         if version != 0x00010000 {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
         }
 
+        Self::parse_impl(data, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], mut s: Stream<'a>) -> Option<Self> {
         let axes_array_offset: Offset16 = s.read()?;
         s.skip::<u16>(); // reserved
         let axis_count: u16 = s.read()?;
@@ -3,6 +3,7 @@
 use crate::GlyphId;
 use crate::parser::{Stream, Offset, Offset16, Offset32, LazyArray16};
 use crate::ggg::{Class, ClassDefinitionTable, CoverageTable};
+use crate::TableParsingError;
 
 
 /// A [glyph class](https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#glyph-class-definition-table).
@@ -24,13 +25,17 @@ pub struct Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
-        let version: u32 = s.read()?;
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
         if !(version == 0x00010000 || version == 0x00010002 || version == 0x00010003) {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
         }
 
+        Self::parse_impl(data, version, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], version: u32, mut s: Stream<'a>) -> Option<Self> {
         let glyph_class_def_offset: Option<Offset16> = s.read()?;
         s.skip::<Offset16>(); // attachListOffset
         s.skip::<Offset16>(); // ligCaretListOffset
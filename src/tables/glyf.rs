@@ -1,9 +1,10 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/glyf
 
+use core::cell::Cell;
 use core::num::NonZeroU16;
 
-use crate::parser::{Stream, F2DOT14, LazyArray16, NumFrom};
-use crate::{loca, GlyphId, OutlineBuilder, Rect, BBox};
+use crate::parser::{Stream, F2DOT14, LazyArray16, LazyArrayIter16, NumFrom, TryNumFrom};
+use crate::{loca, gvar, GlyphId, OutlineBuilder, Rect, BBox, NormalizedCoordinate};
 
 pub(crate) struct Builder<'a> {
     pub builder: &'a mut dyn OutlineBuilder,
@@ -145,18 +146,35 @@ impl<'a> Builder<'a> {
 }
 
 
-#[derive(Clone, Copy)]
+/// An affine transform.
+///
+/// Uses the same layout as an SVG matrix: `x' = a*x + c*y + e` and `y' = b*x + d*y + f`.
+#[derive(Clone, Copy, PartialEq)]
 pub struct Transform {
-    pub a: f32, pub b: f32, pub c: f32,
-    pub d: f32, pub e: f32, pub f: f32,
+    /// Horizontal scale.
+    pub a: f32,
+    /// Vertical skew.
+    pub b: f32,
+    /// Horizontal skew.
+    pub c: f32,
+    /// Vertical scale.
+    pub d: f32,
+    /// Horizontal offset.
+    pub e: f32,
+    /// Vertical offset.
+    pub f: f32,
 }
 
 impl Transform {
+    /// Creates a new translate transform.
     #[inline]
     pub fn new_translate(tx: f32, ty: f32) -> Self {
         Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: tx, f: ty }
     }
 
+    /// Combines two transforms.
+    ///
+    /// `ts2` is applied first.
     #[inline]
     pub fn combine(ts1: Self, ts2: Self) -> Self {
         Transform {
@@ -204,35 +222,51 @@ impl core::fmt::Debug for Transform {
 }
 
 
-#[derive(Clone, Copy, Debug)]
-pub(crate) struct CompositeGlyphInfo {
+/// A composite glyph component.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphComponent {
+    /// Component's glyph ID.
     pub glyph_id: GlyphId,
+    /// Component's transform.
+    ///
+    /// The offset is always zero when the component is positioned via `anchor_points`.
     pub transform: Transform,
-    pub flags: CompositeGlyphFlags,
+    /// Point numbers used to position the component when `args_are_xy_values` flag is not set.
+    ///
+    /// The first one is a point in the parent glyph and the second one
+    /// is a point in the component.
+    pub anchor_points: Option<(u16, u16)>,
+    /// Component flags.
+    pub flags: ComponentFlags,
 }
 
 
-#[derive(Clone)]
-pub(crate) struct CompositeGlyphIter<'a> {
+/// An iterator over composite glyph components.
+///
+/// Created by `Face::glyph_components`.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphComponents<'a> {
     stream: Stream<'a>,
 }
 
-impl<'a> CompositeGlyphIter<'a> {
+impl<'a> GlyphComponents<'a> {
     #[inline]
-    pub fn new(data: &'a [u8]) -> Self {
-        CompositeGlyphIter { stream: Stream::new(data) }
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        GlyphComponents { stream: Stream::new(data) }
     }
 }
 
-impl<'a> Iterator for CompositeGlyphIter<'a> {
-    type Item = CompositeGlyphInfo;
+impl<'a> Iterator for GlyphComponents<'a> {
+    type Item = GlyphComponent;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let flags = CompositeGlyphFlags(self.stream.read::<u16>()?);
+        let flags = ComponentFlags(self.stream.read::<u16>()?);
         let glyph_id: GlyphId = self.stream.read()?;
 
         let mut ts = Transform::default();
+        let mut anchor_points = None;
 
         if flags.args_are_xy_values() {
             if flags.arg_1_and_2_are_words() {
@@ -242,6 +276,15 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
                 ts.e = f32::from(self.stream.read::<i8>()?);
                 ts.f = f32::from(self.stream.read::<i8>()?);
             }
+        } else {
+            // Point numbers are unsigned.
+            if flags.arg_1_and_2_are_words() {
+                anchor_points = Some((self.stream.read::<u16>()?, self.stream.read::<u16>()?));
+            } else {
+                let parent_point = u16::from(self.stream.read::<u8>()?);
+                let component_point = u16::from(self.stream.read::<u8>()?);
+                anchor_points = Some((parent_point, component_point));
+            }
         }
 
         if flags.we_have_a_two_by_two() {
@@ -262,21 +305,148 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
             self.stream.jump_to_end();
         }
 
-        Some(CompositeGlyphInfo {
+        Some(GlyphComponent {
             glyph_id,
             transform: ts,
+            anchor_points,
             flags,
         })
     }
 }
 
 
+/// A TrueType glyph point.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphPoint {
+    /// X coordinate.
+    pub x: f32,
+    /// Y coordinate.
+    pub y: f32,
+    /// Indicates that a point is a point on curve
+    /// and not a control point.
+    pub on_curve: bool,
+}
+
+
+/// TrueType glyph phantom points.
+///
+/// Phantom points are not a part of the outline, but they define glyph metrics
+/// and can be moved by `gvar` deltas and hinting instructions.
+/// Only the meaningful coordinate of each point is stored.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct PhantomPoints {
+    /// Horizontal origin. `xMin - left side bearing` by default.
+    pub left: f32,
+    /// Horizontal advance point. `left + advance width` by default.
+    pub right: f32,
+    /// Vertical origin. `yMax + top side bearing` by default.
+    pub top: f32,
+    /// Vertical advance point. `top - advance height` by default.
+    pub bottom: f32,
+}
+
+
+/// Raw data of a simple TrueType glyph.
+///
+/// Created by `Face::glyph_points`.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphPoints<'a> {
+    glyph: SimpleGlyph<'a>,
+    deltas: Option<gvar::VariationTuples<'a>>,
+    phantom_points: PhantomPoints,
+}
+
+impl<'a> GlyphPoints<'a> {
+    /// Returns the number of glyph points.
+    ///
+    /// Phantom points are not included.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.glyph.points.points_left
+    }
+
+    /// Checks that glyph has no points.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over contour end point indices.
+    #[inline]
+    pub fn contour_end_points(&self) -> ContourEndPoints<'a> {
+        ContourEndPoints(self.glyph.endpoints.into_iter())
+    }
+
+    /// Returns glyph instructions byte code.
+    #[inline]
+    pub fn instructions(&self) -> &'a [u8] {
+        self.glyph.instructions
+    }
+
+    /// Returns an iterator over glyph points.
+    #[inline]
+    pub fn points(&self) -> GlyphPointsIter<'a> {
+        GlyphPointsIter {
+            all_points: self.glyph.points.clone(),
+            points: self.glyph.points.clone(),
+            deltas: self.deltas.clone(),
+        }
+    }
+
+    /// Returns glyph phantom points.
+    #[inline]
+    pub fn phantom_points(&self) -> PhantomPoints {
+        self.phantom_points
+    }
+}
+
+
+/// An iterator over glyph contour end point indices.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct ContourEndPoints<'a>(LazyArrayIter16<'a, u16>);
+
+impl<'a> Iterator for ContourEndPoints<'a> {
+    type Item = u16;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+
+/// An iterator over TrueType glyph points.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphPointsIter<'a> {
+    all_points: RawPointsIter<'a>,
+    points: RawPointsIter<'a>,
+    deltas: Option<gvar::VariationTuples<'a>>,
+}
+
+impl<'a> Iterator for GlyphPointsIter<'a> {
+    type Item = GlyphPoint;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.points.next()?;
+        let (x, y) = match self.deltas {
+            Some(ref mut tuples) => tuples.apply(self.all_points.clone(), self.points.clone(), point)?,
+            None => (f32::from(point.x), f32::from(point.y)),
+        };
+
+        Some(GlyphPoint { x, y, on_curve: point.on_curve_point })
+    }
+}
+
+
 // Due to some optimization magic, using f32 instead of i16
 // makes the code ~10% slower. At least on my machine.
 // I guess it's due to the fact that with i16 the struct
 // fits into the machine word.
 #[derive(Clone, Copy, Debug)]
-pub struct GlyphPoint {
+pub struct RawPoint {
     pub x: i16,
     pub y: i16,
     /// Indicates that a point is a point on curve
@@ -287,7 +457,7 @@ pub struct GlyphPoint {
 
 
 #[derive(Clone, Default)]
-pub struct GlyphPointsIter<'a> {
+pub struct RawPointsIter<'a> {
     endpoints: EndpointsIter<'a>,
     flags: FlagsIter<'a>,
     x_coords: CoordsIter<'a>,
@@ -295,15 +465,15 @@ pub struct GlyphPointsIter<'a> {
     pub points_left: u16, // Number of points left in the glyph.
 }
 
-impl GlyphPointsIter<'_> {
+impl RawPointsIter<'_> {
     #[inline]
     pub fn current_contour(&self) -> u16 {
         self.endpoints.index - 1
     }
 }
 
-impl<'a> Iterator for GlyphPointsIter<'a> {
-    type Item = GlyphPoint;
+impl<'a> Iterator for RawPointsIter<'a> {
+    type Item = RawPoint;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
@@ -313,7 +483,7 @@ impl<'a> Iterator for GlyphPointsIter<'a> {
 
         let last_point = self.endpoints.next();
         let flags = self.flags.next()?;
-        Some(GlyphPoint {
+        Some(RawPoint {
             x: self.x_coords.next(flags.x_short(), flags.x_is_same_or_positive_short()),
             y: self.y_coords.next(flags.y_short(), flags.y_is_same_or_positive_short()),
             on_curve_point: flags.on_curve_point(),
@@ -477,17 +647,36 @@ impl SimpleGlyphFlags {
 }
 
 
-// https://docs.microsoft.com/en-us/typography/opentype/spec/glyf#composite-glyph-description
-#[derive(Clone, Copy, Debug)]
-pub(crate) struct CompositeGlyphFlags(u16);
+/// [Composite glyph flags](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/glyf#composite-glyph-description).
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct ComponentFlags(pub u16);
 
-impl CompositeGlyphFlags {
+impl ComponentFlags {
+    /// Checks that arguments are 16-bit.
     #[inline] pub fn arg_1_and_2_are_words(self) -> bool { self.0 & 0x0001 != 0 }
+    /// Checks that arguments are an offset and not point numbers.
     #[inline] pub fn args_are_xy_values(self) -> bool { self.0 & 0x0002 != 0 }
+    /// Checks that the offset should be rounded to the grid.
+    #[inline] pub fn round_xy_to_grid(self) -> bool { self.0 & 0x0004 != 0 }
+    /// Checks that the component has a simple scale.
     #[inline] pub fn we_have_a_scale(self) -> bool { self.0 & 0x0008 != 0 }
+    /// Checks that at least one more component follows.
     #[inline] pub fn more_components(self) -> bool { self.0 & 0x0020 != 0 }
+    /// Checks that the component has separate horizontal and vertical scales.
     #[inline] pub fn we_have_an_x_and_y_scale(self) -> bool { self.0 & 0x0040 != 0 }
+    /// Checks that the component has a 2x2 transformation.
     #[inline] pub fn we_have_a_two_by_two(self) -> bool { self.0 & 0x0080 != 0 }
+    /// Checks that the composite glyph has instructions after the last component.
+    #[inline] pub fn we_have_instructions(self) -> bool { self.0 & 0x0100 != 0 }
+    /// Checks that the composite glyph should use metrics of this component.
+    #[inline] pub fn use_my_metrics(self) -> bool { self.0 & 0x0200 != 0 }
+    /// Checks that the composite glyph components overlap.
+    #[inline] pub fn overlap_compound(self) -> bool { self.0 & 0x0400 != 0 }
+    /// Checks that the component offset should be scaled.
+    #[inline] pub fn scaled_component_offset(self) -> bool { self.0 & 0x0800 != 0 }
+    /// Checks that the component offset should not be scaled.
+    #[inline] pub fn unscaled_component_offset(self) -> bool { self.0 & 0x1000 != 0 }
 }
 
 
@@ -507,6 +696,9 @@ pub(crate) fn outline(
     outline_impl(loca_table, glyf_table, glyph_data, 0, &mut b)
 }
 
+/// `gvar` table with variation coordinates.
+pub(crate) type GlyphVariation<'a, 'b> = Option<(&'b gvar::Table<'a>, &'b [NormalizedCoordinate])>;
+
 #[inline]
 pub(crate) fn glyph_bbox(
     loca_table: loca::Table,
@@ -526,6 +718,356 @@ pub(crate) fn glyph_bbox(
     })
 }
 
+pub(crate) fn glyph_points<'a>(
+    loca_table: loca::Table,
+    glyf_table: &'a [u8],
+    glyph_id: GlyphId,
+    phantom_points: PhantomPoints,
+    variation: Option<(&gvar::Table<'a>, &[NormalizedCoordinate])>,
+) -> Option<GlyphPoints<'a>> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut s = Stream::new(glyph_data);
+    let number_of_contours: i16 = s.read()?;
+    s.advance(8); // Skip bbox.
+
+    // Only simple glyphs have points.
+    if number_of_contours <= 0 {
+        return None;
+    }
+
+    // u16 casting is safe, since we already checked that the value is positive.
+    let number_of_contours = NonZeroU16::new(number_of_contours as u16)?;
+    let glyph = parse_simple_glyph(s.tail()?, number_of_contours)?;
+
+    let mut points = GlyphPoints { glyph, deltas: None, phantom_points };
+    if let Some((gvar_table, coordinates)) = variation {
+        let points_len = points.len();
+        let tuples = gvar_table.glyph_deltas(glyph_id, coordinates, points_len)?;
+        points.phantom_points = gvar::apply_phantom_deltas(tuples.clone(), points_len, phantom_points)?;
+        points.deltas = Some(tuples);
+    }
+
+    Some(points)
+}
+
+pub(crate) fn glyph_components<'a>(
+    loca_table: loca::Table,
+    glyf_table: &'a [u8],
+    glyph_id: GlyphId,
+) -> Option<GlyphComponents<'a>> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut s = Stream::new(glyph_data);
+    let number_of_contours: i16 = s.read()?;
+    s.advance(8); // Skip bbox.
+
+    // Only composite glyphs have components.
+    if number_of_contours >= 0 {
+        return None;
+    }
+
+    Some(GlyphComponents::new(s.tail()?))
+}
+
+/// Returns the number of points in a simple glyph or the number of components in a composite one.
+///
+/// This is the number of `gvar` deltas stored before the phantom points ones.
+pub(crate) fn variation_points_len(data: &[u8]) -> Option<u16> {
+    let mut s = Stream::new(data);
+    let number_of_contours: i16 = match s.read() {
+        Some(n) => n,
+        None => return Some(0), // An empty glyph.
+    };
+    s.advance(8); // Skip bbox.
+
+    if number_of_contours > 0 {
+        // u16 casting is safe, since we already checked that the value is positive.
+        let endpoints = s.read_array16::<u16>(number_of_contours as u16)?;
+        endpoints.last()?.checked_add(1)
+    } else if number_of_contours < 0 {
+        Some(GlyphComponents::new(s.tail()?).count() as u16)
+    } else {
+        Some(0)
+    }
+}
+
+/// A `glyf` table with its `loca` table.
+///
+/// Used by tables that reference glyph control points.
+#[derive(Clone, Copy)]
+pub(crate) struct GlyphOutlines<'a> {
+    pub loca: loca::Table<'a>,
+    pub glyf: &'a [u8],
+}
+
+impl<'a> GlyphOutlines<'a> {
+    #[inline]
+    pub fn new(loca: Option<loca::Table<'a>>, glyf: Option<&'a [u8]>) -> Option<Self> {
+        Some(GlyphOutlines { loca: loca?, glyf: glyf? })
+    }
+
+    /// Returns a glyph point by index.
+    ///
+    /// Points of a composite glyph are numbered across all of its components.
+    pub fn point(&self, glyph_id: GlyphId, index: u16) -> Option<(i16, i16)> {
+        let resolver = PointsResolver::new(self.loca, self.glyf, None);
+        let (x, y) = resolver.point(glyph_id, index, 0)?;
+        Some((i16::try_num_from(x)?, i16::try_num_from(y)?))
+    }
+}
+
+
+// It's not defined in the spec, so we are using our own value.
+const MAX_GLYPH_LOOKUPS: u16 = 1024;
+
+/// Resolves glyph points by index.
+///
+/// Resolving anchor points can require visiting the same glyphs multiple times,
+/// so the total number of glyph lookups is limited by `MAX_GLYPH_LOOKUPS`.
+pub(crate) struct PointsResolver<'a, 'b> {
+    loca_table: loca::Table<'a>,
+    glyf_table: &'a [u8],
+    variation: GlyphVariation<'a, 'b>,
+    lookups_left: Cell<u16>,
+}
+
+impl<'a, 'b> PointsResolver<'a, 'b> {
+    #[inline]
+    pub fn new(
+        loca_table: loca::Table<'a>,
+        glyf_table: &'a [u8],
+        variation: GlyphVariation<'a, 'b>,
+    ) -> Self {
+        PointsResolver {
+            loca_table,
+            glyf_table,
+            variation,
+            lookups_left: Cell::new(MAX_GLYPH_LOOKUPS),
+        }
+    }
+
+    /// Returns glyph data. An empty glyph has empty data.
+    fn glyph_data(&self, glyph_id: GlyphId) -> Option<&'a [u8]> {
+        let lookups_left = self.lookups_left.get().checked_sub(1)?;
+        self.lookups_left.set(lookups_left);
+
+        match self.loca_table.glyph_range(glyph_id) {
+            Some(range) => self.glyf_table.get(range),
+            None => Some(&[]),
+        }
+    }
+
+    /// Returns the number of glyph points, including points of all components.
+    fn points_len(&self, glyph_id: GlyphId, depth: u8) -> Option<u16> {
+        if depth >= MAX_COMPONENTS {
+            return None;
+        }
+
+        let mut s = Stream::new(self.glyph_data(glyph_id)?);
+        let number_of_contours: i16 = match s.read() {
+            Some(n) => n,
+            None => return Some(0), // An empty glyph.
+        };
+        s.advance(8); // Skip bbox.
+
+        if number_of_contours > 0 {
+            // u16 casting is safe, since we already checked that the value is positive.
+            let endpoints = s.read_array16::<u16>(number_of_contours as u16)?;
+            endpoints.last()?.checked_add(1)
+        } else if number_of_contours < 0 {
+            let mut total: u16 = 0;
+            for component in GlyphComponents::new(s.tail()?) {
+                total = total.checked_add(self.points_len(component.glyph_id, depth + 1)?)?;
+            }
+
+            Some(total)
+        } else {
+            Some(0)
+        }
+    }
+
+    /// Returns a glyph point by index.
+    ///
+    /// Points of a composite glyph are numbered across all of its components.
+    pub fn point(&self, glyph_id: GlyphId, index: u16, depth: u8) -> Option<(f32, f32)> {
+        if depth >= MAX_COMPONENTS {
+            return None;
+        }
+
+        let mut s = Stream::new(self.glyph_data(glyph_id)?);
+        let number_of_contours: i16 = s.read()?;
+        s.advance(8); // Skip bbox.
+
+        if number_of_contours > 0 {
+            // u16 casting is safe, since we already checked that the value is positive.
+            let number_of_contours = NonZeroU16::new(number_of_contours as u16)?;
+            let mut points = parse_simple_glyph(s.tail()?, number_of_contours)?.points;
+            let number_of_points = points.points_left;
+            if index >= number_of_points {
+                return None;
+            }
+
+            if let Some((gvar_table, coordinates)) = self.variation {
+                // Deltas can be inferred from neighbour points,
+                // so we have to process all the points before the requested one.
+                let all_points = points.clone();
+                let mut tuples = gvar_table.glyph_deltas(glyph_id, coordinates, number_of_points)?;
+                for _ in 0..index {
+                    let point = points.next()?;
+                    tuples.apply(all_points.clone(), points.clone(), point)?;
+                }
+
+                let point = points.next()?;
+                tuples.apply(all_points, points.clone(), point)
+            } else {
+                let point = points.nth(usize::from(index))?;
+                Some((f32::from(point.x), f32::from(point.y)))
+            }
+        } else if number_of_contours < 0 {
+            let components = GlyphComponents::new(s.tail()?);
+            let mut tuples = match self.variation {
+                Some((gvar_table, coordinates)) => {
+                    let components_count = components.count() as u16;
+                    Some(gvar_table.glyph_deltas(glyph_id, coordinates, components_count)?)
+                }
+                None => None,
+            };
+
+            let mut placement = ComponentsPlacement::new(self, components, depth, true);
+            for component in components {
+                let offset_delta = match tuples {
+                    Some(ref mut tuples) => tuples.apply_null()?,
+                    None => (0.0, 0.0),
+                };
+
+                let first_point = placement.points_len;
+                let ts = placement.place(component, offset_delta)?;
+                if index < placement.points_len {
+                    let (mut x, mut y) = self.point(component.glyph_id, index - first_point, depth + 1)?;
+                    ts.apply_to(&mut x, &mut y);
+                    return Some((x, y));
+                }
+            }
+
+            None
+        } else {
+            None
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, Default)]
+struct PlacedComponent {
+    glyph_id: GlyphId,
+    first_point: u16,
+    transform: Transform,
+}
+
+/// Resolves composite glyph components transforms.
+///
+/// An anchored component can reference only points of the components placed before it,
+/// so all components are placed in a single forward pass,
+/// while the first point index and the transform of each placed component are kept.
+///
+/// A composite glyph with anchored components can have up to `MAX_COMPONENTS` components.
+pub(crate) struct ComponentsPlacement<'r, 'a, 'b> {
+    resolver: &'r PointsResolver<'a, 'b>,
+    depth: u8,
+    has_anchors: bool,
+    count_points: bool,
+    placed: [PlacedComponent; MAX_COMPONENTS as usize],
+    placed_len: u8,
+    /// The number of points in the components placed so far.
+    /// Tracked only when requested or when anchor points are used.
+    points_len: u16,
+}
+
+impl<'r, 'a, 'b> ComponentsPlacement<'r, 'a, 'b> {
+    pub fn new(
+        resolver: &'r PointsResolver<'a, 'b>,
+        mut components: GlyphComponents,
+        depth: u8,
+        count_points: bool,
+    ) -> Self {
+        let has_anchors = components.any(|c| c.anchor_points.is_some());
+        ComponentsPlacement {
+            resolver,
+            depth,
+            has_anchors,
+            count_points: count_points || has_anchors,
+            placed: [PlacedComponent::default(); MAX_COMPONENTS as usize],
+            placed_len: 0,
+            points_len: 0,
+        }
+    }
+
+    /// Returns the next component transform.
+    ///
+    /// Must be called for each component in order.
+    /// `offset_delta` is a `gvar` adjustment of the component offset.
+    pub fn place(&mut self, component: GlyphComponent, offset_delta: (f32, f32)) -> Option<Transform> {
+        let ts = match component.anchor_points {
+            Some((parent_point, component_point)) => {
+                let (parent_x, parent_y) = self.placed_point(parent_point)?;
+                let (mut x, mut y) = self.resolver.point(
+                    component.glyph_id, component_point, self.depth + 1,
+                )?;
+
+                // The component offset is zero at this point, so only the 2x2 part is applied.
+                let mut ts = component.transform;
+                ts.apply_to(&mut x, &mut y);
+                ts.e = parent_x - x;
+                ts.f = parent_y - y;
+                ts
+            }
+            None => {
+                // Variation component offset will be applied only when
+                // the ARGS_ARE_XY_VALUES flag is set.
+                let (dx, dy) = offset_delta;
+                if dx == 0.0 && dy == 0.0 {
+                    component.transform
+                } else {
+                    Transform::combine(Transform::new_translate(dx, dy), component.transform)
+                }
+            }
+        };
+
+        if self.count_points {
+            let first_point = self.points_len;
+            let points_len = self.resolver.points_len(component.glyph_id, self.depth + 1)?;
+            self.points_len = first_point.checked_add(points_len)?;
+
+            if self.has_anchors {
+                *self.placed.get_mut(usize::from(self.placed_len))? = PlacedComponent {
+                    glyph_id: component.glyph_id,
+                    first_point,
+                    transform: ts,
+                };
+                self.placed_len += 1;
+            }
+        }
+
+        Some(ts)
+    }
+
+    /// Returns a point of the already placed components.
+    fn placed_point(&self, index: u16) -> Option<(f32, f32)> {
+        if index >= self.points_len {
+            return None;
+        }
+
+        let placed = &self.placed[..usize::from(self.placed_len)];
+        let component = placed.iter().rev().find(|c| c.first_point <= index)?;
+        let (mut x, mut y) = self.resolver.point(
+            component.glyph_id, index - component.first_point, self.depth + 1,
+        )?;
+        component.transform.apply_to(&mut x, &mut y);
+        Some((x, y))
+    }
+}
+
 #[inline]
 fn outline_impl(
     loca_table: loca::Table,
@@ -559,10 +1101,14 @@ fn outline_impl(
         }
     } else if number_of_contours < 0 {
         // Composite glyph.
-        for comp in CompositeGlyphIter::new(s.tail()?) {
+        let components = GlyphComponents::new(s.tail()?);
+        let resolver = PointsResolver::new(loca_table, glyf_table, None);
+        let mut placement = ComponentsPlacement::new(&resolver, components, depth, false);
+        for comp in components {
+            let comp_transform = placement.place(comp, (0.0, 0.0))?;
             if let Some(range) = loca_table.glyph_range(comp.glyph_id) {
                 if let Some(glyph_data) = glyf_table.get(range) {
-                    let transform = Transform::combine(builder.transform, comp.transform);
+                    let transform = Transform::combine(builder.transform, comp_transform);
                     let mut b = Builder::new(transform, None, builder.builder);
                     outline_impl(loca_table, glyf_table, glyph_data, depth + 1, &mut b)?;
                 }
@@ -580,7 +1126,7 @@ fn outline_impl(
 pub fn parse_simple_outline(
     glyph_data: &[u8],
     number_of_contours: NonZeroU16,
-) -> Option<GlyphPointsIter> {
+) -> Option<RawPointsIter> {
     let mut s = Stream::new(glyph_data);
     let endpoints = s.read_array16::<u16>(number_of_contours.get())?;
 
@@ -589,12 +1135,30 @@ pub fn parse_simple_outline(
     // Contours with a single point should be ignored.
     // But this is not an error, so we should return an "empty" iterator.
     if points_total == 1 {
-        return Some(GlyphPointsIter::default());
+        return Some(RawPointsIter::default());
     }
 
-    // Skip instructions byte code.
+    parse_simple_glyph(glyph_data, number_of_contours).map(|glyph| glyph.points)
+}
+
+#[derive(Clone)]
+pub(crate) struct SimpleGlyph<'a> {
+    pub endpoints: LazyArray16<'a, u16>,
+    pub instructions: &'a [u8],
+    pub points: RawPointsIter<'a>,
+}
+
+fn parse_simple_glyph(
+    glyph_data: &[u8],
+    number_of_contours: NonZeroU16,
+) -> Option<SimpleGlyph<'_>> {
+    let mut s = Stream::new(glyph_data);
+    let endpoints = s.read_array16::<u16>(number_of_contours.get())?;
+
+    let points_total = endpoints.last()?.checked_add(1)?;
+
     let instructions_len: u16 = s.read()?;
-    s.advance(usize::from(instructions_len));
+    let instructions = s.read_bytes(usize::from(instructions_len))?;
 
     let flags_offset = s.offset();
     let (x_coords_len, y_coords_len) = resolve_coords_len(&mut s, points_total)?;
@@ -602,12 +1166,16 @@ pub fn parse_simple_outline(
     let y_coords_offset = x_coords_offset + usize::num_from(x_coords_len);
     let y_coords_end = y_coords_offset + usize::num_from(y_coords_len);
 
-    Some(GlyphPointsIter {
-        endpoints: EndpointsIter::new(endpoints)?,
-        flags: FlagsIter::new(glyph_data.get(flags_offset..x_coords_offset)?),
-        x_coords: CoordsIter::new(glyph_data.get(x_coords_offset..y_coords_offset)?),
-        y_coords: CoordsIter::new(glyph_data.get(y_coords_offset..y_coords_end)?),
-        points_left: points_total,
+    Some(SimpleGlyph {
+        endpoints,
+        instructions,
+        points: RawPointsIter {
+            endpoints: EndpointsIter::new(endpoints)?,
+            flags: FlagsIter::new(glyph_data.get(flags_offset..x_coords_offset)?),
+            x_coords: CoordsIter::new(glyph_data.get(x_coords_offset..y_coords_offset)?),
+            y_coords: CoordsIter::new(glyph_data.get(y_coords_offset..y_coords_end)?),
+            points_left: points_total,
+        },
     })
 }
 
@@ -660,3 +1228,305 @@ fn resolve_coords_len(
 
     Some((x_coords_len, y_coords_len))
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use std::string::String;
+    use std::fmt::Write;
+    use super::*;
+    use crate::IndexToLocationFormat;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    struct Builder(String);
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    #[test]
+    fn simple_glyph_points() {
+        let glyf_data = convert(&[
+            Int16(1), // number of contours
+            Int16(0), // x min
+            Int16(0), // y min
+            Int16(100), // x max
+            Int16(100), // y max
+            UInt16(2), // end point [0]
+            UInt16(2), // instructions length
+            UInt8(0xB0), // PUSHB[0]
+            UInt8(0x01), // 1
+            UInt8(0x31), // flags [0]: on curve, same x, same y
+            UInt8(0x33), // flags [1]: on curve, positive short x, same y
+            UInt8(0x26), // flags [2]: negative short x, positive short y
+            UInt8(100), // x [1]
+            UInt8(50), // x [2]
+            UInt8(100), // y [2]
+        ]);
+
+        let loca_data = convert(&[
+            UInt16(0), // offset [0]
+            UInt16(11), // offset [1]
+        ]);
+
+        let number_of_glyphs = NonZeroU16::new(1).unwrap();
+        let loca_table = loca::Table::parse(
+            &loca_data, number_of_glyphs, IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let phantom_points = PhantomPoints { left: 0.0, right: 120.0, top: 100.0, bottom: -20.0 };
+        let points = glyph_points(loca_table, &glyf_data, GlyphId(0), phantom_points, None).unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points.contour_end_points().collect::<Vec<_>>(), &[2]);
+        assert_eq!(points.instructions(), &[0xB0, 0x01]);
+        assert_eq!(points.phantom_points(), phantom_points);
+
+        let mut iter = points.points();
+        assert_eq!(iter.next(), Some(GlyphPoint { x: 0.0, y: 0.0, on_curve: true }));
+        assert_eq!(iter.next(), Some(GlyphPoint { x: 100.0, y: 0.0, on_curve: true }));
+        assert_eq!(iter.next(), Some(GlyphPoint { x: 50.0, y: 100.0, on_curve: false }));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn glyph_components() {
+        let glyf_data = convert(&[
+            Int16(-1), // number of contours
+            Int16(0), // x min
+            Int16(0), // y min
+            Int16(100), // x max
+            Int16(100), // y max
+            // Component [0]
+            UInt16(0x0823), // flags: words, xy values, more components, scaled offset
+            UInt16(1), // glyph ID
+            Int16(-300), // x offset
+            Int16(20), // y offset
+            // Component [1]
+            UInt16(0x0288), // flags: scale, two by two, use my metrics
+            UInt16(2), // glyph ID
+            UInt8(5), // parent point
+            UInt8(3), // component point
+            Int16(0x2000), // a: 0.5
+            Int16(0), // b
+            Int16(0), // c
+            Int16(0x4000), // d: 1.0
+        ]);
+
+        let loca_data = convert(&[
+            UInt16(0), // offset [0]
+            UInt16(16), // offset [1]
+            UInt16(16), // offset [2]
+            UInt16(16), // offset [3]
+        ]);
+
+        let number_of_glyphs = NonZeroU16::new(3).unwrap();
+        let loca_table = loca::Table::parse(
+            &loca_data, number_of_glyphs, IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let mut components = super::glyph_components(loca_table, &glyf_data, GlyphId(0)).unwrap();
+
+        let component = components.next().unwrap();
+        assert_eq!(component.glyph_id, GlyphId(1));
+        assert_eq!(component.transform, Transform::new_translate(-300.0, 20.0));
+        assert_eq!(component.anchor_points, None);
+        assert!(component.flags.scaled_component_offset());
+        assert!(!component.flags.use_my_metrics());
+
+        let component = components.next().unwrap();
+        assert_eq!(component.glyph_id, GlyphId(2));
+        assert_eq!(component.transform, Transform { a: 0.5, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 });
+        assert_eq!(component.anchor_points, Some((5, 3)));
+        assert!(component.flags.use_my_metrics());
+        assert!(!component.flags.overlap_compound());
+
+        assert!(components.next().is_none());
+        assert!(super::glyph_components(loca_table, &glyf_data, GlyphId(1)).is_none());
+    }
+
+    #[test]
+    fn anchor_points() {
+        let glyf_data = convert(&[
+            // Glyph [0]
+            Int16(-1), // number of contours
+            Int16(0), // x min
+            Int16(0), // y min
+            Int16(65), // x max
+            Int16(120), // y max
+            // Component [0]
+            UInt16(0x0022), // flags: xy values, more components
+            UInt16(1), // glyph ID
+            UInt8(0), // x offset
+            UInt8(0), // y offset
+            // Component [1]
+            UInt16(0x0020), // flags: more components
+            UInt16(2), // glyph ID
+            UInt8(2), // parent point
+            UInt8(0), // component point
+            // Component [2]
+            UInt16(0x0000), // flags
+            UInt16(2), // glyph ID
+            UInt8(4), // parent point: component [1] point [1]
+            UInt8(2), // component point
+            // Glyph [1]
+            Int16(1), // number of contours
+            Int16(0), // x min
+            Int16(0), // y min
+            Int16(100), // x max
+            Int16(100), // y max
+            UInt16(2), // end point [0]
+            UInt16(0), // instructions length
+            UInt8(0x31), // flags [0]: on curve, same x, same y
+            UInt8(0x33), // flags [1]: on curve, positive short x, same y
+            UInt8(0x27), // flags [2]: on curve, negative short x, positive short y
+            UInt8(100), // x [1]
+            UInt8(50), // x [2]
+            UInt8(100), // y [2]
+            // Glyph [2]
+            Int16(1), // number of contours
+            Int16(10), // x min
+            Int16(10), // y min
+            Int16(20), // x max
+            Int16(30), // y max
+            UInt16(2), // end point [0]
+            UInt16(0), // instructions length
+            UInt8(0x37), // flags [0]: on curve, positive short x, positive short y
+            UInt8(0x33), // flags [1]: on curve, positive short x, same y
+            UInt8(0x27), // flags [2]: on curve, negative short x, positive short y
+            UInt8(10), // x [0]
+            UInt8(10), // x [1]
+            UInt8(5), // x [2]
+            UInt8(10), // y [0]
+            UInt8(20), // y [2]
+        ]);
+
+        let loca_data = convert(&[
+            UInt16(0), // offset [0]
+            UInt16(14), // offset [1]
+            UInt16(24), // offset [2]
+            UInt16(35), // offset [3]
+        ]);
+
+        let number_of_glyphs = NonZeroU16::new(3).unwrap();
+        let loca_table = loca::Table::parse(
+            &loca_data, number_of_glyphs, IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let mut builder = Builder(String::new());
+        outline(loca_table, &glyf_data, GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 0 0 L 100 0 L 50 100 L 0 0 Z \
+                               M 50 100 L 60 100 L 55 120 L 50 100 Z \
+                               M 55 80 L 65 80 L 60 100 L 55 80 Z ");
+
+        let outlines = GlyphOutlines { loca: loca_table, glyf: &glyf_data };
+        assert_eq!(outlines.point(GlyphId(0), 4), Some((60, 100)));
+        assert_eq!(outlines.point(GlyphId(0), 6), Some((55, 80)));
+        assert_eq!(outlines.point(GlyphId(0), 9), None);
+    }
+
+    // A composite glyph where each component is anchored to the previous one.
+    fn chained_anchors(components: u16) -> (Vec<u8>, Vec<u8>) {
+        let mut glyf = vec![
+            // Glyph [0]
+            Int16(-1), // number of contours
+            Int16(0), // x min
+            Int16(0), // y min
+            Int16(0), // x max
+            Int16(0), // y max
+            // Component [0]
+            UInt16(0x0022), // flags: xy values, more components
+            UInt16(1), // glyph ID
+            UInt8(0), // x offset
+            UInt8(0), // y offset
+        ];
+
+        for i in 1..components {
+            let flags = if i + 1 == components { 0x0000 } else { 0x0020 };
+            glyf.extend_from_slice(&[
+                UInt16(flags),
+                UInt16(1), // glyph ID
+                UInt8((i as u8 - 1) * 3 + 1), // parent point: previous component point [1]
+                UInt8(0), // component point
+            ]);
+        }
+
+        glyf.extend_from_slice(&[
+            // Glyph [1]
+            Int16(1), // number of contours
+            Int16(0), // x min
+            Int16(0), // y min
+            Int16(10), // x max
+            Int16(10), // y max
+            UInt16(2), // end point [0]
+            UInt16(0), // instructions length
+            UInt8(0x31), // flags [0]: on curve, same x, same y
+            UInt8(0x33), // flags [1]: on curve, positive short x, same y
+            UInt8(0x27), // flags [2]: on curve, negative short x, positive short y
+            UInt8(10), // x [1]
+            UInt8(5), // x [2]
+            UInt8(10), // y [2]
+        ]);
+
+        let composite_len = 10 + 6 * components;
+        let loca = convert(&[
+            UInt16(0), // offset [0]
+            UInt16(composite_len / 2), // offset [1]
+            UInt16((composite_len + 20) / 2), // offset [2]
+        ]);
+
+        (convert(&glyf), loca)
+    }
+
+    #[test]
+    fn chained_anchor_points() {
+        let (glyf_data, loca_data) = chained_anchors(u16::from(MAX_COMPONENTS));
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let loca_table = loca::Table::parse(
+            &loca_data, number_of_glyphs, IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let mut builder = Builder(String::new());
+        assert!(outline(loca_table, &glyf_data, GlyphId(0), &mut builder).is_some());
+        assert!(builder.0.ends_with("M 310 0 L 320 0 L 315 10 L 310 0 Z "));
+
+        let outlines = GlyphOutlines { loca: loca_table, glyf: &glyf_data };
+        assert_eq!(outlines.point(GlyphId(0), 2), Some((5, 10)));
+        assert_eq!(outlines.point(GlyphId(0), 95), Some((315, 10)));
+        assert_eq!(outlines.point(GlyphId(0), 96), None);
+    }
+
+    #[test]
+    fn too_many_anchored_components() {
+        let (glyf_data, loca_data) = chained_anchors(u16::from(MAX_COMPONENTS) + 1);
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let loca_table = loca::Table::parse(
+            &loca_data, number_of_glyphs, IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let mut builder = Builder(String::new());
+        assert!(outline(loca_table, &glyf_data, GlyphId(0), &mut builder).is_none());
+
+        let outlines = GlyphOutlines { loca: loca_table, glyf: &glyf_data };
+        assert_eq!(outlines.point(GlyphId(0), 2), Some((5, 10)));
+        assert_eq!(outlines.point(GlyphId(0), 98), None);
+    }
+}
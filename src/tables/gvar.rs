@@ -8,12 +8,11 @@ use core::num::NonZeroU16;
 use crate::{loca, GlyphId, OutlineBuilder, Rect, BBox, NormalizedCoordinate};
 use crate::parser::{Stream, Offset, Offset16, Offset32, LazyArray16, F2DOT14};
 use crate::glyf::{self, Transform};
+use crate::TableParsingError;
 
 /// 'The TrueType rasterizer dynamically generates 'phantom' points for each glyph
 /// that represent horizontal and vertical advance widths and side bearings,
 /// and the variation data within the `gvar` table includes data for these phantom points.'
-///
-/// We don't actually use them, but they are required during deltas parsing.
 const PHANTOM_POINTS_LEN: usize = 4;
 
 #[derive(Clone, Copy)]
@@ -32,13 +31,17 @@ pub struct Table<'a> {
 
 impl<'a> Table<'a> {
     // https://docs.microsoft.com/en-us/typography/opentype/spec/gvar#gvar-header
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
-        let version: u32 = s.read()?;
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
         if version != 0x00010000 {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
         }
 
+        Self::parse_impl(data, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], mut s: Stream<'a>) -> Option<Self> {
         let axis_count: u16 = s.read()?;
         let shared_tuple_count: u16 = s.read()?;
         let shared_tuples_offset: Offset32 = s.read()?;
@@ -108,6 +111,64 @@ impl<'a> Table<'a> {
         let data = self.glyphs_variation_data.get(start..end)?;
         parse_variation_data(coordinates, &self.shared_tuple_records, points_len, data, tuples)
     }
+
+    /// Parses glyph variation tuples.
+    ///
+    /// `points_len` is the number of points in a simple glyph
+    /// or the number of components in a composite one.
+    pub(crate) fn glyph_deltas(
+        &self,
+        glyph_id: GlyphId,
+        coordinates: &[NormalizedCoordinate],
+        points_len: u16,
+    ) -> Option<VariationTuples<'a>> {
+        let mut tuples = VariationTuples {
+            headers: [VariationTuple::default(); MAX_TUPLES_LEN as usize],
+            len: 0,
+        };
+
+        self.parse_variation_data(glyph_id, coordinates, points_len, &mut tuples)?;
+        Some(tuples)
+    }
+}
+
+
+/// Returns glyph phantom points with variation deltas applied.
+pub(crate) fn phantom_points(
+    loca_table: loca::Table,
+    glyf_table: &[u8],
+    gvar_table: &Table,
+    coordinates: &[NormalizedCoordinate],
+    glyph_id: GlyphId,
+    points: glyf::PhantomPoints,
+) -> Option<glyf::PhantomPoints> {
+    // Empty glyphs have no `loca` range, but still have phantom points.
+    let glyph_data = loca_table.glyph_range(glyph_id)
+        .and_then(|range| glyf_table.get(range))
+        .unwrap_or(&[]);
+    let points_len = glyf::variation_points_len(glyph_data)?;
+    let tuples = gvar_table.glyph_deltas(glyph_id, coordinates, points_len)?;
+    apply_phantom_deltas(tuples, points_len, points)
+}
+
+/// Applies variation deltas to glyph phantom points.
+///
+/// Phantom point deltas are stored right after the glyph points deltas,
+/// so the first `points_len` deltas are skipped.
+pub(crate) fn apply_phantom_deltas(
+    mut tuples: VariationTuples,
+    points_len: u16,
+    mut points: glyf::PhantomPoints,
+) -> Option<glyf::PhantomPoints> {
+    for _ in 0..points_len {
+        tuples.apply_null()?;
+    }
+
+    points.left += tuples.apply_null()?.0;
+    points.right += tuples.apply_null()?.0;
+    points.top += tuples.apply_null()?.1;
+    points.bottom += tuples.apply_null()?.1;
+    Some(points)
 }
 
 
@@ -186,22 +247,16 @@ fn outline_var_impl<'a>(
         // Details:
         // https://docs.microsoft.com/en-us/typography/opentype/spec/gvar#point-numbers-and-processing-for-composite-glyphs
 
-        let mut components = glyf::CompositeGlyphIter::new(s.tail()?);
-        let components_count = components.clone().count() as u16;
+        let components = glyf::GlyphComponents::new(s.tail()?);
+        let components_count = components.count() as u16;
         gvar_table.parse_variation_data(glyph_id, coordinates, components_count, &mut tuples)?;
 
-        while let Some(component) = components.next() {
-            let (tx, ty) = tuples.apply_null()?;
-
-            let mut transform = builder.transform;
-
-            // Variation component offset should be applied only when
-            // the ARGS_ARE_XY_VALUES flag is set.
-            if component.flags.args_are_xy_values() {
-                transform = Transform::combine(transform, Transform::new_translate(tx, ty));
-            }
-
-            transform = Transform::combine(transform, component.transform);
+        let resolver = glyf::PointsResolver::new(loca_table, glyf_table, Some((gvar_table, coordinates)));
+        let mut placement = glyf::ComponentsPlacement::new(&resolver, components, depth, false);
+        for component in components {
+            let offset_delta = tuples.apply_null()?;
+            let component_transform = placement.place(component, offset_delta)?;
+            let transform = Transform::combine(builder.transform, component_transform);
 
             let mut b = glyf::Builder::new(transform, builder.bbox, builder.builder);
             let range = loca_table.glyph_range(component.glyph_id)?;
@@ -308,7 +363,8 @@ const MAX_TUPLES_LEN: u16 = 16;
 /// On stack and not on heap, but still.
 /// This is probably unavoidable due to `gvar` structure,
 /// since we have to iterate all tuples in parallel.
-struct VariationTuples<'a> {
+#[derive(Clone)]
+pub(crate) struct VariationTuples<'a> {
     headers: [VariationTuple<'a>; MAX_TUPLES_LEN as usize], // 1280B
     len: u16,
 }
@@ -319,11 +375,11 @@ impl<'a> VariationTuples<'a> {
         &mut self.headers[0..usize::from(self.len)]
     }
 
-    fn apply(
+    pub fn apply(
         &mut self,
-        all_points: glyf::GlyphPointsIter,
-        points: glyf::GlyphPointsIter,
-        point: glyf::GlyphPoint,
+        all_points: glyf::RawPointsIter,
+        points: glyf::RawPointsIter,
+        point: glyf::RawPoint,
     ) -> Option<(f32, f32)> {
         let mut x = f32::from(point.x);
         let mut y = f32::from(point.y);
@@ -377,7 +433,7 @@ impl<'a> VariationTuples<'a> {
     // This is just like `apply()`, but without `infer_deltas`,
     // since we use it only for component points and not a contour.
     // And since there are no contour and no points, `infer_deltas()` will do nothing.
-    fn apply_null(&mut self) -> Option<(f32, f32)> {
+    pub fn apply_null(&mut self) -> Option<(f32, f32)> {
         let mut x = 0.0;
         let mut y = 0.0;
 
@@ -757,7 +813,7 @@ mod packed_points {
             }
 
             // Iterator will be returning `Some(true)` after "finished".
-            // This is because this iterator will be zipped with the `glyf::GlyphPointsIter`
+            // This is because this iterator will be zipped with the `glyf::RawPointsIter`
             // and the number of glyph points can be larger than the amount of set points.
             // Anyway, this is a non-issue in a well-formed font.
             Some(true)
@@ -1416,10 +1472,10 @@ fn infer_deltas(
     tuple: &VariationTuple,
     points_set: SetPointsIter,
     // A points iterator that starts after the current point.
-    points: glyf::GlyphPointsIter,
+    points: glyf::RawPointsIter,
     // A points iterator that starts from the first point in the glyph.
-    all_points: glyf::GlyphPointsIter,
-    curr_point: glyf::GlyphPoint,
+    all_points: glyf::RawPointsIter,
+    curr_point: glyf::RawPoint,
 ) -> (f32, f32) {
     let mut current_contour = points.current_contour();
     if curr_point.last_point && current_contour != 0 {
@@ -1566,3 +1622,62 @@ fn infer_delta(
         (1.0 - d) * prev_delta + d * next_delta
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IndexToLocationFormat;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn phantom_points_deltas() {
+        let gvar_data = convert(&[
+            UInt32(0x00010000), // version
+            UInt16(1), // axis count
+            UInt16(0), // shared tuple count
+            UInt32(24), // shared tuples offset
+            UInt16(1), // glyph count
+            UInt16(0), // flags
+            UInt32(24), // glyph variation data array offset
+            UInt16(0), // offset [0]
+            UInt16(11), // offset [1]
+            // Glyph variation data [0]
+            UInt16(1), // tuple variation count
+            UInt16(10), // data offset
+            // Tuple variation header [0]
+            UInt16(11), // variation data size
+            UInt16(0xA000), // tuple index: embedded peak tuple, private point numbers
+            Int16(0x4000), // peak: 1.0
+            // Serialized data
+            UInt8(0), // point numbers: all points
+            UInt8(0x03), // x deltas control: 4 bytes
+            Raw(&[0, 50, 0, 0]),
+            UInt8(0x03), // y deltas control: 4 bytes
+            Raw(&[0, 0, 10, 0xF6]), // 0, 0, 10, -10
+            UInt8(0), // padding
+        ]);
+
+        let loca_data = convert(&[
+            UInt16(0), // offset [0]
+            UInt16(0), // offset [1]
+        ]);
+
+        let number_of_glyphs = NonZeroU16::new(1).unwrap();
+        let loca_table = loca::Table::parse(
+            &loca_data, number_of_glyphs, IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let table = Table::parse(&gvar_data).unwrap();
+        let points = glyf::PhantomPoints { left: 0.0, right: 500.0, top: 800.0, bottom: -200.0 };
+
+        let coordinates = [NormalizedCoordinate::from(1.0)];
+        let varied = phantom_points(loca_table, &[], &table, &coordinates, GlyphId(0), points).unwrap();
+        assert_eq!(varied, glyf::PhantomPoints { left: 0.0, right: 550.0, top: 810.0, bottom: -210.0 });
+
+        let coordinates = [NormalizedCoordinate::from(0.5)];
+        let varied = phantom_points(loca_table, &[], &table, &coordinates, GlyphId(0), points).unwrap();
+        assert_eq!(varied, glyf::PhantomPoints { left: 0.0, right: 525.0, top: 805.0, bottom: -205.0 });
+    }
+}
@@ -0,0 +1,127 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/hdmx
+
+use core::num::NonZeroU16;
+
+use crate::GlyphId;
+use crate::parser::{Stream, NumFrom};
+use crate::TableParsingError;
+
+
+#[derive(Clone, Copy)]
+pub struct Table<'a> {
+    records: &'a [u8],
+    number_of_records: u16,
+    record_size: usize,
+    number_of_glyphs: NonZeroU16,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(number_of_glyphs: NonZeroU16, data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let mut s = Stream::new(data);
+        let version: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+        if version != 0 {
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        Self::parse_impl(number_of_glyphs, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(number_of_glyphs: NonZeroU16, mut s: Stream<'a>) -> Option<Self> {
+        let number_of_records: u16 = s.read()?;
+        let record_size = usize::num_from(s.read::<u32>()?);
+
+        // Each record is `pixelSize (u8) + maxWidth (u8) + widths[numGlyphs] (u8)`.
+        if record_size < usize::from(number_of_glyphs.get()) + 2 {
+            return None;
+        }
+
+        let records = s.read_bytes(record_size.checked_mul(usize::from(number_of_records))?)?;
+        Some(Table {
+            records,
+            number_of_records,
+            record_size,
+            number_of_glyphs,
+        })
+    }
+
+    pub fn glyph_advance(&self, glyph_id: GlyphId, pixels_per_em: u16) -> Option<u8> {
+        // Records can be padded, so we cannot rely on the record size.
+        if glyph_id.0 >= self.number_of_glyphs.get() {
+            return None;
+        }
+
+        for i in 0..usize::from(self.number_of_records) {
+            let start = i * self.record_size;
+            let record = self.records.get(start..start + self.record_size)?;
+            if u16::from(*record.first()?) == pixels_per_em {
+                return Stream::read_at::<u8>(record, 2 + usize::from(glyph_id.0));
+            }
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn basic() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(2), // number of records
+            UInt32(4), // record size
+            // Record [0]
+            UInt8(12), // pixel size
+            UInt8(7), // max width
+            UInt8(5), // width [0]
+            UInt8(7), // width [1]
+            // Record [1]
+            UInt8(16), // pixel size
+            UInt8(9), // max width
+            UInt8(7), // width [0]
+            UInt8(9), // width [1]
+        ]);
+
+        let table = Table::parse(NonZeroU16::new(2).unwrap(), &data).unwrap();
+        assert_eq!(table.glyph_advance(GlyphId(1), 12), Some(7));
+        assert_eq!(table.glyph_advance(GlyphId(0), 16), Some(7));
+        assert_eq!(table.glyph_advance(GlyphId(1), 16), Some(9));
+        assert_eq!(table.glyph_advance(GlyphId(1), 14), None);
+        assert_eq!(table.glyph_advance(GlyphId(2), 12), None);
+        assert_eq!(table.glyph_advance(GlyphId(2), 16), None);
+
+        // Record size is too small for 3 glyphs.
+        assert!(Table::parse(NonZeroU16::new(3).unwrap(), &data).is_err());
+    }
+
+    #[test]
+    fn padded_records() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(2), // number of records
+            UInt32(4), // record size
+            // Record [0]
+            UInt8(12), // pixel size
+            UInt8(5), // max width
+            UInt8(5), // width [0]
+            UInt8(0), // padding
+            // Record [1]
+            UInt8(16), // pixel size
+            UInt8(7), // max width
+            UInt8(7), // width [0]
+            UInt8(0), // padding
+        ]);
+
+        let table = Table::parse(NonZeroU16::new(1).unwrap(), &data).unwrap();
+        assert_eq!(table.glyph_advance(GlyphId(0), 12), Some(5));
+        assert_eq!(table.glyph_advance(GlyphId(1), 12), None);
+        assert_eq!(table.glyph_advance(GlyphId(2), 12), None);
+        assert_eq!(table.glyph_advance(GlyphId(0), 16), Some(7));
+        assert_eq!(table.glyph_advance(GlyphId(1), 16), None);
+    }
+}
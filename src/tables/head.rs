@@ -1,14 +1,71 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/head
 
 use crate::Rect;
-use crate::parser::Stream;
+use crate::parser::{Stream, Fixed};
 
 
 const TABLE_SIZE: usize = 54;
+const FONT_REVISION_OFFSET: usize = 4;
+#[cfg(feature = "std")]
+const CHECK_SUM_ADJUSTMENT_OFFSET: usize = 8;
+const FLAGS_OFFSET: usize = 16;
 const UNITS_PER_EM_OFFSET: usize = 18;
+const CREATED_OFFSET: usize = 20;
+const MODIFIED_OFFSET: usize = 28;
 const BBOX_OFFSET: usize = 36;
+const MAC_STYLE_OFFSET: usize = 44;
+const LOWEST_REC_PPEM_OFFSET: usize = 46;
+const FONT_DIRECTION_HINT_OFFSET: usize = 48;
 const INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
 
+// The number of seconds between 1904-01-01 and 1970-01-01.
+const MAC_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+
+/// A [`head` table flags](https://docs.microsoft.com/en-us/typography/opentype/spec/head).
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct HeadFlags(pub u16);
+
+impl HeadFlags {
+    /// Checks that the baseline for the font is at `y = 0`.
+    #[inline] pub fn is_baseline_at_zero(self) -> bool { self.0 & (1 << 0) != 0 }
+    /// Checks that the left sidebearing point is at `x = 0`.
+    ///
+    /// Relevant only for TrueType rasterizers.
+    #[inline] pub fn is_lsb_at_zero(self) -> bool { self.0 & (1 << 1) != 0 }
+    /// Checks that instructions may depend on point size.
+    #[inline] pub fn instructions_depend_on_point_size(self) -> bool { self.0 & (1 << 2) != 0 }
+    /// Checks that scaled point size and ppem should be forced to integer values.
+    #[inline] pub fn force_integer_ppem(self) -> bool { self.0 & (1 << 3) != 0 }
+    /// Checks that instructions may alter advance width.
+    #[inline] pub fn instructions_alter_advance_width(self) -> bool { self.0 & (1 << 4) != 0 }
+    /// Checks that the font data is lossless as a result of
+    /// an optimizing transformation, like the one in WOFF2.
+    #[inline] pub fn is_lossless(self) -> bool { self.0 & (1 << 11) != 0 }
+    /// Checks that the font was converted and produces compatible metrics.
+    #[inline] pub fn is_converted(self) -> bool { self.0 & (1 << 12) != 0 }
+    /// Checks that the font is optimized for ClearType.
+    #[inline] pub fn is_cleartype_optimized(self) -> bool { self.0 & (1 << 13) != 0 }
+    /// Checks that the font is a *Last Resort* font.
+    #[inline] pub fn is_last_resort(self) -> bool { self.0 & (1 << 14) != 0 }
+}
+
+
+/// A [`head` table macStyle](https://docs.microsoft.com/en-us/typography/opentype/spec/head).
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct MacStyle(pub u16);
+
+#[allow(missing_docs)]
+impl MacStyle {
+    #[inline] pub fn is_bold(self) -> bool { self.0 & (1 << 0) != 0 }
+    #[inline] pub fn is_italic(self) -> bool { self.0 & (1 << 1) != 0 }
+    #[inline] pub fn is_underline(self) -> bool { self.0 & (1 << 2) != 0 }
+    #[inline] pub fn is_outline(self) -> bool { self.0 & (1 << 3) != 0 }
+    #[inline] pub fn is_shadow(self) -> bool { self.0 & (1 << 4) != 0 }
+    #[inline] pub fn is_condensed(self) -> bool { self.0 & (1 << 5) != 0 }
+    #[inline] pub fn is_extended(self) -> bool { self.0 & (1 << 6) != 0 }
+}
+
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub(crate) enum IndexToLocationFormat {
@@ -25,6 +82,17 @@ pub fn parse(data: &[u8]) -> Option<&[u8]> {
     }
 }
 
+#[inline]
+pub fn font_revision(data: &[u8]) -> Option<f32> {
+    Stream::read_at::<Fixed>(data, FONT_REVISION_OFFSET).map(|v| v.0)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+pub fn check_sum_adjustment(data: &[u8]) -> Option<u32> {
+    Stream::read_at(data, CHECK_SUM_ADJUSTMENT_OFFSET)
+}
+
 #[inline]
 pub fn units_per_em(data: &[u8]) -> Option<u16> {
     let num: u16 = Stream::read_at(data, UNITS_PER_EM_OFFSET)?;
@@ -46,6 +114,46 @@ pub fn global_bbox(data: &[u8]) -> Option<Rect> {
     })
 }
 
+#[inline]
+pub fn flags(data: &[u8]) -> HeadFlags {
+    HeadFlags(Stream::read_at::<u16>(data, FLAGS_OFFSET).unwrap_or(0))
+}
+
+#[inline]
+pub fn created(data: &[u8]) -> Option<i64> {
+    timestamp(data, CREATED_OFFSET)
+}
+
+#[inline]
+pub fn modified(data: &[u8]) -> Option<i64> {
+    timestamp(data, MODIFIED_OFFSET)
+}
+
+// Converts a `LONGDATETIME` into a Unix timestamp.
+#[inline]
+fn timestamp(data: &[u8], offset: usize) -> Option<i64> {
+    let mut s = Stream::new_at(data, offset)?;
+    let high: i32 = s.read()?;
+    let low: u32 = s.read()?;
+    let n = (i64::from(high) << 32) | i64::from(low);
+    n.checked_sub(MAC_EPOCH_OFFSET)
+}
+
+#[inline]
+pub fn mac_style(data: &[u8]) -> MacStyle {
+    MacStyle(Stream::read_at::<u16>(data, MAC_STYLE_OFFSET).unwrap_or(0))
+}
+
+#[inline]
+pub fn lowest_rec_ppem(data: &[u8]) -> u16 {
+    Stream::read_at::<u16>(data, LOWEST_REC_PPEM_OFFSET).unwrap_or(0)
+}
+
+#[inline]
+pub fn font_direction_hint(data: &[u8]) -> i16 {
+    Stream::read_at::<i16>(data, FONT_DIRECTION_HINT_OFFSET).unwrap_or(0)
+}
+
 #[inline]
 pub(crate) fn index_to_loc_format(data: &[u8]) -> Option<IndexToLocationFormat> {
     let format: i16 = Stream::read_at(data, INDEX_TO_LOC_FORMAT_OFFSET)?;
@@ -55,3 +163,56 @@ pub(crate) fn index_to_loc_format(data: &[u8]) -> Option<IndexToLocationFormat>
         _ => None,
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn basic() {
+        let data = convert(&[
+            UInt32(0x00010000), // version
+            UInt32(0x00028000), // font revision: 2.5
+            UInt32(0), // checksum adjustment
+            UInt32(0x5F0F3CF5), // magic number
+            UInt16(0x0809), // flags
+            UInt16(1000), // units per em
+            UInt32(0), // created (high)
+            UInt32(2_082_844_800), // created (low): 1970-01-01
+            UInt32(0), // modified (high)
+            UInt32(3_692_304_000), // modified (low): 2021-01-01
+            Int16(-100), // x min
+            Int16(-200), // y min
+            Int16(1000), // x max
+            Int16(900), // y max
+            UInt16(0x0003), // mac style
+            UInt16(8), // lowest rec ppem
+            Int16(2), // font direction hint
+            Int16(0), // index to location format
+            Int16(0), // glyph data format
+        ]);
+
+        let data = parse(&data).unwrap();
+        assert_eq!(font_revision(data), Some(2.5));
+        assert_eq!(created(data), Some(0));
+        assert_eq!(modified(data), Some(1_609_459_200));
+
+        let flags = flags(data);
+        assert!(flags.is_baseline_at_zero());
+        assert!(!flags.is_lsb_at_zero());
+        assert!(flags.force_integer_ppem());
+        assert!(flags.is_lossless());
+
+        let style = mac_style(data);
+        assert!(style.is_bold());
+        assert!(style.is_italic());
+        assert!(!style.is_underline());
+
+        assert_eq!(lowest_rec_ppem(data), 8);
+        assert_eq!(font_direction_hint(data), 2);
+        assert_eq!(index_to_loc_format(data), Some(IndexToLocationFormat::Short));
+    }
+}
@@ -5,6 +5,7 @@ use core::convert::TryFrom;
 use crate::{GlyphId, NormalizedCoordinate};
 use crate::parser::{Stream, Offset, Offset32};
 use crate::var_store::ItemVariationStore;
+use crate::TableParsingError;
 
 #[derive(Clone, Copy)]
 pub struct Table<'a> {
@@ -15,14 +16,18 @@ pub struct Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
 
-        let version: u32 = s.read()?;
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
         if version != 0x00010000 {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
         }
 
+        Self::parse_impl(data, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], mut s: Stream<'a>) -> Option<Self> {
         let variation_store_offset: Offset32 = s.read()?;
         let var_store_s = Stream::new_at(data, variation_store_offset.to_usize())?;
         let variation_store = ItemVariationStore::parse(var_store_s)?;
@@ -0,0 +1,982 @@
+/*!
+An [Extended Kerning Table](
+https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html) implementation.
+
+Just like with `kern`, there is no single correct way to process a kerning data,
+so we provide an access to subtables and a caller must implement
+a kerning algorithm manually.
+*/
+
+use core::num::NonZeroU16;
+
+use crate::GlyphId;
+use crate::{aat, ankr, loca};
+use crate::glyf::GlyphOutlines;
+use crate::parser::{Stream, FromData, NumFrom, Offset32, Offset};
+use crate::TableParsingError;
+
+pub use crate::ankr::Point as AnchorPoint;
+
+const HEADER_SIZE: usize = 12;
+
+
+#[derive(Clone, Copy, Debug)]
+struct Coverage(u8);
+
+impl Coverage {
+    #[inline]
+    fn is_horizontal(self) -> bool {
+        self.0 & (1 << 7) == 0
+    }
+
+    #[inline]
+    fn has_cross_stream(self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+
+    #[inline]
+    fn is_variable(self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct KerningRecord {
+    // Just like in `kern`, a pair is stored as one u32, so we can binary search it directly.
+    pair: u32,
+    value: i16,
+}
+
+impl FromData for KerningRecord {
+    const SIZE: usize = 6;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(KerningRecord {
+            pair: s.read::<u32>()?,
+            value: s.read::<i16>()?,
+        })
+    }
+}
+
+
+/// An extended kerning subtable.
+#[derive(Clone, Copy)]
+pub struct Subtable<'a> {
+    is_horizontal: bool,
+    is_variable: bool,
+    has_cross_stream: bool,
+    format: u8,
+    tuple_count: u32,
+    data: &'a [u8], // Without the header.
+    number_of_glyphs: NonZeroU16,
+    ankr: Option<ankr::Table<'a>>,
+    outlines: Option<GlyphOutlines<'a>>,
+}
+
+impl<'a> Subtable<'a> {
+    /// Checks that subtable is for horizontal text.
+    #[inline]
+    pub fn is_horizontal(&self) -> bool {
+        self.is_horizontal
+    }
+
+    /// Checks that subtable is variable.
+    #[inline]
+    pub fn is_variable(&self) -> bool {
+        self.is_variable
+    }
+
+    /// Checks that subtable has a cross-stream values.
+    #[inline]
+    pub fn has_cross_stream(&self) -> bool {
+        self.has_cross_stream
+    }
+
+    /// Returns subtable's format.
+    ///
+    /// Formats 0, 1, 2, 4 and 6 are supported.
+    #[inline]
+    pub fn format(&self) -> u8 {
+        self.format
+    }
+
+    /// Returns the number of variation tuples.
+    ///
+    /// Zero for non-variable fonts.
+    #[inline]
+    pub fn tuple_count(&self) -> u32 {
+        self.tuple_count
+    }
+
+    /// Checks that subtable uses a state machine.
+    ///
+    /// In this case `glyphs_kerning()` will return `None` and you have to use
+    /// `state_machine()` instead.
+    #[inline]
+    pub fn has_state_machine(&self) -> bool {
+        self.format == 1 || self.format == 4
+    }
+
+    /// Returns kerning for a pair of glyphs.
+    ///
+    /// Returns `None` in case of state machine based subtable.
+    #[inline]
+    pub fn glyphs_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        match self.format {
+            0 => parse_format0(self.data, left, right),
+            2 => parse_format2(self.data, self.number_of_glyphs, left, right),
+            6 => parse_format6(self.data, self.number_of_glyphs, self.tuple_count, left, right),
+            _ => None,
+        }
+    }
+
+    /// Returns subtable's state machine if there is one.
+    #[inline]
+    pub fn state_machine(&self) -> Option<state_machine::Machine<'a>> {
+        if !self.has_state_machine() {
+            return None;
+        }
+
+        state_machine::Machine::parse(self)
+    }
+}
+
+impl core::fmt::Debug for Subtable<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        // TODO: finish_non_exhaustive
+        f.debug_struct("Subtable")
+            .field("is_horizontal", &self.is_horizontal())
+            .field("is_variable", &self.is_variable())
+            .field("has_state_machine", &self.has_state_machine())
+            .field("has_cross_stream", &self.has_cross_stream())
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+
+/// An iterator over extended kerning subtables.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct Subtables<'a> {
+    /// The current table index,
+    table_index: u32,
+    /// The total number of tables.
+    number_of_tables: u32,
+    /// Actual data. Starts right after `kerx` header.
+    stream: Stream<'a>,
+    number_of_glyphs: NonZeroU16,
+    ankr: Option<ankr::Table<'a>>,
+    outlines: Option<GlyphOutlines<'a>>,
+}
+
+impl Default for Subtables<'_> {
+    #[inline]
+    fn default() -> Self {
+        Subtables {
+            table_index: 0,
+            number_of_tables: 0,
+            stream: Stream::default(),
+            number_of_glyphs: NonZeroU16::new(1).unwrap(),
+            ankr: None,
+            outlines: None,
+        }
+    }
+}
+
+impl<'a> Iterator for Subtables<'a> {
+    type Item = Subtable<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.table_index == self.number_of_tables {
+            return None;
+        }
+
+        if self.stream.at_end() {
+            return None;
+        }
+
+        let table_len: u32 = self.stream.read()?;
+        let coverage: Coverage = Coverage(self.stream.read::<u8>()?);
+        self.stream.advance(2); // unused
+        let format: u8 = self.stream.read()?;
+        let tuple_count: u32 = self.stream.read()?;
+
+        // Subtract the header size.
+        let data_len = usize::num_from(table_len).checked_sub(HEADER_SIZE)?;
+        let data = self.stream.read_bytes(data_len)?;
+
+        self.table_index += 1;
+
+        match format {
+            0 | 1 | 2 | 4 | 6 => {}
+            _ => return None, // Unknown format.
+        }
+
+        Some(Subtable {
+            is_horizontal: coverage.is_horizontal(),
+            is_variable: coverage.is_variable(),
+            has_cross_stream: coverage.has_cross_stream(),
+            format,
+            tuple_count,
+            data,
+            number_of_glyphs: self.number_of_glyphs,
+            ankr: self.ankr,
+            outlines: self.outlines,
+        })
+    }
+}
+
+pub(crate) fn parse<'a>(
+    data: &'a [u8],
+    number_of_glyphs: NonZeroU16,
+    ankr: Option<ankr::Table<'a>>,
+    loca: Option<loca::Table<'a>>,
+    glyf: Option<&'a [u8]>,
+) -> Result<Subtables<'a>, TableParsingError> {
+    let mut s = Stream::new(data);
+    let version: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+    if !(2..=4).contains(&version) {
+        return Err(TableParsingError::UnsupportedVersion);
+    }
+
+    parse_impl(number_of_glyphs, ankr, loca, glyf, s).ok_or(TableParsingError::Malformed)
+}
+
+fn parse_impl<'a>(
+    number_of_glyphs: NonZeroU16,
+    ankr: Option<ankr::Table<'a>>,
+    loca: Option<loca::Table<'a>>,
+    glyf: Option<&'a [u8]>,
+    mut s: Stream<'a>,
+) -> Option<Subtables<'a>> {
+    s.skip::<u16>(); // padding
+    let number_of_tables: u32 = s.read()?;
+
+    Some(Subtables {
+        table_index: 0,
+        number_of_tables,
+        stream: s,
+        number_of_glyphs,
+        ankr,
+        outlines: GlyphOutlines::new(loca, glyf),
+    })
+}
+
+/// A *Format 0 Kerning Subtable (Ordered List of Kerning Pairs)* implementation
+/// from https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html
+fn parse_format0(data: &[u8], left: GlyphId, right: GlyphId) -> Option<i16> {
+    let mut s = Stream::new(data);
+    let number_of_pairs: u32 = s.read()?;
+    s.advance(12); // search_range (u32) + entry_selector (u32) + range_shift (u32)
+    let pairs = s.read_array32::<KerningRecord>(number_of_pairs)?;
+
+    let needle = u32::from(left.0) << 16 | u32::from(right.0);
+    pairs.binary_search_by(|v| v.pair.cmp(&needle)).map(|(_, v)| v.value)
+}
+
+/// A *Format 2 Kerning Table (Simple n x m Array of Kerning Values)* implementation
+/// from https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html
+fn parse_format2(
+    data: &[u8],
+    number_of_glyphs: NonZeroU16,
+    left: GlyphId,
+    right: GlyphId,
+) -> Option<i16> {
+    let mut s = Stream::new(data);
+    s.skip::<u32>(); // row_width
+
+    // Offsets are from beginning of the subtable and not from the `data` start,
+    // so we have to subtract the header.
+    let left_hand_table_offset = s.read::<Offset32>()?.to_usize().checked_sub(HEADER_SIZE)?;
+    let right_hand_table_offset = s.read::<Offset32>()?.to_usize().checked_sub(HEADER_SIZE)?;
+    let array_offset = s.read::<Offset32>()?.to_usize().checked_sub(HEADER_SIZE)?;
+
+    let left_classes = aat::Lookup::parse(number_of_glyphs, data.get(left_hand_table_offset..)?)?;
+    let right_classes = aat::Lookup::parse(number_of_glyphs, data.get(right_hand_table_offset..)?)?;
+
+    // Unlike `kern`, classes are indices and not byte offsets.
+    let left_class = left_classes.value(left).unwrap_or(0);
+    let right_class = right_classes.value(right).unwrap_or(0);
+    let index = usize::from(left_class) + usize::from(right_class);
+    Stream::read_at::<i16>(data, array_offset + index * i16::SIZE)
+}
+
+/// A *Format 6 Kerning Table (Simple Index-based n x m Array of Kerning Values)* implementation
+/// from https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html
+fn parse_format6(
+    data: &[u8],
+    number_of_glyphs: NonZeroU16,
+    tuple_count: u32,
+    left: GlyphId,
+    right: GlyphId,
+) -> Option<i16> {
+    let mut s = Stream::new(data);
+    let flags: u32 = s.read()?;
+    s.skip::<u16>(); // row_count
+    s.skip::<u16>(); // column_count
+
+    // Offsets are from beginning of the subtable and not from the `data` start,
+    // so we have to subtract the header.
+    let row_index_table_offset = s.read::<Offset32>()?.to_usize().checked_sub(HEADER_SIZE)?;
+    let column_index_table_offset = s.read::<Offset32>()?.to_usize().checked_sub(HEADER_SIZE)?;
+    let array_offset = s.read::<Offset32>()?.to_usize().checked_sub(HEADER_SIZE)?;
+
+    let rows = aat::Lookup::parse(number_of_glyphs, data.get(row_index_table_offset..)?)?;
+    let columns = aat::Lookup::parse(number_of_glyphs, data.get(column_index_table_offset..)?)?;
+
+    let values_are_long = flags & 0x00000001 != 0;
+    let value = if values_are_long {
+        let row = rows.value32(left).unwrap_or(0);
+        let column = columns.value32(right).unwrap_or(0);
+        let index = usize::num_from(row.checked_add(column)?);
+        let value: i32 = Stream::read_at(data, array_offset + index * i32::SIZE)?;
+        core::convert::TryFrom::try_from(value).ok()?
+    } else {
+        let row = rows.value(left).unwrap_or(0);
+        let column = columns.value(right).unwrap_or(0);
+        let index = usize::from(row) + usize::from(column);
+        Stream::read_at::<i16>(data, array_offset + index * i16::SIZE)?
+    };
+
+    if tuple_count == 0 {
+        Some(value)
+    } else {
+        // In variable fonts, the value is an offset into the kerning vector
+        // and we are using the default value from it.
+        let vector_offset = s.read::<Offset32>()?.to_usize().checked_sub(HEADER_SIZE)?;
+        let offset = usize::from(value as u16);
+        Stream::read_at::<i16>(data, vector_offset + offset)
+    }
+}
+
+/// An [Extended State Table](
+/// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html)
+/// implementation for formats 1 and 4.
+pub mod state_machine {
+    use super::*;
+
+    pub use crate::aat::class;
+
+
+    /// A type-safe wrapper for a state machine state.
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct State(u16);
+
+    /// Predefined states.
+    pub mod state {
+        #![allow(missing_docs)]
+
+        use super::State;
+
+        pub const START_OF_TEXT: State = State(0);
+        pub const START_OF_LINE: State = State(1);
+    }
+
+
+    /// A state machine entry.
+    #[derive(Clone, Copy, Debug)]
+    pub struct Entry {
+        new_state: u16,
+        flags: u16,
+        action_index: u16,
+    }
+
+    impl Entry {
+        /// Returns a new state.
+        #[inline]
+        pub fn new_state(&self) -> State {
+            State(self.new_state)
+        }
+
+        /// If set, push this glyph on the kerning stack.
+        ///
+        /// Used only by format 1.
+        #[inline]
+        pub fn has_push(&self) -> bool {
+            self.flags & 0x8000 != 0
+        }
+
+        /// If set, remember this glyph as the marked glyph.
+        ///
+        /// Used only by format 4.
+        #[inline]
+        pub fn has_mark(&self) -> bool {
+            self.flags & 0x8000 != 0
+        }
+
+        /// If set, advance to the next glyph before going to the new state.
+        #[inline]
+        pub fn has_advance(&self) -> bool {
+            self.flags & 0x4000 == 0
+        }
+
+        /// If set, reset the cross-stream kerning.
+        ///
+        /// Used only by format 1.
+        #[inline]
+        pub fn has_reset(&self) -> bool {
+            self.flags & 0x2000 != 0
+        }
+
+        /// Checks that entry has an action.
+        #[inline]
+        pub fn has_action(&self) -> bool {
+            self.action_index != 0xFFFF
+        }
+    }
+
+
+    /// An attachment action type.
+    ///
+    /// Used only by format 4.
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub enum ActionType {
+        /// Control points from the `glyf` table.
+        ControlPoints,
+        /// Anchor points from the `ankr` table.
+        AnchorPoints,
+        /// Explicit control point coordinates.
+        ControlPointCoordinates,
+    }
+
+
+    /// A state machine.
+    #[derive(Clone, Copy)]
+    pub struct Machine<'a> {
+        format: u8,
+        table: aat::ExtendedStateTable<'a, u16>,
+        // Format 1: the kerning values array.
+        // Format 4: the control point actions array.
+        actions: &'a [u8],
+        action_type: Option<ActionType>,
+        tuple_count: u32,
+        ankr: Option<ankr::Table<'a>>,
+        outlines: Option<GlyphOutlines<'a>>,
+    }
+
+    impl<'a> Machine<'a> {
+        pub(crate) fn parse(subtable: &Subtable<'a>) -> Option<Self> {
+            let data = subtable.data;
+            let table = aat::ExtendedStateTable::parse(subtable.number_of_glyphs, data)?;
+
+            // The state table header has 4 u32 values.
+            let mut s = Stream::new_at(data, 16)?;
+            let (actions_offset, action_type) = if subtable.format == 1 {
+                // An offset from the start of the state table.
+                (s.read::<Offset32>()?.to_usize(), None)
+            } else {
+                let flags: u32 = s.read()?;
+                let action_type = match (flags & 0xC0000000) >> 30 {
+                    0 => ActionType::ControlPoints,
+                    1 => ActionType::AnchorPoints,
+                    2 => ActionType::ControlPointCoordinates,
+                    _ => return None,
+                };
+
+                (usize::num_from(flags & 0x00FFFFFF), Some(action_type))
+            };
+
+            Some(Machine {
+                format: subtable.format,
+                table,
+                actions: data.get(actions_offset..)?,
+                action_type,
+                tuple_count: subtable.tuple_count,
+                ankr: subtable.ankr,
+                outlines: subtable.outlines,
+            })
+        }
+
+        /// Returns a glyph class.
+        #[inline]
+        pub fn class(&self, glyph_id: GlyphId) -> Option<u16> {
+            self.table.class(glyph_id)
+        }
+
+        /// Returns a class entry.
+        #[inline]
+        pub fn entry(&self, state: State, class: u16) -> Option<Entry> {
+            let entry = self.table.entry(state.0, class)?;
+            Some(Entry {
+                new_state: entry.new_state,
+                flags: entry.flags,
+                action_index: entry.extra,
+            })
+        }
+
+        /// Returns an attachment action type.
+        ///
+        /// Returns `None` for format 1.
+        #[inline]
+        pub fn action_type(&self) -> Option<ActionType> {
+            self.action_type
+        }
+
+        /// Returns an iterator over kerning values of the entry's action.
+        ///
+        /// Each value should be applied to a glyph popped from the kerning stack.
+        /// Values are returned as is, so the last one still has the lowest bit set.
+        ///
+        /// Returns `None` for format 4 or when entry has no action.
+        pub fn kerning_values(&self, entry: Entry) -> Option<KerningValues<'a>> {
+            if self.format != 1 || !entry.has_action() {
+                return None;
+            }
+
+            // The action index points to the first value and not to a tuple.
+            // When a font is variable, each value is followed by `tuple_count - 1` values.
+            // We are using only the default one.
+            let stride = usize::num_from(self.tuple_count.max(1)) * i16::SIZE;
+            let offset = usize::from(entry.action_index) * i16::SIZE;
+            Some(KerningValues {
+                stream: Stream::new_at(self.actions, offset)?,
+                stride,
+                finished: false,
+            })
+        }
+
+        /// Returns an offset that should be applied to the current glyph
+        /// to attach it to the marked one.
+        ///
+        /// `ActionType::ControlPoints` requires the `glyf` table
+        /// and `ActionType::AnchorPoints` requires the `ankr` table.
+        ///
+        /// Returns `None` for format 1 or when entry has no action.
+        pub fn attachment(
+            &self,
+            entry: Entry,
+            marked: GlyphId,
+            current: GlyphId,
+        ) -> Option<AnchorPoint> {
+            if !entry.has_action() {
+                return None;
+            }
+
+            let (mark_point, current_point) = match self.action_type? {
+                ActionType::ControlPoints => {
+                    let mut s = Stream::new_at(self.actions, usize::from(entry.action_index) * 4)?;
+                    let mark_index: u16 = s.read()?;
+                    let current_index: u16 = s.read()?;
+
+                    let outlines = self.outlines?;
+                    let mark = outlines.point(marked, mark_index)?;
+                    let current = outlines.point(current, current_index)?;
+                    (AnchorPoint { x: mark.0, y: mark.1 }, AnchorPoint { x: current.0, y: current.1 })
+                }
+                ActionType::AnchorPoints => {
+                    let mut s = Stream::new_at(self.actions, usize::from(entry.action_index) * 4)?;
+                    let mark_index: u16 = s.read()?;
+                    let current_index: u16 = s.read()?;
+
+                    let ankr = self.ankr?;
+                    (ankr.point(marked, mark_index)?, ankr.point(current, current_index)?)
+                }
+                ActionType::ControlPointCoordinates => {
+                    let mut s = Stream::new_at(self.actions, usize::from(entry.action_index) * 8)?;
+                    (s.read::<AnchorPoint>()?, s.read::<AnchorPoint>()?)
+                }
+            };
+
+            Some(AnchorPoint {
+                x: mark_point.x.checked_sub(current_point.x)?,
+                y: mark_point.y.checked_sub(current_point.y)?,
+            })
+        }
+    }
+
+    impl core::fmt::Debug for Machine<'_> {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.write_str("Machine(...)")
+        }
+    }
+
+
+    /// An iterator over kerning values.
+    ///
+    /// The last value is the first one with the lowest bit set.
+    #[derive(Clone, Copy)]
+    #[allow(missing_debug_implementations)]
+    pub struct KerningValues<'a> {
+        stream: Stream<'a>,
+        stride: usize,
+        finished: bool,
+    }
+
+    impl<'a> Iterator for KerningValues<'a> {
+        type Item = i16;
+
+        #[inline]
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.finished {
+                return None;
+            }
+
+            let value: i16 = Stream::read_at(self.stream.tail()?, 0)?;
+            self.stream.advance(self.stride);
+            // 'The end of the list is marked by an odd value.'
+            self.finished = value & 1 != 0;
+            Some(value)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::state_machine::*;
+    use std::vec::Vec;
+    use crate::IndexToLocationFormat;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    fn subtables(data: &[u8]) -> Subtables {
+        parse(data, NonZeroU16::new(10).unwrap(), None, None, None).unwrap()
+    }
+
+    // A state machine that marks or pushes glyph 1 and applies action 0 to the next glyph 1.
+    fn state_machine_subtable(format: u8, flags: u32, actions: &[u8]) -> Vec<u8> {
+        variable_state_machine_subtable(format, 0, 0, flags, actions)
+    }
+
+    fn variable_state_machine_subtable(
+        format: u8,
+        tuple_count: u32,
+        action_index: u16,
+        flags: u32,
+        actions: &[u8],
+    ) -> Vec<u8> {
+        let mut data = convert(&[
+            UInt16(2), // version
+            UInt16(0), // padding
+            UInt32(1), // number of subtables
+            // Subtable
+            UInt32((12 + 66 + actions.len()) as u32), // length
+            UInt8(0), UInt16(0), UInt8(format), // coverage: horizontal
+            UInt32(tuple_count), // tuple count
+            // State table header
+            UInt32(5), // number of classes
+            UInt32(20), // class table offset
+            UInt32(28), // state array offset
+            UInt32(48), // entry table offset
+            UInt32(flags), // format 1: values offset, format 4: flags
+            // Class table
+            UInt16(8), // format: trimmed array
+            UInt16(1), // first glyph
+            UInt16(1), // glyph count
+            UInt16(4), // class [0]
+            // State array
+            UInt16(0), UInt16(0), UInt16(0), UInt16(0), UInt16(1), // state [0]
+            UInt16(0), UInt16(0), UInt16(0), UInt16(0), UInt16(2), // state [1]
+            // Entry [0]
+            UInt16(0), // new state
+            UInt16(0), // flags
+            UInt16(0xFFFF), // action index: none
+            // Entry [1]
+            UInt16(1), // new state
+            UInt16(0x8000), // flags: push/mark
+            UInt16(0xFFFF), // action index: none
+            // Entry [2]
+            UInt16(0), // new state
+            UInt16(0x8000), // flags: push/mark
+            UInt16(action_index), // action index
+        ]);
+
+        data.extend_from_slice(actions);
+        data
+    }
+
+    #[test]
+    fn format1() {
+        let values = convert(&[
+            Int16(10), // value [0]
+            Int16(-31), // value [1], odd, so it's the last one
+        ]);
+        let data = state_machine_subtable(1, 66, &values);
+
+        let mut subtables = subtables(&data);
+        let subtable = subtables.next().unwrap();
+        assert_eq!(subtable.format(), 1);
+        assert!(subtable.has_state_machine());
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(1)), None);
+
+        let machine = subtable.state_machine().unwrap();
+        assert_eq!(machine.action_type(), None);
+        assert_eq!(machine.class(GlyphId(1)), Some(4));
+        assert_eq!(machine.class(GlyphId(2)), None);
+        assert_eq!(machine.class(GlyphId(0xFFFF)), Some(class::DELETED_GLYPH));
+
+        let entry = machine.entry(state::START_OF_TEXT, 4).unwrap();
+        assert_eq!(entry.new_state(), state::START_OF_LINE);
+        assert!(entry.has_push());
+        assert!(entry.has_advance());
+        assert!(!entry.has_reset());
+        assert!(!entry.has_action());
+        assert!(machine.kerning_values(entry).is_none());
+
+        let entry = machine.entry(entry.new_state(), 4).unwrap();
+        assert_eq!(entry.new_state(), state::START_OF_TEXT);
+        assert!(entry.has_action());
+        let values: Vec<i16> = machine.kerning_values(entry).unwrap().collect();
+        assert_eq!(values, [10, -31]);
+        assert_eq!(machine.attachment(entry, GlyphId(1), GlyphId(1)), None);
+
+        // An unknown class is treated as out of bounds.
+        let entry = machine.entry(state::START_OF_LINE, 10).unwrap();
+        assert!(!entry.has_action());
+    }
+
+    #[test]
+    fn format1_variable() {
+        let values = convert(&[
+            Int16(1), Int16(2), // unused value
+            Int16(10), Int16(11), // value [2]
+            Int16(-31), Int16(-32), // value [4], odd, so it's the last one
+        ]);
+        // The action index is in values and not in tuples.
+        let data = variable_state_machine_subtable(1, 2, 2, 66, &values);
+
+        let subtable = subtables(&data).next().unwrap();
+        let machine = subtable.state_machine().unwrap();
+        let entry = machine.entry(state::START_OF_LINE, 4).unwrap();
+        assert!(entry.has_action());
+        let values: Vec<i16> = machine.kerning_values(entry).unwrap().collect();
+        assert_eq!(values, [10, -31]);
+    }
+
+    #[test]
+    fn format4_control_point_coordinates() {
+        let actions = convert(&[
+            Int16(100), Int16(200), // marked glyph point
+            Int16(30), Int16(50), // current glyph point
+        ]);
+        let data = state_machine_subtable(4, 0x80000000 | 66, &actions);
+
+        let subtable = subtables(&data).next().unwrap();
+        let machine = subtable.state_machine().unwrap();
+        assert_eq!(machine.action_type(), Some(ActionType::ControlPointCoordinates));
+
+        let entry = machine.entry(state::START_OF_TEXT, 4).unwrap();
+        assert!(entry.has_mark());
+        assert!(!entry.has_action());
+        assert_eq!(machine.attachment(entry, GlyphId(1), GlyphId(1)), None);
+
+        let entry = machine.entry(state::START_OF_LINE, 4).unwrap();
+        assert!(machine.kerning_values(entry).is_none());
+        assert_eq!(machine.attachment(entry, GlyphId(1), GlyphId(1)),
+                   Some(AnchorPoint { x: 70, y: 150 }));
+    }
+
+    #[test]
+    fn format4_anchor_points() {
+        let actions = convert(&[
+            UInt16(0), // marked glyph anchor index
+            UInt16(1), // current glyph anchor index
+        ]);
+        let data = state_machine_subtable(4, 0x40000000 | 66, &actions);
+        let number_of_glyphs = NonZeroU16::new(3).unwrap();
+
+        let ankr_data = crate::ankr::tests::table_data();
+        let ankr_table = ankr::Table::parse(number_of_glyphs, &ankr_data).unwrap();
+        let mut subtables = parse(&data, number_of_glyphs, Some(ankr_table), None, None).unwrap();
+        let machine = subtables.next().unwrap().state_machine().unwrap();
+        assert_eq!(machine.action_type(), Some(ActionType::AnchorPoints));
+
+        let entry = machine.entry(state::START_OF_LINE, 4).unwrap();
+        assert_eq!(machine.attachment(entry, GlyphId(1), GlyphId(2)),
+                   Some(AnchorPoint { x: 70, y: 150 }));
+        // Glyph 1 has only one anchor point.
+        assert_eq!(machine.attachment(entry, GlyphId(2), GlyphId(1)), None);
+
+        // Anchor points require the `ankr` table.
+        let mut subtables = parse(&data, number_of_glyphs, None, None, None).unwrap();
+        let machine = subtables.next().unwrap().state_machine().unwrap();
+        assert_eq!(machine.attachment(entry, GlyphId(1), GlyphId(2)), None);
+    }
+
+    #[test]
+    fn format4_control_points() {
+        let actions = convert(&[
+            UInt16(2), // marked glyph point index
+            UInt16(1), // current glyph point index
+        ]);
+        let data = state_machine_subtable(4, 66, &actions);
+        let number_of_glyphs = NonZeroU16::new(3).unwrap();
+
+        let glyph = |x: [i16; 3], y: [i16; 3]| convert(&[
+            Int16(1), // number of contours
+            Int16(x[0]), // x min
+            Int16(y[0]), // y min
+            Int16(x[1]), // x max
+            Int16(y[2]), // y max
+            UInt16(2), // end point [0]
+            UInt16(0), // instructions length
+            UInt8(1), UInt8(1), UInt8(1), // flags: on curve
+            Int16(x[0]), Int16(x[1] - x[0]), Int16(x[2] - x[1]), // x coordinates
+            Int16(y[0]), Int16(y[1] - y[0]), Int16(y[2] - y[1]), // y coordinates
+            UInt8(0), // padding
+        ]);
+
+        let mut glyf_data = glyph([100, 400, 250], [0, 0, 500]);
+        glyf_data.extend_from_slice(&glyph([0, 50, 25], [0, 0, 60]));
+
+        let loca_data = convert(&[
+            UInt16(0), // offset [0]
+            UInt16(0), // offset [1]
+            UInt16(15), // offset [2]
+            UInt16(30), // offset [3]
+        ]);
+        let loca_table = loca::Table::parse(
+            &loca_data, number_of_glyphs, IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let mut subtables = parse(
+            &data, number_of_glyphs, None, Some(loca_table), Some(&glyf_data),
+        ).unwrap();
+        let machine = subtables.next().unwrap().state_machine().unwrap();
+        assert_eq!(machine.action_type(), Some(ActionType::ControlPoints));
+
+        let entry = machine.entry(state::START_OF_LINE, 4).unwrap();
+        assert_eq!(machine.attachment(entry, GlyphId(1), GlyphId(2)),
+                   Some(AnchorPoint { x: 200, y: 500 }));
+        // Glyph 0 is empty.
+        assert_eq!(machine.attachment(entry, GlyphId(0), GlyphId(2)), None);
+
+        // Control points require the `glyf` table.
+        let mut subtables = parse(&data, number_of_glyphs, None, None, None).unwrap();
+        let machine = subtables.next().unwrap().state_machine().unwrap();
+        assert_eq!(machine.attachment(entry, GlyphId(1), GlyphId(2)), None);
+    }
+
+    #[test]
+    fn format6() {
+        let data = convert(&[
+            UInt16(2), // version
+            UInt16(0), // padding
+            UInt32(1), // number of subtables
+            // Subtable
+            UInt32(60), // length
+            UInt32(6), // coverage: horizontal, format 6
+            UInt32(0), // tuple count
+            UInt32(0), // flags: short values
+            UInt16(2), // row count
+            UInt16(2), // column count
+            UInt32(32), // row index table offset
+            UInt32(42), // column index table offset
+            UInt32(52), // kerning array offset
+            // Row index table
+            UInt16(8), // format: trimmed array
+            UInt16(1), // first glyph
+            UInt16(2), // glyph count
+            UInt16(0), // row [0]
+            UInt16(2), // row [1], multiplied by the column count
+            // Column index table
+            UInt16(8), // format: trimmed array
+            UInt16(3), // first glyph
+            UInt16(2), // glyph count
+            UInt16(0), // column [0]
+            UInt16(1), // column [1]
+            // Kerning array
+            Int16(-10), Int16(-20),
+            Int16(-30), Int16(-40),
+        ]);
+
+        let mut subtables = subtables(&data);
+        let subtable = subtables.next().unwrap();
+        assert_eq!(subtable.format(), 6);
+        assert!(!subtable.has_state_machine());
+        assert!(subtable.state_machine().is_none());
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(3)), Some(-10));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(4)), Some(-20));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(3)), Some(-30));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(4)), Some(-40));
+        assert!(subtables.next().is_none());
+    }
+
+    #[test]
+    fn format0() {
+        let data = &[
+            0x00, 0x02, // version: 2
+            0x00, 0x00, // padding
+            0x00, 0x00, 0x00, 0x01, // number of subtables: 1
+            // Subtable
+            0x00, 0x00, 0x00, 0x28, // length: 40
+            0x00, 0x00, 0x00, 0x00, // coverage: horizontal, format 0
+            0x00, 0x00, 0x00, 0x00, // tuple count: 0
+            0x00, 0x00, 0x00, 0x02, // number of pairs: 2
+            0x00, 0x00, 0x00, 0x0C, // search range: 12
+            0x00, 0x00, 0x00, 0x01, // entry selector: 1
+            0x00, 0x00, 0x00, 0x00, // range shift: 0
+            // Pair [0]
+            0x00, 0x01, // left: 1
+            0x00, 0x02, // right: 2
+            0xFF, 0xCE, // value: -50
+            // Pair [1]
+            0x00, 0x03, // left: 3
+            0x00, 0x04, // right: 4
+            0x00, 0x0A, // value: 10
+        ];
+
+        let mut subtables = subtables(data);
+        let subtable = subtables.next().unwrap();
+        assert!(subtable.is_horizontal());
+        assert!(!subtable.has_state_machine());
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-50));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(3), GlyphId(4)), Some(10));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(2), GlyphId(1)), None);
+        assert!(subtables.next().is_none());
+    }
+
+    #[test]
+    fn format2() {
+        let data = &[
+            0x00, 0x02, // version: 2
+            0x00, 0x00, // padding
+            0x00, 0x00, 0x00, 0x01, // number of subtables: 1
+            // Subtable
+            0x00, 0x00, 0x00, 0x30, // length: 48
+            0x80, 0x00, 0x00, 0x02, // coverage: vertical, format 2
+            0x00, 0x00, 0x00, 0x00, // tuple count: 0
+            0x00, 0x00, 0x00, 0x04, // row width: 4
+            0x00, 0x00, 0x00, 0x1C, // left class table offset: 28
+            0x00, 0x00, 0x00, 0x24, // right class table offset: 36
+            0x00, 0x00, 0x00, 0x2C, // array offset: 44
+            // Left class table
+            0x00, 0x08, // format: 8
+            0x00, 0x01, // first glyph: 1
+            0x00, 0x01, // glyph count: 1
+            0x00, 0x00, // class [0]: 0
+            // Right class table
+            0x00, 0x08, // format: 8
+            0x00, 0x02, // first glyph: 2
+            0x00, 0x01, // glyph count: 1
+            0x00, 0x01, // class [0]: 1
+            // Array
+            0x00, 0x00, // value [0]: 0
+            0xFF, 0xCE, // value [1]: -50
+        ];
+
+        let mut subtables = subtables(data);
+        let subtable = subtables.next().unwrap();
+        assert!(!subtable.is_horizontal());
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-50));
+        assert_eq!(subtable.glyphs_kerning(GlyphId(1), GlyphId(3)), Some(0));
+    }
+
+    #[test]
+    fn unknown_version() {
+        let data = &[
+            0x00, 0x01, // version: 1
+            0x00, 0x00, // padding
+            0x00, 0x00, 0x00, 0x00, // number of subtables: 0
+        ];
+
+        let table = parse(data, NonZeroU16::new(10).unwrap(), None, None, None);
+        assert_eq!(table.err(), Some(TableParsingError::UnsupportedVersion));
+    }
+}
@@ -0,0 +1,148 @@
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6lcar.html
+
+use core::num::NonZeroU16;
+
+use crate::GlyphId;
+use crate::aat;
+use crate::glyf::GlyphOutlines;
+use crate::parser::{Stream, LazyArrayIter16};
+use crate::TableParsingError;
+
+
+/// An iterator over ligature caret positions.
+///
+/// Positions are distances from the glyph's origin along the baseline, in font units.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct LigatureCarets<'a> {
+    glyph_id: GlyphId,
+    values: LazyArrayIter16<'a, i16>,
+    outlines: Option<GlyphOutlines<'a>>,
+    has_control_points: bool,
+}
+
+impl<'a> Iterator for LigatureCarets<'a> {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let value = self.values.next()?;
+            if !self.has_control_points {
+                return Some(value);
+            }
+
+            // Control points that cannot be resolved are skipped.
+            let point = if value >= 0 { value as u16 } else { continue };
+            let outlines = self.outlines?;
+            if let Some((x, _)) = outlines.point(self.glyph_id, point) {
+                return Some(x);
+            }
+        }
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    data: &'a [u8], // The whole `lcar` table. All offsets are from its start.
+    lookup: aat::Lookup<'a>,
+    has_control_points: bool,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(number_of_glyphs: NonZeroU16, data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let mut s = Stream::new(data);
+
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
+        if version != 0x00010000 {
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        let format: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+        if format > 1 {
+            return Err(TableParsingError::UnsupportedFormat);
+        }
+
+        Self::parse_impl(number_of_glyphs, data, format, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(
+        number_of_glyphs: NonZeroU16,
+        data: &'a [u8],
+        format: u16,
+        s: Stream<'a>,
+    ) -> Option<Self> {
+        Some(Table {
+            data,
+            lookup: aat::Lookup::parse(number_of_glyphs, s.tail()?)?,
+            has_control_points: format == 1,
+        })
+    }
+
+    pub fn glyph_carets(
+        &self,
+        glyph_id: GlyphId,
+        outlines: Option<GlyphOutlines<'a>>,
+    ) -> Option<LigatureCarets<'a>> {
+        let offset = self.lookup.value(glyph_id)?;
+        let mut s = Stream::new_at(self.data, usize::from(offset))?;
+        let count: u16 = s.read()?;
+        let values = s.read_array16::<i16>(count)?;
+
+        Some(LigatureCarets {
+            glyph_id,
+            values: values.into_iter(),
+            outlines,
+            has_control_points: self.has_control_points,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // format: 0
+            // Lookup table
+            0x00, 0x00, // format: 0
+            0x00, 0x0C, // offset [0]: 12
+            0x00, 0x12, // offset [1]: 18
+            // Carets [0]
+            0x00, 0x02, // count: 2
+            0x01, 0x2C, // caret [0]: 300
+            0x02, 0x58, // caret [1]: 600
+            // Carets [1]
+            0x00, 0x00, // count: 0
+        ];
+
+        let table = Table::parse(NonZeroU16::new(2).unwrap(), data).unwrap();
+        let mut carets = table.glyph_carets(GlyphId(0), None).unwrap();
+        assert_eq!(carets.next(), Some(300));
+        assert_eq!(carets.next(), Some(600));
+        assert_eq!(carets.next(), None);
+        assert_eq!(table.glyph_carets(GlyphId(1), None).unwrap().count(), 0);
+        assert!(table.glyph_carets(GlyphId(2), None).is_none());
+    }
+
+    #[test]
+    fn unresolved_control_points() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x01, // format: 1
+            // Lookup table
+            0x00, 0x00, // format: 0
+            0x00, 0x0A, // offset [0]: 10
+            // Carets [0]
+            0x00, 0x01, // count: 1
+            0x00, 0x05, // control point: 5
+        ];
+
+        let table = Table::parse(NonZeroU16::new(1).unwrap(), data).unwrap();
+        assert_eq!(table.glyph_carets(GlyphId(0), None).unwrap().count(), 0);
+    }
+}
@@ -39,13 +39,23 @@ impl<'a> Table<'a> {
     }
 
     #[inline]
-    fn len(&self) -> u16 {
+    pub fn len(&self) -> u16 {
         match self {
             Table::Short(ref array) => array.len(),
             Table::Long(ref array) => array.len(),
         }
     }
 
+    /// Returns an offset into the `glyf` table by index.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn offset(&self, index: u16) -> Option<usize> {
+        match self {
+            Table::Short(ref array) => array.get(index).map(|n| usize::from(n) * 2),
+            Table::Long(ref array) => array.get(index).map(usize::num_from),
+        }
+    }
+
     #[inline]
     pub fn glyph_range(&self, glyph_id: GlyphId) -> Option<Range<usize>> {
         let glyph_id = glyph_id.0;
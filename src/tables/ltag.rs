@@ -0,0 +1,132 @@
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6ltag.html
+
+use crate::parser::{Stream, FromData, LazyArray32};
+use crate::TableParsingError;
+
+
+#[derive(Clone, Copy)]
+struct TagRange {
+    offset: u16,
+    length: u16,
+}
+
+impl FromData for TagRange {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(TagRange {
+            offset: s.read::<u16>()?,
+            length: s.read::<u16>()?,
+        })
+    }
+}
+
+
+/// An iterator over IETF BCP 47 language tags.
+///
+/// Malformed or non-ASCII tags are skipped.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct LanguageTags<'a> {
+    data: &'a [u8], // The whole `ltag` table. All offsets are from its start.
+    ranges: LazyArray32<'a, TagRange>,
+    index: u32,
+}
+
+impl<'a> LanguageTags<'a> {
+    /// Returns a language tag by index.
+    ///
+    /// Indices are used by the `name` table language IDs
+    /// and the `morx` table language ligatures.
+    pub fn get(&self, index: u32) -> Option<&'a str> {
+        let range = self.ranges.get(index)?;
+        let start = usize::from(range.offset);
+        let end = start + usize::from(range.length);
+        let tag = self.data.get(start..end)?;
+        if !tag.is_ascii() {
+            return None;
+        }
+
+        core::str::from_utf8(tag).ok()
+    }
+}
+
+impl<'a> Iterator for LanguageTags<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.ranges.len() {
+            self.index += 1;
+            if let Some(tag) = self.get(self.index - 1) {
+                return Some(tag);
+            }
+        }
+
+        None
+    }
+}
+
+
+pub(crate) fn parse(data: &[u8]) -> Result<LanguageTags<'_>, TableParsingError> {
+    let mut s = Stream::new(data);
+
+    let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
+    if version != 1 {
+        return Err(TableParsingError::UnsupportedVersion);
+    }
+
+    parse_impl(data, s).ok_or(TableParsingError::Malformed)
+}
+
+fn parse_impl<'a>(data: &'a [u8], mut s: Stream<'a>) -> Option<LanguageTags<'a>> {
+    s.skip::<u32>(); // flags
+    let count: u32 = s.read()?;
+    let ranges = s.read_array32::<TagRange>(count)?;
+
+    Some(LanguageTags {
+        data,
+        ranges,
+        index: 0,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let data = &[
+            0x00, 0x00, 0x00, 0x01, // version: 1
+            0x00, 0x00, 0x00, 0x00, // flags: 0
+            0x00, 0x00, 0x00, 0x03, // number of tags: 3
+            // Range [0]
+            0x00, 0x18, // offset: 24
+            0x00, 0x02, // length: 2
+            // Range [1]
+            0x00, 0x1A, // offset: 26
+            0x00, 0x07, // length: 7
+            // Range [2]
+            0x00, 0x1A, // offset: 26
+            0x00, 0x10, // length: 16 (out of bounds)
+            // Strings
+            0x65, 0x6E, // en
+            0x7A, 0x68, 0x2D, 0x48, 0x61, 0x6E, 0x74, // zh-Hant
+        ];
+
+        let tags = parse(data).unwrap();
+        assert_eq!(tags.count(), 2);
+        assert_eq!(tags.get(0), Some("en"));
+        assert_eq!(tags.get(1), Some("zh-Hant"));
+        assert_eq!(tags.get(2), None);
+        assert_eq!(tags.get(3), None);
+
+        let mut iter = tags;
+        assert_eq!(iter.next(), Some("en"));
+        assert_eq!(iter.next(), Some("zh-Hant"));
+        assert_eq!(iter.next(), None);
+    }
+}
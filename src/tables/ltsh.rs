@@ -0,0 +1,77 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/ltsh
+
+use crate::GlyphId;
+use crate::parser::{Stream, LazyArray16};
+use crate::TableParsingError;
+
+
+#[derive(Clone, Copy)]
+pub struct Table<'a> {
+    thresholds: LazyArray16<'a, u8>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let mut s = Stream::new(data);
+        let version: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+        if version != 0 {
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        Self::parse_impl(s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(mut s: Stream<'a>) -> Option<Self> {
+        let number_of_glyphs: u16 = s.read()?;
+        let thresholds = s.read_array16::<u8>(number_of_glyphs)?;
+        Some(Table { thresholds })
+    }
+
+    pub fn glyph_threshold(&self, glyph_id: GlyphId) -> Option<u8> {
+        self.thresholds.get(glyph_id.0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn basic() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(2), // number of glyphs
+            UInt8(1), // threshold [0]
+            UInt8(12), // threshold [1]
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.glyph_threshold(GlyphId(0)), Some(1));
+        assert_eq!(table.glyph_threshold(GlyphId(1)), Some(12));
+        assert_eq!(table.glyph_threshold(GlyphId(2)), None);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let data = convert(&[
+            UInt16(1), // version
+            UInt16(0), // number of glyphs
+        ]);
+
+        assert_eq!(Table::parse(&data).err(), Some(TableParsingError::UnsupportedVersion));
+    }
+
+    #[test]
+    fn truncated() {
+        let data = convert(&[
+            UInt16(0), // version
+            UInt16(2), // number of glyphs
+            UInt8(1), // threshold [0]
+        ]);
+
+        assert_eq!(Table::parse(&data).err(), Some(TableParsingError::Malformed));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod ankr;
 pub mod avar;
 pub mod cbdt;
 pub mod cblc;
@@ -5,23 +6,32 @@ mod cff;
 pub mod cmap;
 pub mod colr;
 pub mod cpal;
+pub mod feat;
 pub mod fvar;
 pub mod gdef;
 pub mod glyf;
 pub mod gvar;
+pub mod hdmx;
 pub mod head;
 pub mod hhea;
 pub mod hmtx;
 pub mod hvar;
 pub mod kern;
+pub mod kerx;
+pub mod lcar;
 pub mod loca;
+pub mod ltag;
+pub mod ltsh;
 pub mod maxp;
 pub mod mvar;
 pub mod name;
+pub mod opbd;
 pub mod os2;
 pub mod post;
 pub mod sbix;
 pub mod svg;
+pub mod trak;
+pub mod vdmx;
 pub mod vhea;
 pub mod vorg;
 
@@ -3,6 +3,7 @@
 use crate::{Tag, NormalizedCoordinate};
 use crate::parser::{Stream, FromData, Offset, Offset16, LazyArray16};
 use crate::var_store::ItemVariationStore;
+use crate::TableParsingError;
 
 
 #[derive(Clone, Copy)]
@@ -34,14 +35,18 @@ pub(crate) struct Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
 
-        let version: u32 = s.read()?;
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
         if version != 0x00010000 {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
         }
 
+        Self::parse_impl(data, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], mut s: Stream<'a>) -> Option<Self> {
         s.skip::<u16>(); // reserved
         let value_record_size: u16 = s.read()?;
 
@@ -0,0 +1,123 @@
+// Johab (KS X 1001:1992 annex 3) decoder.
+//
+// `encoding_rs` doesn't support Johab, but it can be decoded without large tables:
+// Hangul is composed from jamo indices and other characters map to KS X 1001,
+// which is a subset of EUC-KR.
+
+use std::string::String;
+
+// Compatibility jamo for the 19 leading consonants.
+const CHOSEONG: [u16; 19] = [
+    0x3131, 0x3132, 0x3134, 0x3137, 0x3138, 0x3139, 0x3141, 0x3142,
+    0x3143, 0x3145, 0x3146, 0x3147, 0x3148, 0x3149, 0x314A, 0x314B,
+    0x314C, 0x314D, 0x314E,
+];
+
+// Compatibility jamo for the 27 trailing consonants.
+const JONGSEONG: [u16; 27] = [
+    0x3131, 0x3132, 0x3133, 0x3134, 0x3135, 0x3136, 0x3137, 0x3139,
+    0x313A, 0x313B, 0x313C, 0x313D, 0x313E, 0x313F, 0x3140, 0x3141,
+    0x3142, 0x3144, 0x3145, 0x3146, 0x3147, 0x3148, 0x314A, 0x314B,
+    0x314C, 0x314D, 0x314E,
+];
+
+const FILL: u8 = 0xFF;
+const INVALID: u8 = 0xFE;
+
+// Maps a 5-bit vowel code to a vowel index.
+const JUNGSEONG_INDICES: [u8; 32] = [
+    INVALID, INVALID, FILL, 0, 1, 2, 3, 4,
+    INVALID, INVALID, 5, 6, 7, 8, 9, 10,
+    INVALID, INVALID, 11, 12, 13, 14, 15, 16,
+    INVALID, INVALID, 17, 18, 19, 20, INVALID, INVALID,
+];
+
+pub fn decode(data: &[u8]) -> Option<String> {
+    let mut name = String::with_capacity(data.len());
+    let mut iter = data.iter().cloned();
+    while let Some(lead) = iter.next() {
+        if lead < 0x80 {
+            name.push(char::from(lead));
+            continue;
+        }
+
+        let trail = iter.next()?;
+        let c = match lead {
+            0x84..=0xD3 => decode_hangul(lead, trail)?,
+            0xD9..=0xDE | 0xE0..=0xF9 => decode_ks_x_1001(lead, trail)?,
+            _ => return None,
+        };
+
+        name.push(c);
+    }
+
+    Some(name)
+}
+
+fn decode_hangul(lead: u8, trail: u8) -> Option<char> {
+    if !(0x41..=0x7E).contains(&trail) && !(0x81..=0xFE).contains(&trail) {
+        return None;
+    }
+
+    let code = u16::from(lead) << 8 | u16::from(trail);
+    let initial = ((code >> 10) & 0x1F) as u8;
+    let medial = ((code >> 5) & 0x1F) as u8;
+    let last = (code & 0x1F) as u8;
+
+    let initial = match initial {
+        1 => FILL,
+        2..=20 => initial - 2,
+        _ => return None,
+    };
+
+    let medial = JUNGSEONG_INDICES[usize::from(medial)];
+    if medial == INVALID {
+        return None;
+    }
+
+    // Zero is both a fill code and "no trailing consonant".
+    let last = match last {
+        1 => 0,
+        2..=17 => last - 1,
+        19..=29 => last - 2,
+        _ => return None,
+    };
+
+    let c = match (initial, medial, last) {
+        (FILL, FILL, 0) => 0x3164, // Hangul filler
+        (FILL, FILL, t) => u32::from(JONGSEONG[usize::from(t - 1)]),
+        (FILL, v, 0) => 0x314F + u32::from(v),
+        (l, FILL, 0) => u32::from(CHOSEONG[usize::from(l)]),
+        (FILL, _, _) | (_, FILL, _) => return None,
+        (l, v, t) => 0xAC00 + (u32::from(l) * 21 + u32::from(v)) * 28 + u32::from(t),
+    };
+
+    core::char::from_u32(c)
+}
+
+// Symbols and Hanja map to KS X 1001, two rows per lead byte.
+fn decode_ks_x_1001(lead: u8, trail: u8) -> Option<char> {
+    let mut row = if lead < 0xE0 {
+        (lead - 0xD9) * 2 + 0xA1
+    } else {
+        (lead - 0xE0) * 2 + 0xCA
+    };
+
+    let cell = match trail {
+        0x31..=0x7E => trail + 0x70,
+        0x91..=0xA0 => trail + 0x5E,
+        0xA1..=0xFE => {
+            row += 1;
+            trail
+        }
+        _ => return None,
+    };
+
+    let bytes = [row, cell];
+    let s = encoding_rs::EUC_KR.decode_without_bom_handling_and_without_replacement(&bytes)?;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
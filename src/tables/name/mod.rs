@@ -0,0 +1,902 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/name
+
+#[cfg(feature = "std")]
+use std::vec::Vec;
+#[cfg(feature = "std")]
+use std::string::String;
+
+use crate::parser::{Stream, FromData, LazyArray16, LazyArrayIter16, Offset, Offset16};
+use crate::TableParsingError;
+
+mod languages;
+#[cfg(feature = "std")]
+mod mac_encodings;
+#[cfg(feature = "legacy-encodings")]
+mod johab;
+
+
+/// A list of [name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids)'s.
+pub mod name_id {
+    #![allow(missing_docs)]
+
+    pub const COPYRIGHT_NOTICE: u16                     = 0;
+    pub const FAMILY: u16                               = 1;
+    pub const SUBFAMILY: u16                            = 2;
+    pub const UNIQUE_ID: u16                            = 3;
+    pub const FULL_NAME: u16                            = 4;
+    pub const VERSION: u16                              = 5;
+    pub const POST_SCRIPT_NAME: u16                     = 6;
+    pub const TRADEMARK: u16                            = 7;
+    pub const MANUFACTURER: u16                         = 8;
+    pub const DESIGNER: u16                             = 9;
+    pub const DESCRIPTION: u16                          = 10;
+    pub const VENDOR_URL: u16                           = 11;
+    pub const DESIGNER_URL: u16                         = 12;
+    pub const LICENSE: u16                              = 13;
+    pub const LICENSE_URL: u16                          = 14;
+    //        RESERVED                                  = 15
+    pub const TYPOGRAPHIC_FAMILY: u16                   = 16;
+    pub const TYPOGRAPHIC_SUBFAMILY: u16                = 17;
+    pub const COMPATIBLE_FULL: u16                      = 18;
+    pub const SAMPLE_TEXT: u16                          = 19;
+    pub const POST_SCRIPT_CID: u16                      = 20;
+    pub const WWS_FAMILY: u16                           = 21;
+    pub const WWS_SUBFAMILY: u16                        = 22;
+    pub const LIGHT_BACKGROUND_PALETTE: u16             = 23;
+    pub const DARK_BACKGROUND_PALETTE: u16              = 24;
+    pub const VARIATIONS_POST_SCRIPT_NAME_PREFIX: u16   = 25;
+}
+
+
+/// A [name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids).
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum NameId {
+    CopyrightNotice,
+    Family,
+    Subfamily,
+    UniqueId,
+    FullName,
+    Version,
+    PostScriptName,
+    Trademark,
+    Manufacturer,
+    Designer,
+    Description,
+    VendorUrl,
+    DesignerUrl,
+    License,
+    LicenseUrl,
+    TypographicFamily,
+    TypographicSubfamily,
+    CompatibleFull,
+    SampleText,
+    PostScriptCid,
+    WwsFamily,
+    WwsSubfamily,
+    LightBackgroundPalette,
+    DarkBackgroundPalette,
+    VariationsPostScriptNamePrefix,
+    Other(u16),
+}
+
+impl NameId {
+    /// Returns a numeric representation of a name ID.
+    #[inline]
+    pub fn to_number(self) -> u16 {
+        match self {
+            NameId::CopyrightNotice                 => name_id::COPYRIGHT_NOTICE,
+            NameId::Family                          => name_id::FAMILY,
+            NameId::Subfamily                       => name_id::SUBFAMILY,
+            NameId::UniqueId                        => name_id::UNIQUE_ID,
+            NameId::FullName                        => name_id::FULL_NAME,
+            NameId::Version                         => name_id::VERSION,
+            NameId::PostScriptName                  => name_id::POST_SCRIPT_NAME,
+            NameId::Trademark                       => name_id::TRADEMARK,
+            NameId::Manufacturer                    => name_id::MANUFACTURER,
+            NameId::Designer                        => name_id::DESIGNER,
+            NameId::Description                     => name_id::DESCRIPTION,
+            NameId::VendorUrl                       => name_id::VENDOR_URL,
+            NameId::DesignerUrl                     => name_id::DESIGNER_URL,
+            NameId::License                         => name_id::LICENSE,
+            NameId::LicenseUrl                      => name_id::LICENSE_URL,
+            NameId::TypographicFamily               => name_id::TYPOGRAPHIC_FAMILY,
+            NameId::TypographicSubfamily            => name_id::TYPOGRAPHIC_SUBFAMILY,
+            NameId::CompatibleFull                  => name_id::COMPATIBLE_FULL,
+            NameId::SampleText                      => name_id::SAMPLE_TEXT,
+            NameId::PostScriptCid                   => name_id::POST_SCRIPT_CID,
+            NameId::WwsFamily                       => name_id::WWS_FAMILY,
+            NameId::WwsSubfamily                    => name_id::WWS_SUBFAMILY,
+            NameId::LightBackgroundPalette          => name_id::LIGHT_BACKGROUND_PALETTE,
+            NameId::DarkBackgroundPalette           => name_id::DARK_BACKGROUND_PALETTE,
+            NameId::VariationsPostScriptNamePrefix  => name_id::VARIATIONS_POST_SCRIPT_NAME_PREFIX,
+            NameId::Other(n)                        => n,
+        }
+    }
+}
+
+impl From<u16> for NameId {
+    #[inline]
+    fn from(value: u16) -> Self {
+        match value {
+            name_id::COPYRIGHT_NOTICE                   => NameId::CopyrightNotice,
+            name_id::FAMILY                             => NameId::Family,
+            name_id::SUBFAMILY                          => NameId::Subfamily,
+            name_id::UNIQUE_ID                          => NameId::UniqueId,
+            name_id::FULL_NAME                          => NameId::FullName,
+            name_id::VERSION                            => NameId::Version,
+            name_id::POST_SCRIPT_NAME                   => NameId::PostScriptName,
+            name_id::TRADEMARK                          => NameId::Trademark,
+            name_id::MANUFACTURER                       => NameId::Manufacturer,
+            name_id::DESIGNER                           => NameId::Designer,
+            name_id::DESCRIPTION                        => NameId::Description,
+            name_id::VENDOR_URL                         => NameId::VendorUrl,
+            name_id::DESIGNER_URL                       => NameId::DesignerUrl,
+            name_id::LICENSE                            => NameId::License,
+            name_id::LICENSE_URL                        => NameId::LicenseUrl,
+            name_id::TYPOGRAPHIC_FAMILY                 => NameId::TypographicFamily,
+            name_id::TYPOGRAPHIC_SUBFAMILY              => NameId::TypographicSubfamily,
+            name_id::COMPATIBLE_FULL                    => NameId::CompatibleFull,
+            name_id::SAMPLE_TEXT                        => NameId::SampleText,
+            name_id::POST_SCRIPT_CID                    => NameId::PostScriptCid,
+            name_id::WWS_FAMILY                         => NameId::WwsFamily,
+            name_id::WWS_SUBFAMILY                      => NameId::WwsSubfamily,
+            name_id::LIGHT_BACKGROUND_PALETTE           => NameId::LightBackgroundPalette,
+            name_id::DARK_BACKGROUND_PALETTE            => NameId::DarkBackgroundPalette,
+            name_id::VARIATIONS_POST_SCRIPT_NAME_PREFIX => NameId::VariationsPostScriptNamePrefix,
+            _                                           => NameId::Other(value),
+        }
+    }
+}
+
+
+/// A [platform ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#platform-ids).
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum PlatformId {
+    Unicode,
+    Macintosh,
+    Iso,
+    Windows,
+    Custom,
+}
+
+impl FromData for PlatformId {
+    const SIZE: usize = 2;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        match u16::parse(data)? {
+            0 => Some(PlatformId::Unicode),
+            1 => Some(PlatformId::Macintosh),
+            2 => Some(PlatformId::Iso),
+            3 => Some(PlatformId::Windows),
+            4 => Some(PlatformId::Custom),
+            _ => None,
+        }
+    }
+}
+
+
+#[inline]
+fn is_unicode_encoding(platform_id: PlatformId, encoding_id: u16) -> bool {
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
+    const WINDOWS_SYMBOL_ENCODING_ID: u16 = 0;
+    const WINDOWS_UNICODE_BMP_ENCODING_ID: u16 = 1;
+
+    match platform_id {
+        PlatformId::Unicode => true,
+        PlatformId::Windows => match encoding_id {
+            WINDOWS_SYMBOL_ENCODING_ID |
+            WINDOWS_UNICODE_BMP_ENCODING_ID => true,
+            _ => false,
+        }
+        _ => false,
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct NameRecord {
+    platform_id: PlatformId,
+    encoding_id: u16,
+    language_id: u16,
+    name_id: u16,
+    length: u16,
+    offset: u16,
+}
+
+impl FromData for NameRecord {
+    const SIZE: usize = 12;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(NameRecord {
+            platform_id: s.read::<PlatformId>()?,
+            encoding_id: s.read::<u16>()?,
+            language_id: s.read::<u16>()?,
+            name_id: s.read::<u16>()?,
+            length: s.read::<u16>()?,
+            offset: s.read::<u16>()?,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct LangTagRecord {
+    length: u16,
+    offset: u16,
+}
+
+impl FromData for LangTagRecord {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(LangTagRecord {
+            length: s.read::<u16>()?,
+            offset: s.read::<u16>()?,
+        })
+    }
+}
+
+
+/// A [language tag](https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1)
+/// from the `name` table version 1.
+#[derive(Clone, Copy)]
+pub struct LangTag<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> LangTag<'a> {
+    /// Returns the tag data as bytes.
+    ///
+    /// Tags are stored as UTF-16BE.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the tag as a UTF-8 string.
+    #[cfg(feature = "std")]
+    #[inline(never)]
+    pub fn to_string(&self) -> Option<String> {
+        let name: Vec<u16> = LazyArray16::<u16>::new(self.data).into_iter().collect();
+        String::from_utf16(&name).ok()
+    }
+
+    // BCP 47 tags are ASCII only, so we can decode them without an allocation.
+    fn decode_ascii<'b>(&self, buf: &'b mut [u8]) -> Option<&'b str> {
+        let tag = LazyArray16::<u16>::new(self.data);
+        let buf = buf.get_mut(..usize::from(tag.len()))?;
+        for (b, c) in buf.iter_mut().zip(tag) {
+            if c > 0x7F {
+                return None;
+            }
+
+            *b = c as u8;
+        }
+
+        core::str::from_utf8(buf).ok()
+    }
+}
+
+impl core::fmt::Debug for LangTag<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let mut buf = [0; MAX_LANG_TAG_LEN];
+        match self.decode_ascii(&mut buf) {
+            Some(tag) => write!(f, "LangTag({:?})", tag),
+            None => write!(f, "LangTag({:?})", self.data),
+        }
+    }
+}
+
+// Enough for any well-formed BCP 47 tag.
+const MAX_LANG_TAG_LEN: usize = 64;
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+const LANG_TAG_LANGUAGE_ID: u16 = 0x8000;
+
+
+/// A [Name Record](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-records).
+#[derive(Clone, Copy)]
+pub struct Name<'a> {
+    data: NameRecord,
+    strings: &'a [u8],
+    lang_tags: LazyArray16<'a, LangTagRecord>,
+}
+
+impl<'a> Name<'a> {
+    /// Returns the platform ID.
+    pub fn platform_id(&self) -> PlatformId {
+        self.data.platform_id
+    }
+
+    /// Returns the platform-specific encoding ID.
+    pub fn encoding_id(&self) -> u16 {
+        self.data.encoding_id
+    }
+
+    /// Returns the language ID.
+    pub fn language_id(&self) -> u16 {
+        self.data.language_id
+    }
+
+    /// Returns the [Name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids).
+    ///
+    /// A predefined list of ID's can be found in the [`name_id`](name_id/index.html) module.
+    pub fn name_id(&self) -> u16 {
+        self.data.name_id
+    }
+
+    /// Returns the typed Name ID.
+    #[inline]
+    pub fn id(&self) -> NameId {
+        NameId::from(self.data.name_id)
+    }
+
+    /// Returns the Name's data as bytes.
+    ///
+    /// Can be empty.
+    pub fn name(&self) -> &'a [u8] {
+        let start = usize::from(self.data.offset);
+        let end = start + usize::from(self.data.length);
+        self.strings.get(start..end).unwrap_or(&[])
+    }
+
+    /// Returns the Name's language as a [BCP 47](https://tools.ietf.org/html/bcp47) tag.
+    ///
+    /// Only Windows and Macintosh language IDs are supported.
+    /// Language IDs from the `name` table version 1 are resolved via `Name::language_tag`.
+    pub fn language(&self) -> Option<&'static str> {
+        let languages = match self.platform_id() {
+            PlatformId::Windows => languages::WINDOWS_LANGUAGES,
+            PlatformId::Macintosh => languages::MACINTOSH_LANGUAGES,
+            _ => return None,
+        };
+
+        let language_id = self.language_id();
+        languages.binary_search_by(|&(id, _)| id.cmp(&language_id))
+            .ok()
+            .map(|idx| languages[idx].1)
+    }
+
+    /// Returns the Name's language tag.
+    ///
+    /// Available only for language IDs starting from 0x8000,
+    /// which are used by the `name` table version 1.
+    pub fn language_tag(&self) -> Option<LangTag<'a>> {
+        let index = self.language_id().checked_sub(LANG_TAG_LANGUAGE_ID)?;
+        let record = self.lang_tags.get(index)?;
+        let start = usize::from(record.offset);
+        let end = start + usize::from(record.length);
+        self.strings.get(start..end).map(|data| LangTag { data })
+    }
+
+    /// Returns the Name's data as a UTF-8 string.
+    ///
+    /// Since names are not stored as UTF-8, we can't return `&str`
+    /// and have to allocate a `String`.
+    ///
+    /// Supports:
+    /// - Unicode Platform ID
+    /// - Windows Platform ID + Symbol
+    /// - Windows Platform ID + Unicode BMP
+    /// - Macintosh Platform ID + Roman, Greek, Cyrillic and Central European.
+    ///   Icelandic, Turkish, Croatian and Romanian variants of Roman are selected by language ID.
+    ///
+    /// With the `legacy-encodings` feature also supports:
+    /// - Windows Platform ID + ShiftJIS, PRC, Big5, Wansung and Johab
+    /// - Macintosh Platform ID + Japanese, Traditional Chinese, Korean and Simplified Chinese
+    ///
+    /// Other Macintosh encodings, like Arabic, Hebrew or Thai, are not supported.
+    /// Returns `None` for them.
+    #[cfg(feature = "std")]
+    #[inline(never)]
+    pub fn to_string(&self) -> Option<String> {
+        if self.is_unicode() {
+            self.name_from_utf16_be()
+        } else if let Some(table) = self.mac_encoding() {
+            Some(self.name().iter().map(|&b| decode_mac_byte(table, b)).collect())
+        } else {
+            self.name_from_legacy_encoding()
+        }
+    }
+
+    /// Returns an iterator over the Name's characters.
+    ///
+    /// Unlike `Name::to_string`, doesn't allocate and doesn't require the `std` feature.
+    /// Unpaired surrogates are replaced with `U+FFFD`.
+    ///
+    /// Returns `None` when the Name data doesn't have a Unicode encoding.
+    #[inline]
+    pub fn chars(&self) -> Option<NameChars<'a>> {
+        if self.is_unicode() {
+            let data = LazyArray16::<u16>::new(self.name());
+            Some(NameChars { iter: core::char::decode_utf16(data) })
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the current Name data has a Unicode encoding.
+    #[inline]
+    pub fn is_unicode(&self) -> bool {
+        is_unicode_encoding(self.platform_id(), self.encoding_id())
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(never)]
+    fn name_from_utf16_be(&self) -> Option<String> {
+        let mut name: Vec<u16> = Vec::new();
+        for c in LazyArray16::<u16>::new(self.name()) {
+            name.push(c);
+        }
+
+        String::from_utf16(&name).ok()
+    }
+
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-encoding-ids-script-manager-codes
+    #[cfg(feature = "std")]
+    fn mac_encoding(&self) -> Option<&'static [u16; 128]> {
+        if self.platform_id() != PlatformId::Macintosh {
+            return None;
+        }
+
+        match self.encoding_id() {
+            0 => match self.language_id() {
+                15 | 30 => Some(&mac_encodings::MAC_ICELANDIC), // Icelandic and Faroese
+                17 => Some(&mac_encodings::MAC_TURKISH),
+                18 => Some(&mac_encodings::MAC_CROATIAN),
+                37 => Some(&mac_encodings::MAC_ROMANIAN),
+                _ => Some(&mac_encodings::MAC_ROMAN),
+            }
+            6 => Some(&mac_encodings::MAC_GREEK),
+            7 => Some(&mac_encodings::MAC_CYRILLIC),
+            29 => Some(&mac_encodings::MAC_CENTRAL_EUROPEAN),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "legacy-encodings")]
+    #[inline(never)]
+    fn name_from_legacy_encoding(&self) -> Option<String> {
+        let encoding = match (self.platform_id(), self.encoding_id()) {
+            (PlatformId::Macintosh, 1) | (PlatformId::Windows, 2) => encoding_rs::SHIFT_JIS,
+            (PlatformId::Macintosh, 2) | (PlatformId::Windows, 4) => encoding_rs::BIG5,
+            (PlatformId::Macintosh, 3) | (PlatformId::Windows, 5) => encoding_rs::EUC_KR,
+            (PlatformId::Macintosh, 25) | (PlatformId::Windows, 3) => encoding_rs::GBK,
+            (PlatformId::Windows, 6) => return johab::decode(&self.name_from_windows_bytes()),
+            _ => return None,
+        };
+
+        let name = if self.platform_id() == PlatformId::Windows {
+            self.name_from_windows_bytes()
+        } else {
+            self.name().to_vec()
+        };
+
+        encoding.decode_without_bom_handling_and_without_replacement(&name)
+            .map(|s| s.into_owned())
+    }
+
+    #[cfg(feature = "legacy-encodings")]
+    #[inline]
+    fn name_from_windows_bytes(&self) -> Vec<u8> {
+        // Windows stores each character as a big-endian 16-bit value,
+        // so single-byte characters are padded with zeros.
+        self.name().iter().cloned().filter(|b| *b != 0).collect()
+    }
+
+    #[cfg(all(feature = "std", not(feature = "legacy-encodings")))]
+    #[inline]
+    fn name_from_legacy_encoding(&self) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn decode_mac_byte(table: &[u16; 128], b: u8) -> char {
+    if b < 0x80 {
+        char::from(b)
+    } else {
+        char::from_u32(u32::from(table[usize::from(b - 0x80)])).unwrap_or('\u{FFFD}')
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> core::fmt::Debug for Name<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        // TODO: https://github.com/rust-lang/rust/issues/50264
+
+        let name = self.to_string();
+        f.debug_struct("Name")
+            .field("name", &name.as_ref().map(core::ops::Deref::deref)
+                                .unwrap_or("unsupported encoding"))
+            .field("platform_id", &self.platform_id())
+            .field("encoding_id", &self.encoding_id())
+            .field("language_id", &self.language_id())
+            .field("name_id", &self.name_id())
+            .finish()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a> core::fmt::Debug for Name<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Name")
+            .field("name", &self.name())
+            .field("platform_id", &self.platform_id())
+            .field("encoding_id", &self.encoding_id())
+            .field("language_id", &self.language_id())
+            .field("name_id", &self.name_id())
+            .finish()
+    }
+}
+
+
+/// An iterator over Name's characters.
+///
+/// Created by `Name::chars`.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct NameChars<'a> {
+    iter: core::char::DecodeUtf16<LazyArrayIter16<'a, u16>>,
+}
+
+impl<'a> Iterator for NameChars<'a> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|c| c.unwrap_or(core::char::REPLACEMENT_CHARACTER))
+    }
+}
+
+
+/// An iterator over font's names.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Names<'a> {
+    names: &'a [u8],
+    storage: &'a [u8],
+    lang_tags: LazyArray16<'a, LangTagRecord>,
+    index: u16,
+    total: u16,
+}
+
+impl<'a> Iterator for Names<'a> {
+    type Item = Name<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.total {
+            self.index += 1;
+            self.nth(usize::from(self.index) - 1)
+        } else {
+            None
+        }
+    }
+
+    fn count(self) -> usize {
+        usize::from(self.total)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        Some(Name {
+            data: Stream::read_at::<NameRecord>(self.names, NameRecord::SIZE * n)?,
+            strings: self.storage,
+            lang_tags: self.lang_tags,
+        })
+    }
+}
+
+
+/// Finds the best matching name.
+///
+/// Typographic family and subfamily are preferred over the legacy ones.
+/// Records with the same ID are ranked by language first and by platform second.
+pub(crate) fn find<'a>(names: Names<'a>, id: NameId, languages: &[&str]) -> Option<Name<'a>> {
+    let ids: &[NameId] = match id {
+        NameId::Family => &[NameId::TypographicFamily, NameId::Family],
+        NameId::Subfamily => &[NameId::TypographicSubfamily, NameId::Subfamily],
+        _ => &[id],
+    };
+
+    for id in ids {
+        let id = id.to_number();
+        let name = names
+            .filter(|name| name.name_id() == id)
+            .min_by_key(|name| (language_rank(name, languages), platform_rank(name)));
+
+        if name.is_some() {
+            return name;
+        }
+    }
+
+    None
+}
+
+fn language_rank(name: &Name, languages: &[&str]) -> usize {
+    let mut buf = [0; MAX_LANG_TAG_LEN];
+    let tag = match name.language() {
+        Some(v) => Some(v),
+        None => match name.language_tag() {
+            Some(tag) => tag.decode_ascii(&mut buf),
+            None => None,
+        },
+    };
+
+    let tag = match tag {
+        Some(v) => v,
+        None => return languages.len() * 2 + 3,
+    };
+
+    let primary = primary_language(tag);
+    for (i, lang) in languages.iter().enumerate() {
+        if lang.eq_ignore_ascii_case(tag) {
+            return i * 2;
+        } else if primary_language(lang).eq_ignore_ascii_case(primary) {
+            return i * 2 + 1;
+        }
+    }
+
+    // Fallback to English.
+    if tag == "en" {
+        languages.len() * 2
+    } else if primary == "en" {
+        languages.len() * 2 + 1
+    } else {
+        languages.len() * 2 + 2
+    }
+}
+
+#[inline]
+fn primary_language(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+fn platform_rank(name: &Name) -> u8 {
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
+    const WINDOWS_UNICODE_BMP_ENCODING_ID: u16 = 1;
+    const WINDOWS_UNICODE_FULL_ENCODING_ID: u16 = 10;
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-encoding-ids-script-manager-codes
+    const MACINTOSH_ROMAN_ENCODING_ID: u16 = 0;
+
+    match (name.platform_id(), name.encoding_id()) {
+        (PlatformId::Windows, WINDOWS_UNICODE_BMP_ENCODING_ID) |
+        (PlatformId::Windows, WINDOWS_UNICODE_FULL_ENCODING_ID) => 0,
+        (PlatformId::Unicode, _) => 1,
+        (PlatformId::Macintosh, MACINTOSH_ROMAN_ENCODING_ID) => 2,
+        _ => 3,
+    }
+}
+
+
+#[inline(never)]
+pub(crate) fn parse(data: &[u8]) -> Result<Names, TableParsingError> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+    if format > 1 {
+        return Err(TableParsingError::UnsupportedVersion);
+    }
+
+    parse_impl(data, format, s).ok_or(TableParsingError::Malformed)
+}
+
+fn parse_impl<'a>(data: &'a [u8], format: u16, mut s: Stream<'a>) -> Option<Names<'a>> {
+    let count: u16 = s.read()?;
+    let storage_offset: Offset16 = s.read()?;
+    let names = s.read_bytes(NameRecord::SIZE * usize::from(count))?;
+
+    let lang_tags = if format == 1 {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+        let lang_tag_count: u16 = s.read()?;
+        s.read_array16(lang_tag_count)?
+    } else {
+        LazyArray16::default()
+    };
+
+    Some(Names {
+        names,
+        storage: data.get(storage_offset.to_usize()..)?,
+        lang_tags,
+        index: 0,
+        total: count,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    fn name(platform_id: PlatformId, encoding_id: u16, language_id: u16, strings: &[u8]) -> Name {
+        Name {
+            data: NameRecord {
+                platform_id,
+                encoding_id,
+                language_id,
+                name_id: name_id::FAMILY,
+                length: strings.len() as u16,
+                offset: 0,
+            },
+            strings,
+            lang_tags: LazyArray16::default(),
+        }
+    }
+
+    #[test]
+    fn language() {
+        assert_eq!(name(PlatformId::Windows, 1, 0x0409, &[]).language(), Some("en"));
+        assert_eq!(name(PlatformId::Windows, 1, 0x0804, &[]).language(), Some("zh-CN"));
+        assert_eq!(name(PlatformId::Macintosh, 0, 0, &[]).language(), Some("en"));
+        assert_eq!(name(PlatformId::Windows, 1, 0xFFFF, &[]).language(), None);
+        assert_eq!(name(PlatformId::Unicode, 0, 0, &[]).language(), None);
+    }
+
+    fn names_data() -> Vec<u8> {
+        convert(&[
+            UInt16(0), // format
+            UInt16(6), // count
+            UInt16(78), // string offset
+            // Mac Roman, English, full name
+            UInt16(1), UInt16(0), UInt16(0), UInt16(4), UInt16(1), UInt16(0),
+            // Windows Unicode BMP, German, full name
+            UInt16(3), UInt16(1), UInt16(0x0407), UInt16(4), UInt16(2), UInt16(1),
+            // Windows Unicode BMP, English, full name
+            UInt16(3), UInt16(1), UInt16(0x0409), UInt16(4), UInt16(2), UInt16(3),
+            // Unicode, full name
+            UInt16(0), UInt16(3), UInt16(0), UInt16(4), UInt16(2), UInt16(5),
+            // Windows Unicode BMP, English, typographic family
+            UInt16(3), UInt16(1), UInt16(0x0409), UInt16(16), UInt16(2), UInt16(7),
+            // Windows Unicode BMP, English, family
+            UInt16(3), UInt16(1), UInt16(0x0409), UInt16(1), UInt16(2), UInt16(9),
+            // strings
+            Raw(b"M\0D\0W\0U\0T\0F"),
+        ])
+    }
+
+    #[test]
+    fn find_name() {
+        let data = names_data();
+        let names = parse(&data).unwrap();
+        let find = |id, languages| find(names, id, languages).map(|name| name.name());
+
+        assert_eq!(find(NameId::FullName, &[]), Some(&b"\0W"[..]));
+        assert_eq!(find(NameId::FullName, &["fr"]), Some(&b"\0W"[..]));
+        assert_eq!(find(NameId::FullName, &["de"]), Some(&b"\0D"[..]));
+        assert_eq!(find(NameId::FullName, &["de-AT", "en"]), Some(&b"\0D"[..]));
+        assert_eq!(find(NameId::FullName, &["EN-us", "de"]), Some(&b"\0W"[..]));
+        assert_eq!(find(NameId::Family, &[]), Some(&b"\0T"[..]));
+        assert_eq!(find(NameId::Subfamily, &[]), None);
+    }
+
+    #[test]
+    fn lang_tags() {
+        let data = convert(&[
+            UInt16(1), // format
+            UInt16(2), // count
+            UInt16(36), // string offset
+            // Windows Unicode BMP, English, family
+            UInt16(3), UInt16(1), UInt16(0x0409), UInt16(1), UInt16(2), UInt16(0),
+            // Windows Unicode BMP, first language tag, family
+            UInt16(3), UInt16(1), UInt16(0x8000), UInt16(1), UInt16(2), UInt16(2),
+            UInt16(1), // language tag count
+            UInt16(14), // language tag length
+            UInt16(4), // language tag offset
+            // strings
+            Raw(b"\0F\0H\0z\0h\0-\0H\0a\0n\0t"),
+        ]);
+
+        let names = parse(&data).unwrap();
+        let name = names.clone().nth(1).unwrap();
+        assert_eq!(name.name(), b"\0H");
+        assert_eq!(name.language(), None);
+        let tag = name.language_tag().unwrap();
+        assert_eq!(tag.data(), b"\0z\0h\0-\0H\0a\0n\0t");
+        #[cfg(feature = "std")]
+        assert_eq!(tag.to_string().unwrap(), "zh-Hant");
+
+        assert!(names.clone().nth(0).unwrap().language_tag().is_none());
+
+        let find = |languages| find(names, NameId::Family, languages).map(|name| name.name());
+        assert_eq!(find(&[]), Some(&b"\0F"[..]));
+        assert_eq!(find(&["zh-hant"]), Some(&b"\0H"[..]));
+        assert_eq!(find(&["zh-TW"]), Some(&b"\0H"[..]));
+    }
+
+    #[test]
+    fn unsupported_format() {
+        let data = convert(&[
+            UInt16(2), // format
+            UInt16(0), // count
+            UInt16(6), // string offset
+        ]);
+
+        assert_eq!(parse(&data).err(), Some(TableParsingError::UnsupportedVersion));
+        assert_eq!(parse(&[0, 0, 0]).err(), Some(TableParsingError::Malformed));
+    }
+
+    #[test]
+    fn name_id_conversion() {
+        assert_eq!(NameId::from(16), NameId::TypographicFamily);
+        assert_eq!(NameId::from(300), NameId::Other(300));
+        assert_eq!(NameId::WwsSubfamily.to_number(), 22);
+    }
+
+    #[test]
+    fn chars() {
+        // "A😀", an unpaired high surrogate and "B"
+        let data = [0x00, 0x41, 0xD8, 0x3D, 0xDE, 0x00, 0xD8, 0x3D, 0x00, 0x42];
+        let n = name(PlatformId::Windows, 1, 0x0409, &data);
+        let mut chars = n.chars().unwrap();
+        assert_eq!(chars.next(), Some('A'));
+        assert_eq!(chars.next(), Some('😀'));
+        assert_eq!(chars.next(), Some('\u{FFFD}'));
+        assert_eq!(chars.next(), Some('B'));
+        assert_eq!(chars.next(), None);
+
+        assert!(name(PlatformId::Macintosh, 0, 0, b"A").chars().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mac_roman() {
+        // "Café™"
+        let n = name(PlatformId::Macintosh, 0, 0, &[0x43, 0x61, 0x66, 0x8E, 0xAA]);
+        assert_eq!(n.to_string().unwrap(), "Café™");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mac_cyrillic() {
+        // "Шрифт"
+        let n = name(PlatformId::Macintosh, 7, 32, &[0x98, 0xF0, 0xE8, 0xF4, 0xF2]);
+        assert_eq!(n.to_string().unwrap(), "Шрифт");
+    }
+
+    #[cfg(feature = "legacy-encodings")]
+    #[test]
+    fn shift_jis() {
+        // "日本"
+        let n = name(PlatformId::Macintosh, 1, 11, &[0x93, 0xFA, 0x96, 0x7B]);
+        assert_eq!(n.to_string().unwrap(), "日本");
+    }
+
+    #[cfg(feature = "legacy-encodings")]
+    #[test]
+    fn johab() {
+        // "한글 A", a compatibility jamo, an ideographic space and a Hanja
+        let data = [
+            0xD0, 0x65, 0x8B, 0x69, 0x00, 0x20, 0x00, 0x41,
+            0x88, 0x41, 0xD9, 0x31, 0xE0, 0x31,
+        ];
+        let n = name(PlatformId::Windows, 6, 0x0412, &data);
+        assert_eq!(n.to_string().unwrap(), "한글 Aㄱ\u{3000}伽");
+
+        // An invalid vowel code.
+        let n = name(PlatformId::Windows, 6, 0x0412, &[0x88, 0x01]);
+        assert_eq!(n.to_string(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn unsupported_mac_encoding() {
+        // Mac Hebrew
+        let n = name(PlatformId::Macintosh, 5, 10, &[0xF9, 0xEC, 0xE5, 0xED]);
+        assert_eq!(n.to_string(), None);
+    }
+
+    #[cfg(all(feature = "std", not(feature = "legacy-encodings")))]
+    #[test]
+    fn unsupported_encoding() {
+        let n = name(PlatformId::Macintosh, 1, 11, &[0x93, 0xFA, 0x96, 0x7B]);
+        assert_eq!(n.to_string(), None);
+    }
+}
@@ -0,0 +1,182 @@
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6opbd.html
+
+use core::num::NonZeroU16;
+
+use crate::GlyphId;
+use crate::aat;
+use crate::glyf::GlyphOutlines;
+use crate::parser::Stream;
+use crate::TableParsingError;
+
+
+/// Glyph optical bounds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OpticalBounds {
+    /// Distances from the glyph's edges to its optical edges, in font units.
+    Distances {
+        /// Left distance.
+        left: i16,
+        /// Top distance.
+        top: i16,
+        /// Right distance.
+        right: i16,
+        /// Bottom distance.
+        bottom: i16,
+    },
+    /// Coordinates of control points that mark the optical edges, in font units.
+    ///
+    /// `left` and `right` are X coordinates, while `top` and `bottom` are Y coordinates.
+    ///
+    /// Set to `None` when an edge has no control point
+    /// or when the point cannot be resolved via the `glyf` table.
+    ControlPoints {
+        /// Left edge.
+        left: Option<i16>,
+        /// Top edge.
+        top: Option<i16>,
+        /// Right edge.
+        right: Option<i16>,
+        /// Bottom edge.
+        bottom: Option<i16>,
+    },
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    data: &'a [u8], // The whole `opbd` table. All offsets are from its start.
+    lookup: aat::Lookup<'a>,
+    has_control_points: bool,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(number_of_glyphs: NonZeroU16, data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let mut s = Stream::new(data);
+
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
+        if version != 0x00010000 {
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        let format: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+        if format > 1 {
+            return Err(TableParsingError::UnsupportedFormat);
+        }
+
+        Self::parse_impl(number_of_glyphs, data, format, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(
+        number_of_glyphs: NonZeroU16,
+        data: &'a [u8],
+        format: u16,
+        s: Stream<'a>,
+    ) -> Option<Self> {
+        Some(Table {
+            data,
+            lookup: aat::Lookup::parse(number_of_glyphs, s.tail()?)?,
+            has_control_points: format == 1,
+        })
+    }
+
+    pub fn glyph_bounds(
+        &self,
+        glyph_id: GlyphId,
+        outlines: Option<GlyphOutlines>,
+    ) -> Option<OpticalBounds> {
+        let offset = self.lookup.value(glyph_id)?;
+        let mut s = Stream::new_at(self.data, usize::from(offset))?;
+
+        if !self.has_control_points {
+            return Some(OpticalBounds::Distances {
+                left: s.read()?,
+                top: s.read()?,
+                right: s.read()?,
+                bottom: s.read()?,
+            });
+        }
+
+        let mut points = [0i16; 4];
+        for point in &mut points {
+            *point = s.read()?;
+        }
+
+        // -1 indicates that there is no control point for this edge.
+        let point = |index: i16| {
+            let index = if index >= 0 { index as u16 } else { return None };
+            outlines?.point(glyph_id, index)
+        };
+
+        Some(OpticalBounds::ControlPoints {
+            left: point(points[0]).map(|p| p.0),
+            top: point(points[1]).map(|p| p.1),
+            right: point(points[2]).map(|p| p.0),
+            bottom: point(points[3]).map(|p| p.1),
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x00, // format: 0
+            // Lookup table
+            0x00, 0x00, // format: 0
+            0x00, 0x0C, // offset [0]: 12
+            0x00, 0x00, // offset [1]: 0
+            // Bounds [0]
+            0xFF, 0xF6, // left: -10
+            0x00, 0x05, // top: 5
+            0x00, 0x14, // right: 20
+            0x00, 0x00, // bottom: 0
+        ];
+
+        let table = Table::parse(NonZeroU16::new(2).unwrap(), data).unwrap();
+        assert_eq!(
+            table.glyph_bounds(GlyphId(0), None),
+            Some(OpticalBounds::Distances { left: -10, top: 5, right: 20, bottom: 0 })
+        );
+        // Out of range glyph.
+        assert!(table.glyph_bounds(GlyphId(2), None).is_none());
+    }
+
+    #[test]
+    fn unresolved_control_points() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x01, // format: 1
+            // Lookup table
+            0x00, 0x00, // format: 0
+            0x00, 0x0A, // offset [0]: 10
+            // Control points [0]
+            0x00, 0x01, // left: 1
+            0xFF, 0xFF, // top: none
+            0x00, 0x02, // right: 2
+            0xFF, 0xFF, // bottom: none
+        ];
+
+        let table = Table::parse(NonZeroU16::new(1).unwrap(), data).unwrap();
+        assert_eq!(
+            table.glyph_bounds(GlyphId(0), None),
+            Some(OpticalBounds::ControlPoints { left: None, top: None, right: None, bottom: None })
+        );
+    }
+
+    #[test]
+    fn unknown_format() {
+        let data = &[
+            0x00, 0x01, 0x00, 0x00, // version: 1.0
+            0x00, 0x02, // format: 2
+            0x00, 0x00, // lookup format: 0
+        ];
+
+        let table = Table::parse(NonZeroU16::new(1).unwrap(), data);
+        assert_eq!(table.err(), Some(TableParsingError::UnsupportedFormat));
+    }
+}
@@ -1,21 +1,41 @@
-// https://docs.microsoft.com/en-us/typography/opentype/spec/os2
+/*!
+An [OS/2 and Windows Metrics Table](
+https://docs.microsoft.com/en-us/typography/opentype/spec/os2) implementation.
+*/
 
-use crate::LineMetrics;
+use crate::{LineMetrics, Tag};
 use crate::parser::Stream;
+use crate::TableParsingError;
 
 
+const X_AVG_CHAR_WIDTH_OFFSET: usize = 2;
 const US_WEIGHT_CLASS_OFFSET: usize = 4;
 const US_WIDTH_CLASS_OFFSET: usize = 6;
+const FS_TYPE_OFFSET: usize = 8;
 const Y_SUBSCRIPT_X_SIZE_OFFSET: usize = 10;
 const Y_SUPERSCRIPT_X_SIZE_OFFSET: usize = 18;
 const Y_STRIKEOUT_SIZE_OFFSET: usize = 26;
 const Y_STRIKEOUT_POSITION_OFFSET: usize = 28;
+const S_FAMILY_CLASS_OFFSET: usize = 30;
+const PANOSE_OFFSET: usize = 32;
+const UL_UNICODE_RANGE1_OFFSET: usize = 42;
+const ACH_VEND_ID_OFFSET: usize = 58;
 const FS_SELECTION_OFFSET: usize = 62;
+const US_FIRST_CHAR_INDEX_OFFSET: usize = 64;
+const US_LAST_CHAR_INDEX_OFFSET: usize = 66;
 const S_TYPO_ASCENDER_OFFSET: usize = 68;
 const S_TYPO_DESCENDER_OFFSET: usize = 70;
 const S_TYPO_LINE_GAP_OFFSET: usize = 72;
+const US_WIN_ASCENT_OFFSET: usize = 74;
+const US_WIN_DESCENT_OFFSET: usize = 76;
+const UL_CODE_PAGE_RANGE1_OFFSET: usize = 78;
 const SX_HEIGHT_OFFSET: usize = 86;
 const S_CAP_HEIGHT_OFFSET: usize = 88;
+const US_DEFAULT_CHAR_OFFSET: usize = 90;
+const US_BREAK_CHAR_OFFSET: usize = 92;
+const US_MAX_CONTEXT_OFFSET: usize = 94;
+const US_LOWER_OPTICAL_POINT_SIZE_OFFSET: usize = 96;
+const US_UPPER_OPTICAL_POINT_SIZE_OFFSET: usize = 98;
 
 
 /// A font [weight](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#usweightclass).
@@ -138,6 +158,88 @@ pub struct ScriptMetrics {
 }
 
 
+/// A font [embedding permissions](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fstype).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Permissions {
+    /// The font may be embedded and permanently installed on the remote system.
+    Installable,
+    /// The font must not be embedded, modified or exchanged.
+    Restricted,
+    /// The font may be embedded only to view or print the document.
+    PreviewAndPrint,
+    /// The font may be embedded to view, print and edit the document.
+    Editable,
+}
+
+
+/// A [PANOSE](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#panose)
+/// classification.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[allow(missing_docs)]
+pub struct Panose {
+    pub family_type: u8,
+    pub serif_style: u8,
+    pub weight: u8,
+    pub proportion: u8,
+    pub contrast: u8,
+    pub stroke_variation: u8,
+    pub arm_style: u8,
+    pub letterform: u8,
+    pub midline: u8,
+    pub x_height: u8,
+}
+
+
+/// [Unicode ranges](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#ur)
+/// supported by the font.
+///
+/// Stored as a 128-bit mask, where `ulUnicodeRange1` bit 0 is bit 0.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct UnicodeRanges(pub u128);
+
+impl UnicodeRanges {
+    /// Checks that a specified bit is set.
+    ///
+    /// Bits are numbered according to the OpenType specification, from 0 to 127.
+    #[inline]
+    pub fn is_set(&self, bit: u8) -> bool {
+        bit < 128 && self.0 & (1 << bit) != 0
+    }
+}
+
+
+/// [Code page character ranges](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#cpr)
+/// supported by the font.
+///
+/// Stored as a 64-bit mask, where `ulCodePageRange1` bit 0 is bit 0.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct CodePageRanges(pub u64);
+
+impl CodePageRanges {
+    /// Checks that a specified bit is set.
+    ///
+    /// Bits are numbered according to the OpenType specification, from 0 to 63.
+    #[inline]
+    pub fn is_set(&self, bit: u8) -> bool {
+        bit < 64 && self.0 & (1 << bit) != 0
+    }
+}
+
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fstype
+#[derive(Clone, Copy)]
+struct TypeFlags(u16);
+
+impl TypeFlags {
+    #[inline] fn restricted(self) -> bool { self.0 & (1 << 1) != 0 }
+    #[inline] fn preview_and_print(self) -> bool { self.0 & (1 << 2) != 0 }
+    #[inline] fn editable(self) -> bool { self.0 & (1 << 3) != 0 }
+    #[inline] fn no_subsetting(self) -> bool { self.0 & (1 << 8) != 0 }
+    #[inline] fn bitmap_only(self) -> bool { self.0 & (1 << 9) != 0 }
+}
+
+
 // https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fsselection
 #[derive(Clone, Copy)]
 struct SelectionFlags(u16);
@@ -147,20 +249,24 @@ impl SelectionFlags {
     #[inline] fn bold(self) -> bool { self.0 & (1 << 5) != 0 }
     #[inline] fn regular(self) -> bool { self.0 & (1 << 6) != 0 }
     #[inline] fn use_typo_metrics(self) -> bool { self.0 & (1 << 7) != 0 }
+    #[inline] fn wws(self) -> bool { self.0 & (1 << 8) != 0 }
     #[inline] fn oblique(self) -> bool { self.0 & (1 << 9) != 0 }
 }
 
 
+/// An [OS/2 and Windows Metrics Table](https://docs.microsoft.com/en-us/typography/opentype/spec/os2).
+///
+/// Getters return raw values. Metrics variations are not applied.
 #[derive(Clone, Copy)]
-pub(crate) struct Table<'a> {
+pub struct Table<'a> {
     version: u8,
     data: &'a [u8],
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub(crate) fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
-        let version: u16 = s.read()?;
+        let version: u16 = s.read().ok_or(TableParsingError::Malformed)?;
 
         let table_len = match version {
             0 => 78,
@@ -169,9 +275,13 @@ impl<'a> Table<'a> {
             3 => 96,
             4 => 96,
             5 => 100,
-            _ => return None,
+            _ => return Err(TableParsingError::UnsupportedVersion),
         };
 
+        Self::parse_impl(data, version, table_len).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], version: u16, table_len: usize) -> Option<Self> {
         if data.len() != table_len {
             return None;
         }
@@ -182,11 +292,15 @@ impl<'a> Table<'a> {
         })
     }
 
+    /// Returns font's weight.
     #[inline]
     pub fn weight(&self) -> Weight {
         Weight::from(Stream::read_at::<u16>(self.data, US_WEIGHT_CLASS_OFFSET).unwrap_or(0))
     }
 
+    /// Returns font's width.
+    ///
+    /// Invalid values are treated as `Width::Normal`.
     #[inline]
     pub fn width(&self) -> Width {
         match Stream::read_at::<u16>(self.data, US_WIDTH_CLASS_OFFSET).unwrap_or(0) {
@@ -203,21 +317,27 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Checks that the *Regular* flag is set.
     #[inline]
     pub fn is_regular(&self) -> bool {
         SelectionFlags(self.fs_selection()).regular()
     }
 
+    /// Checks that the *Italic* flag is set.
     #[inline]
     pub fn is_italic(&self) -> bool {
         SelectionFlags(self.fs_selection()).italic()
     }
 
+    /// Checks that the *Bold* flag is set.
     #[inline]
     pub fn is_bold(&self) -> bool {
         SelectionFlags(self.fs_selection()).bold()
     }
 
+    /// Checks that the *Oblique* flag is set.
+    ///
+    /// Always `false` for table versions < 4.
     #[inline]
     pub fn is_oblique(&self) -> bool {
         if self.version < 4 {
@@ -227,8 +347,11 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Checks that the *UseTypoMetrics* flag is set.
+    ///
+    /// Always `false` for table versions < 4.
     #[inline]
-    pub(crate) fn is_use_typo_metrics(&self) -> bool {
+    pub fn is_use_typo_metrics(&self) -> bool {
         if self.version < 4 {
             false
         } else {
@@ -236,6 +359,9 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Returns x height.
+    ///
+    /// Returns `None` for table versions < 2.
     #[inline]
     pub fn x_height(&self) -> Option<i16> {
         if self.version < 2 {
@@ -246,6 +372,9 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Returns capital height.
+    ///
+    /// Returns `None` for table versions < 2.
     #[inline]
     pub fn cap_height(&self) -> Option<i16> {
         if self.version < 2 {
@@ -255,6 +384,7 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Returns strikeout metrics.
     #[inline]
     pub fn strikeout_metrics(&self) -> LineMetrics {
         LineMetrics {
@@ -263,6 +393,7 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Returns subscript metrics.
     #[inline]
     pub fn subscript_metrics(&self) -> ScriptMetrics {
         let mut s = Stream::new_at(self.data, Y_SUBSCRIPT_X_SIZE_OFFSET).unwrap_or_default();
@@ -274,6 +405,7 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Returns superscript metrics.
     #[inline]
     pub fn superscript_metrics(&self) -> ScriptMetrics {
         let mut s = Stream::new_at(self.data, Y_SUPERSCRIPT_X_SIZE_OFFSET).unwrap_or_default();
@@ -285,23 +417,322 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Returns typographic ascender.
     #[inline]
     pub fn typo_ascender(&self) -> i16 {
         Stream::read_at::<i16>(self.data, S_TYPO_ASCENDER_OFFSET).unwrap_or(0)
     }
 
+    /// Returns typographic descender.
     #[inline]
     pub fn typo_descender(&self) -> i16 {
         Stream::read_at::<i16>(self.data, S_TYPO_DESCENDER_OFFSET).unwrap_or(0)
     }
 
+    /// Returns typographic line gap.
     #[inline]
     pub fn typo_line_gap(&self) -> i16 {
         Stream::read_at::<i16>(self.data, S_TYPO_LINE_GAP_OFFSET).unwrap_or(0)
     }
 
+    /// Checks that the *WWS* flag is set.
+    ///
+    /// Indicates that the font has names that are consistent
+    /// with a weight/width/slope family model.
+    ///
+    /// Always `false` for table versions < 4.
+    #[inline]
+    pub fn is_wws(&self) -> bool {
+        if self.version < 4 {
+            false
+        } else {
+            SelectionFlags(self.fs_selection()).wws()
+        }
+    }
+
+    /// Returns the table version.
+    #[inline]
+    pub fn version(&self) -> u16 {
+        u16::from(self.version)
+    }
+
+    /// Returns average width of all non-zero width glyphs.
+    #[inline]
+    pub fn average_char_width(&self) -> i16 {
+        Stream::read_at::<i16>(self.data, X_AVG_CHAR_WIDTH_OFFSET).unwrap_or(0)
+    }
+
+    /// Returns embedding permissions.
+    ///
+    /// When multiple permission bits are set, which is invalid,
+    /// the least restrictive one is returned.
+    #[inline]
+    pub fn permissions(&self) -> Permissions {
+        let flags = TypeFlags(self.fs_type());
+        if flags.editable() {
+            Permissions::Editable
+        } else if flags.preview_and_print() {
+            Permissions::PreviewAndPrint
+        } else if flags.restricted() {
+            Permissions::Restricted
+        } else {
+            Permissions::Installable
+        }
+    }
+
+    /// Checks that the font may be subsetted before embedding.
+    #[inline]
+    pub fn is_subsetting_allowed(&self) -> bool {
+        !TypeFlags(self.fs_type()).no_subsetting()
+    }
+
+    /// Checks that only bitmaps contained in the font may be embedded.
+    ///
+    /// When set, outlines must not be embedded.
+    #[inline]
+    pub fn is_bitmap_embedding_only(&self) -> bool {
+        TypeFlags(self.fs_type()).bitmap_only()
+    }
+
+    /// Returns font family class and subclass as `(class, subclass)`.
+    #[inline]
+    pub fn family_class(&self) -> (u8, u8) {
+        let n = Stream::read_at::<u16>(self.data, S_FAMILY_CLASS_OFFSET).unwrap_or(0);
+        ((n >> 8) as u8, n as u8)
+    }
+
+    /// Returns PANOSE classification.
+    #[inline]
+    pub fn panose(&self) -> Panose {
+        let mut s = Stream::new_at(self.data, PANOSE_OFFSET).unwrap_or_default();
+        Panose {
+            family_type: s.read::<u8>().unwrap_or(0),
+            serif_style: s.read::<u8>().unwrap_or(0),
+            weight: s.read::<u8>().unwrap_or(0),
+            proportion: s.read::<u8>().unwrap_or(0),
+            contrast: s.read::<u8>().unwrap_or(0),
+            stroke_variation: s.read::<u8>().unwrap_or(0),
+            arm_style: s.read::<u8>().unwrap_or(0),
+            letterform: s.read::<u8>().unwrap_or(0),
+            midline: s.read::<u8>().unwrap_or(0),
+            x_height: s.read::<u8>().unwrap_or(0),
+        }
+    }
+
+    /// Returns Unicode ranges.
+    #[inline]
+    pub fn unicode_ranges(&self) -> UnicodeRanges {
+        let mut s = Stream::new_at(self.data, UL_UNICODE_RANGE1_OFFSET).unwrap_or_default();
+        let mut n = 0u128;
+        for i in 0..4 {
+            n |= u128::from(s.read::<u32>().unwrap_or(0)) << (i * 32);
+        }
+
+        UnicodeRanges(n)
+    }
+
+    /// Returns font vendor identification.
+    #[inline]
+    pub fn vendor_id(&self) -> Tag {
+        Stream::read_at::<Tag>(self.data, ACH_VEND_ID_OFFSET).unwrap_or(Tag(0))
+    }
+
+    /// Returns the minimum Unicode index in the font.
+    ///
+    /// Limited to `0xFFFF`.
+    #[inline]
+    pub fn first_char_index(&self) -> u16 {
+        Stream::read_at::<u16>(self.data, US_FIRST_CHAR_INDEX_OFFSET).unwrap_or(0)
+    }
+
+    /// Returns the maximum Unicode index in the font.
+    ///
+    /// Limited to `0xFFFF`.
+    #[inline]
+    pub fn last_char_index(&self) -> u16 {
+        Stream::read_at::<u16>(self.data, US_LAST_CHAR_INDEX_OFFSET).unwrap_or(0)
+    }
+
+    /// Returns Windows ascender.
+    #[inline]
+    pub fn windows_ascender(&self) -> u16 {
+        Stream::read_at::<u16>(self.data, US_WIN_ASCENT_OFFSET).unwrap_or(0)
+    }
+
+    /// Returns Windows descender.
+    ///
+    /// Unlike other descenders, it's a positive value.
+    #[inline]
+    pub fn windows_descender(&self) -> u16 {
+        Stream::read_at::<u16>(self.data, US_WIN_DESCENT_OFFSET).unwrap_or(0)
+    }
+
+    /// Returns code page character ranges.
+    ///
+    /// Returns `None` for table version 0.
+    #[inline]
+    pub fn code_page_ranges(&self) -> Option<CodePageRanges> {
+        if self.version < 1 {
+            return None;
+        }
+
+        let mut s = Stream::new_at(self.data, UL_CODE_PAGE_RANGE1_OFFSET)?;
+        let n1: u32 = s.read()?;
+        let n2: u32 = s.read()?;
+        Some(CodePageRanges(u64::from(n1) | (u64::from(n2) << 32)))
+    }
+
+    /// Returns a character that should be used when a requested one is not in the font.
+    ///
+    /// Returns `None` for table versions < 2.
+    #[inline]
+    pub fn default_char(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at::<u16>(self.data, US_DEFAULT_CHAR_OFFSET)
+        }
+    }
+
+    /// Returns a character that should be used as a word break.
+    ///
+    /// Returns `None` for table versions < 2.
+    #[inline]
+    pub fn break_char(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at::<u16>(self.data, US_BREAK_CHAR_OFFSET)
+        }
+    }
+
+    /// Returns the maximum length of a target glyph context for any feature in the font.
+    ///
+    /// Returns `None` for table versions < 2.
+    #[inline]
+    pub fn max_context(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at::<u16>(self.data, US_MAX_CONTEXT_OFFSET)
+        }
+    }
+
+    /// Returns the optical point size range the font was designed for as `(lower, upper)`.
+    ///
+    /// Values are in TWIPs (1/20 of a point). The upper value is exclusive.
+    ///
+    /// Returns `None` for table versions < 5.
+    #[inline]
+    pub fn optical_size_range(&self) -> Option<(u16, u16)> {
+        if self.version < 5 {
+            None
+        } else {
+            let lower = Stream::read_at::<u16>(self.data, US_LOWER_OPTICAL_POINT_SIZE_OFFSET)?;
+            let upper = Stream::read_at::<u16>(self.data, US_UPPER_OPTICAL_POINT_SIZE_OFFSET)?;
+            Some((lower, upper))
+        }
+    }
+
+    #[inline]
+    fn fs_type(&self) -> u16 {
+        Stream::read_at::<u16>(self.data, FS_TYPE_OFFSET).unwrap_or(0)
+    }
+
     #[inline]
     fn fs_selection(&self) -> u16 {
         Stream::read_at::<u16>(self.data, FS_SELECTION_OFFSET).unwrap_or(0)
     }
 }
+
+
+impl core::fmt::Debug for Table<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Table {{ version: {} }}", self.version)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn version_5() {
+        let data = convert(&[
+            UInt16(5), // version
+            Int16(500), // average char width
+            UInt16(400), // weight class
+            UInt16(5), // width class
+            UInt16(0x0104), // type flags
+            Raw(&[0; 20]), // subscript and superscript metrics, strikeout metrics
+            UInt16(0x0801), // family class
+            Raw(&[2, 11, 5, 3, 3, 4, 3, 2, 2, 4]), // PANOSE
+            UInt32(0x00000003), // Unicode range 1
+            UInt32(0), // Unicode range 2
+            UInt32(0), // Unicode range 3
+            UInt32(0x02000000), // Unicode range 4
+            Raw(b"ADBE"), // vendor ID
+            UInt16(0x0100), // selection flags
+            UInt16(0x0020), // first char index
+            UInt16(0xFFFD), // last char index
+            Int16(750), // typo ascender
+            Int16(-250), // typo descender
+            Int16(0), // typo line gap
+            UInt16(900), // windows ascender
+            UInt16(300), // windows descender
+            UInt32(0x00000001), // code page range 1
+            UInt32(0x80000000), // code page range 2
+            Int16(500), // x height
+            Int16(700), // cap height
+            UInt16(0), // default char
+            UInt16(0x0020), // break char
+            UInt16(3), // max context
+            UInt16(180), // lower optical point size
+            UInt16(480), // upper optical point size
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.version(), 5);
+        assert_eq!(table.average_char_width(), 500);
+        assert_eq!(table.permissions(), Permissions::PreviewAndPrint);
+        assert!(!table.is_subsetting_allowed());
+        assert!(!table.is_bitmap_embedding_only());
+        assert_eq!(table.family_class(), (8, 1));
+        assert_eq!(table.panose().family_type, 2);
+        assert_eq!(table.panose().x_height, 4);
+        assert!(table.unicode_ranges().is_set(0));
+        assert!(table.unicode_ranges().is_set(1));
+        assert!(!table.unicode_ranges().is_set(2));
+        assert!(table.unicode_ranges().is_set(121));
+        assert!(!table.unicode_ranges().is_set(200));
+        assert_eq!(table.vendor_id(), Tag::from_bytes(b"ADBE"));
+        assert!(table.is_wws());
+        assert_eq!(table.first_char_index(), 0x0020);
+        assert_eq!(table.last_char_index(), 0xFFFD);
+        assert_eq!(table.windows_ascender(), 900);
+        assert_eq!(table.windows_descender(), 300);
+        let code_pages = table.code_page_ranges().unwrap();
+        assert!(code_pages.is_set(0));
+        assert!(code_pages.is_set(63));
+        assert!(!code_pages.is_set(1));
+        assert_eq!(table.default_char(), Some(0));
+        assert_eq!(table.break_char(), Some(0x0020));
+        assert_eq!(table.max_context(), Some(3));
+        assert_eq!(table.optical_size_range(), Some((180, 480)));
+    }
+
+    #[test]
+    fn version_0() {
+        let mut data = vec![0; 78];
+        data[8] = 0x01; // type flags: no subsetting
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.permissions(), Permissions::Installable);
+        assert!(!table.is_subsetting_allowed());
+        assert_eq!(table.code_page_ranges(), None);
+        assert_eq!(table.default_char(), None);
+        assert_eq!(table.optical_size_range(), None);
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{LineMetrics, GlyphId};
 use crate::parser::{Stream, Fixed, LazyArray16};
+use crate::TableParsingError;
 
 
 const TABLE_SIZE: usize = 32;
@@ -278,24 +279,30 @@ pub struct Table<'a> {
     italic_angle: f32,
     underline: LineMetrics,
     is_monospaced: bool,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    number_of_glyphs: Option<u16>,
     name_indexes: LazyArray16<'a, u16>,
     names: &'a [u8],
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
-        if data.len() < TABLE_SIZE {
-            return None;
-        }
-
-        let version: u32 = Stream::new(data).read()?;
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let version: u32 = Stream::new(data).read().ok_or(TableParsingError::Malformed)?;
         if !(version == 0x00010000 || version == 0x00020000 ||
              version == 0x00025000 || version == 0x00030000 ||
              version == 0x00040000)
         {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        if data.len() < TABLE_SIZE {
+            return Err(TableParsingError::Malformed);
         }
 
+        Self::parse_impl(data, version).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], version: u32) -> Option<Self> {
         let italic_angle = Stream::read_at::<Fixed>(data, ITALIC_ANGLE_OFFSET)?.0;
 
         let underline = LineMetrics {
@@ -305,6 +312,7 @@ impl<'a> Table<'a> {
 
         let is_monospaced = Stream::read_at::<u32>(data, IS_FIXED_PITCH_OFFSET)? != 0;
 
+        let mut number_of_glyphs = None;
         let mut name_indexes = LazyArray16::default();
         let mut names: &[u8] = &[];
 
@@ -313,6 +321,7 @@ impl<'a> Table<'a> {
             let mut s = Stream::new_at(data, TABLE_SIZE)?;
             let count: u16 = s.read()?;
             name_indexes = s.read_array16::<u16>(count)?;
+            number_of_glyphs = Some(count);
             names = s.tail()?;
         }
 
@@ -320,11 +329,21 @@ impl<'a> Table<'a> {
             italic_angle,
             underline,
             is_monospaced,
+            number_of_glyphs,
             name_indexes,
             names,
         })
     }
 
+    /// Returns the number of glyphs in the table.
+    ///
+    /// Only version 2.0 of the table stores it.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn number_of_glyphs(&self) -> Option<u16> {
+        self.number_of_glyphs
+    }
+
     #[inline]
     pub fn italic_angle(&self) -> f32 {
         self.italic_angle
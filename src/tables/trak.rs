@@ -0,0 +1,232 @@
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6trak.html
+
+use crate::parser::{Stream, FromData, LazyArray16, Fixed, Offset16, Offset32, Offset};
+use crate::TableParsingError;
+
+
+/// A tracking value.
+///
+/// Both values are in font units.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Tracking {
+    /// Horizontal tracking.
+    ///
+    /// Set to 0 when the table has no horizontal tracking data.
+    pub horizontal: f32,
+
+    /// Vertical tracking.
+    ///
+    /// Set to 0 when the table has no vertical tracking data.
+    pub vertical: f32,
+}
+
+
+#[derive(Clone, Copy)]
+struct TrackTableRecord {
+    track: f32,
+    // We don't care about `nameIndex`.
+    values_offset: Offset16,
+}
+
+impl FromData for TrackTableRecord {
+    const SIZE: usize = 8;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let track = s.read::<Fixed>()?.0;
+        s.skip::<u16>(); // name index
+        Some(TrackTableRecord {
+            track,
+            values_offset: s.read::<Offset16>()?,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct TrackData<'a> {
+    tracks: LazyArray16<'a, TrackTableRecord>,
+    sizes: LazyArray16<'a, Fixed>,
+    data: &'a [u8], // The whole `trak` table. All offsets are from its start.
+}
+
+impl<'a> TrackData<'a> {
+    fn parse(offset: usize, data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new_at(data, offset)?;
+        let number_of_tracks: u16 = s.read()?;
+        let number_of_sizes: u16 = s.read()?;
+        let size_table_offset = s.read::<Offset32>()?.to_usize();
+        let tracks = s.read_array16::<TrackTableRecord>(number_of_tracks)?;
+
+        let mut s = Stream::new_at(data, size_table_offset)?;
+        let sizes = s.read_array16::<Fixed>(number_of_sizes)?;
+
+        Some(TrackData {
+            tracks,
+            sizes,
+            data,
+        })
+    }
+
+    fn tracking(&self, point_size: f32, track: f32) -> Option<f32> {
+        // Tracks are sorted by the track value.
+        // Use the closest track when the requested one is out of range.
+        let mut lower = self.tracks.get(0)?;
+        let mut upper = lower;
+        for record in self.tracks {
+            if record.track <= track {
+                lower = record;
+                upper = record;
+            } else {
+                upper = record;
+                break;
+            }
+        }
+
+        let lower_value = self.track_value(lower, point_size)?;
+        if lower.track == upper.track || track <= lower.track {
+            return Some(lower_value);
+        }
+
+        let upper_value = self.track_value(upper, point_size)?;
+        let t = (track - lower.track) / (upper.track - lower.track);
+        Some(lower_value + t * (upper_value - lower_value))
+    }
+
+    fn track_value(&self, record: TrackTableRecord, point_size: f32) -> Option<f32> {
+        let mut s = Stream::new_at(self.data, record.values_offset.to_usize())?;
+        let values = s.read_array16::<i16>(self.sizes.len())?;
+
+        match self.sizes.len() {
+            0 => return None,
+            1 => return values.get(0).map(f32::from),
+            _ => {}
+        }
+
+        // Find the first size that is larger or equal to the requested one.
+        // Values for sizes outside the table are extrapolated, just like CoreText does.
+        let mut idx = 0;
+        while idx + 1 < self.sizes.len() - 1 && self.sizes.get(idx + 1)?.0 < point_size {
+            idx += 1;
+        }
+
+        let s0 = self.sizes.get(idx)?.0;
+        let s1 = self.sizes.get(idx + 1)?.0;
+        let v0 = f32::from(values.get(idx)?);
+        let v1 = f32::from(values.get(idx + 1)?);
+        let t = if s0 == s1 { 0.0 } else { (point_size - s0) / (s1 - s0) };
+        Some(v0 + t * (v1 - v0))
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    horizontal: Option<TrackData<'a>>,
+    vertical: Option<TrackData<'a>>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let mut s = Stream::new(data);
+
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
+        if version != 0x00010000 {
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        let format: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+        if format != 0 {
+            return Err(TableParsingError::UnsupportedFormat);
+        }
+
+        Self::parse_impl(data, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], mut s: Stream<'a>) -> Option<Self> {
+        let horizontal_offset: Option<Offset16> = s.read()?;
+        let vertical_offset: Option<Offset16> = s.read()?;
+
+        // A malformed track data is treated as a missing one.
+        Some(Table {
+            horizontal: horizontal_offset.and_then(|offset| TrackData::parse(offset.to_usize(), data)),
+            vertical: vertical_offset.and_then(|offset| TrackData::parse(offset.to_usize(), data)),
+        })
+    }
+
+    pub fn tracking(&self, point_size: f32, track: f32) -> Tracking {
+        Tracking {
+            horizontal: self.horizontal.and_then(|d| d.tracking(point_size, track)).unwrap_or(0.0),
+            vertical: self.vertical.and_then(|d| d.tracking(point_size, track)).unwrap_or(0.0),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = &[
+        0x00, 0x01, 0x00, 0x00, // version: 1.0
+        0x00, 0x00, // format: 0
+        0x00, 0x0C, // horizontal offset: 12
+        0x00, 0x00, // vertical offset: 0
+        0x00, 0x00, // reserved
+        // Horizontal track data
+        0x00, 0x02, // number of tracks: 2
+        0x00, 0x02, // number of sizes: 2
+        0x00, 0x00, 0x00, 0x24, // size table offset: 36
+        // Track [0]
+        0x00, 0x00, 0x00, 0x00, // track: 0.0
+        0x01, 0x00, // name index: 256
+        0x00, 0x2C, // offset: 44
+        // Track [1]
+        0x00, 0x01, 0x00, 0x00, // track: 1.0
+        0x01, 0x01, // name index: 257
+        0x00, 0x30, // offset: 48
+        // Size table
+        0x00, 0x0C, 0x00, 0x00, // size [0]: 12.0
+        0x00, 0x18, 0x00, 0x00, // size [1]: 24.0
+        // Track [0] values
+        0x00, 0x00, // value [0]: 0
+        0xFF, 0xF4, // value [1]: -12
+        // Track [1] values
+        0x00, 0x64, // value [0]: 100
+        0x00, 0x32, // value [1]: 50
+    ];
+
+    #[test]
+    fn interpolate_size() {
+        let table = Table::parse(DATA).unwrap();
+        assert_eq!(table.tracking(12.0, 0.0), Tracking { horizontal: 0.0, vertical: 0.0 });
+        assert_eq!(table.tracking(18.0, 0.0), Tracking { horizontal: -6.0, vertical: 0.0 });
+        assert_eq!(table.tracking(24.0, 0.0), Tracking { horizontal: -12.0, vertical: 0.0 });
+        assert_eq!(table.tracking(36.0, 0.0), Tracking { horizontal: -24.0, vertical: 0.0 });
+        assert_eq!(table.tracking(6.0, 1.0), Tracking { horizontal: 125.0, vertical: 0.0 });
+    }
+
+    #[test]
+    fn interpolate_track() {
+        let table = Table::parse(DATA).unwrap();
+        assert_eq!(table.tracking(12.0, 0.5).horizontal, 50.0);
+        assert_eq!(table.tracking(24.0, 0.5).horizontal, 19.0);
+        // Out of range tracks are clamped.
+        assert_eq!(table.tracking(12.0, -1.0).horizontal, 0.0);
+        assert_eq!(table.tracking(12.0, 2.0).horizontal, 100.0);
+    }
+
+    #[test]
+    fn unknown_version() {
+        let data = &[
+            0x00, 0x02, 0x00, 0x00, // version: 2.0
+            0x00, 0x00, // format: 0
+            0x00, 0x00, // horizontal offset: 0
+            0x00, 0x00, // vertical offset: 0
+            0x00, 0x00, // reserved
+        ];
+
+        assert_eq!(Table::parse(data).err(), Some(TableParsingError::UnsupportedVersion));
+    }
+}
@@ -0,0 +1,186 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/vdmx
+
+use crate::parser::{Stream, FromData, LazyArray16, Offset, Offset16};
+use crate::TableParsingError;
+
+
+/// Vertical device metrics.
+///
+/// Both values are in pixels.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VerticalDeviceMetrics {
+    /// Maximum value for all glyphs in the font.
+    pub y_max: i16,
+    /// Minimum value for all glyphs in the font.
+    pub y_min: i16,
+}
+
+
+#[derive(Clone, Copy)]
+struct RatioRange {
+    x_ratio: u8,
+    y_start_ratio: u8,
+    y_end_ratio: u8,
+}
+
+impl RatioRange {
+    fn contains(&self, x_ratio: u8, y_ratio: u8) -> bool {
+        // A default ratio. Matches any aspect ratio.
+        if self.x_ratio == 0 {
+            return true;
+        }
+
+        if x_ratio == 0 {
+            return false;
+        }
+
+        // Compare `y_ratio / x_ratio` with `[y_start_ratio, y_end_ratio] / self.x_ratio`.
+        let y = u32::from(y_ratio) * u32::from(self.x_ratio);
+        let x = u32::from(x_ratio);
+        u32::from(self.y_start_ratio) * x <= y && y <= u32::from(self.y_end_ratio) * x
+    }
+}
+
+impl FromData for RatioRange {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        s.skip::<u8>(); // character set
+        Some(RatioRange {
+            x_ratio: s.read::<u8>()?,
+            y_start_ratio: s.read::<u8>()?,
+            y_end_ratio: s.read::<u8>()?,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct VTableRecord {
+    y_pel_height: u16,
+    y_max: i16,
+    y_min: i16,
+}
+
+impl FromData for VTableRecord {
+    const SIZE: usize = 6;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(VTableRecord {
+            y_pel_height: s.read::<u16>()?,
+            y_max: s.read::<i16>()?,
+            y_min: s.read::<i16>()?,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub struct Table<'a> {
+    data: &'a [u8], // The whole `VDMX` table. All offsets are from its start.
+    ratios: LazyArray16<'a, RatioRange>,
+    offsets: LazyArray16<'a, Offset16>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
+        let mut s = Stream::new(data);
+        let version: u16 = s.read().ok_or(TableParsingError::Malformed)?;
+        if version > 1 {
+            return Err(TableParsingError::UnsupportedVersion);
+        }
+
+        Self::parse_impl(data, s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(data: &'a [u8], mut s: Stream<'a>) -> Option<Self> {
+        s.skip::<u16>(); // number of groups
+        let number_of_ratios: u16 = s.read()?;
+        let ratios = s.read_array16::<RatioRange>(number_of_ratios)?;
+        let offsets = s.read_array16::<Offset16>(number_of_ratios)?;
+        Some(Table {
+            data,
+            ratios,
+            offsets,
+        })
+    }
+
+    pub fn metrics(&self, pixels_per_em: u16, x_ratio: u8, y_ratio: u8) -> Option<VerticalDeviceMetrics> {
+        // The first matching ratio is used.
+        let (index, _) = self.ratios.into_iter().enumerate()
+            .find(|(_, ratio)| ratio.contains(x_ratio, y_ratio))?;
+        let offset = self.offsets.get(index as u16)?;
+
+        let mut s = Stream::new_at(self.data, offset.to_usize())?;
+        let count: u16 = s.read()?;
+        s.skip::<u8>(); // start size
+        s.skip::<u8>(); // end size
+        let records = s.read_array16::<VTableRecord>(count)?;
+        let (_, record) = records.binary_search_by(|r| r.y_pel_height.cmp(&pixels_per_em))?;
+        Some(VerticalDeviceMetrics {
+            y_max: record.y_max,
+            y_min: record.y_min,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn basic() {
+        let data = convert(&[
+            UInt16(1), // version
+            UInt16(2), // number of groups
+            UInt16(2), // number of ratios
+            // Ratio [0]
+            UInt8(1), // character set
+            UInt8(2), // x ratio
+            UInt8(1), // y start ratio
+            UInt8(1), // y end ratio
+            // Ratio [1]
+            UInt8(1), // character set
+            UInt8(0), // x ratio
+            UInt8(0), // y start ratio
+            UInt8(0), // y end ratio
+            // Offsets
+            UInt16(18), // offset [0]
+            UInt16(28), // offset [1]
+            // Group [0]
+            UInt16(1), // number of records
+            UInt8(10), // start size
+            UInt8(10), // end size
+            UInt16(10), // y pel height
+            Int16(8), // y max
+            Int16(-2), // y min
+            // Group [1]
+            UInt16(2), // number of records
+            UInt8(10), // start size
+            UInt8(11), // end size
+            UInt16(10), // y pel height
+            Int16(9), // y max
+            Int16(-3), // y min
+            UInt16(11), // y pel height
+            Int16(10), // y max
+            Int16(-3), // y min
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let metrics = |ppem, x, y| table.metrics(ppem, x, y).map(|m| (m.y_max, m.y_min));
+        assert_eq!(metrics(10, 2, 1), Some((8, -2)));
+        assert_eq!(metrics(10, 4, 2), Some((8, -2)));
+        assert_eq!(metrics(10, 1, 1), Some((9, -3)));
+        assert_eq!(metrics(11, 1, 1), Some((10, -3)));
+        // The first matching group has no such size.
+        assert_eq!(metrics(11, 2, 1), None);
+        assert_eq!(metrics(12, 1, 1), None);
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::GlyphId;
 use crate::parser::{Stream, FromData, LazyArray16};
+use crate::TableParsingError;
 
 
 #[derive(Clone, Copy)]
@@ -31,14 +32,18 @@ pub struct Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    pub fn parse(data: &'a [u8]) -> Option<Self> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, TableParsingError> {
         let mut s = Stream::new(data);
 
-        let version: u32 = s.read()?;
+        let version: u32 = s.read().ok_or(TableParsingError::Malformed)?;
         if version != 0x00010000 {
-            return None;
+            return Err(TableParsingError::UnsupportedVersion);
         }
 
+        Self::parse_impl(s).ok_or(TableParsingError::Malformed)
+    }
+
+    fn parse_impl(mut s: Stream<'a>) -> Option<Self> {
         let default_y: i16 = s.read()?;
         let count: u16 = s.read()?;
         let origins = s.read_array16::<VertOriginYMetrics>(count)?;
@@ -0,0 +1,542 @@
+use std::vec::Vec;
+
+use crate::{Face, GlyphId, Magic, Tag, TableRecord, TableParsingError};
+use crate::parser::{Stream, NumFrom};
+use crate::tables::{cff1, head, hhea};
+use crate::woff::checksum;
+
+
+/// A face validation issue.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ValidationIssue {
+    /// A table checksum doesn't match the one from the table record.
+    ChecksumMismatch {
+        /// A table tag.
+        tag: Tag,
+        /// A checksum from the table record.
+        expected: u32,
+        /// An actual table data checksum.
+        actual: u32,
+    },
+
+    /// `head.checkSumAdjustment` doesn't match the font data.
+    ///
+    /// Checked only for faces that are not a part of a font collection.
+    ChecksumAdjustmentMismatch {
+        /// A value from the `head` table.
+        expected: u32,
+        /// An actual value.
+        actual: u32,
+    },
+
+    /// A table range is out of the font data bounds.
+    TableOutOfBounds(Tag),
+
+    /// Two table ranges are overlapping.
+    OverlappingTables(Tag, Tag),
+
+    /// A table is present, but failed to parse and will be ignored.
+    ///
+    /// Tables not supported by this library are not checked.
+    /// Out of bounds tables are reported via `TableOutOfBounds`.
+    MalformedTable(Tag, TableParsingError),
+
+    /// `loca` offsets are not in ascending order.
+    ///
+    /// The glyph is the one with the offset greater than the next one.
+    UnorderedLocaOffsets(GlyphId),
+
+    /// The last `loca` offset is past the `glyf` table end.
+    LocaOutOfBounds,
+
+    /// A number of glyphs in a table doesn't match `maxp.numGlyphs`.
+    NumberOfGlyphsMismatch {
+        /// A table tag.
+        tag: Tag,
+        /// A number of glyphs in the table.
+        ///
+        /// For `hmtx`, this is `hhea.numberOfHMetrics`.
+        number_of_glyphs: u32,
+    },
+
+    /// A `cmap` subtable maps code points to glyphs that are past `maxp.numGlyphs`.
+    ///
+    /// Consecutive code points are reported as a single range
+    /// and only the first 16 ranges of each subtable are reported.
+    CmapGlyphOutOfBounds {
+        /// A subtable index.
+        subtable: u16,
+        /// The first code point in the range.
+        first_code_point: u32,
+        /// The last code point in the range. Inclusive.
+        last_code_point: u32,
+    },
+}
+
+
+/// A face validation report.
+///
+/// Created by `Face::validate`.
+#[derive(Clone, Default, Debug)]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns a list of found issues.
+    #[inline]
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    /// Checks that no issues were found.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+
+pub(crate) fn validate(face: &Face) -> ValidationReport {
+    let mut issues = Vec::new();
+    check_table_ranges(face, &mut issues);
+    check_checksums(face, &mut issues);
+    check_parsed_tables(face, &mut issues);
+    check_loca(face, &mut issues);
+    check_number_of_glyphs(face, &mut issues);
+    check_cmap(face, &mut issues);
+    ValidationReport { issues }
+}
+
+fn table_range(record: &TableRecord) -> Option<core::ops::Range<usize>> {
+    let offset = usize::num_from(record.offset);
+    let end = offset.checked_add(usize::num_from(record.length))?;
+    Some(offset..end)
+}
+
+fn check_table_ranges(face: &Face, issues: &mut Vec<ValidationIssue>) {
+    let mut ranges = Vec::new();
+    for record in face.table_records {
+        match table_range(&record) {
+            Some(range) if range.end <= face.font_data.len() => {
+                if range.start != range.end {
+                    ranges.push((record.tag, range));
+                }
+            }
+            _ => issues.push(ValidationIssue::TableOutOfBounds(record.tag)),
+        }
+    }
+
+    ranges.sort_by_key(|(_, range)| range.start);
+
+    // Compare each table with all the following tables that start before it ends.
+    for (i, (tag, range)) in ranges.iter().enumerate() {
+        for (other_tag, other_range) in &ranges[i + 1..] {
+            if other_range.start >= range.end {
+                break;
+            }
+
+            issues.push(ValidationIssue::OverlappingTables(*tag, *other_tag));
+        }
+    }
+}
+
+fn check_checksums(face: &Face, issues: &mut Vec<ValidationIssue>) {
+    let head_tag = Tag::from_bytes(b"head");
+    for record in face.table_records {
+        let data = match table_range(&record).and_then(|range| face.font_data.get(range)) {
+            Some(data) => data,
+            None => continue, // Already reported.
+        };
+
+        let actual = checksum(data, record.tag == head_tag);
+        if actual != record.check_sum {
+            issues.push(ValidationIssue::ChecksumMismatch {
+                tag: record.tag,
+                expected: record.check_sum,
+                actual,
+            });
+        }
+    }
+
+    // `checkSumAdjustment` is calculated for the whole font file,
+    // which is not defined for font collections.
+    if Stream::read_at::<Magic>(face.font_data, 0) == Some(Magic::FontCollection) {
+        return;
+    }
+
+    let head_record = face.table_records.into_iter().find(|r| r.tag == head_tag);
+    let head_offset = match head_record {
+        Some(record) => usize::num_from(record.offset),
+        None => return,
+    };
+
+    // The adjustment must be aligned to be excluded from the whole font checksum.
+    if head_offset % 4 != 0 {
+        return;
+    }
+
+    let expected = match head::check_sum_adjustment(face.head) {
+        Some(v) => v,
+        None => return,
+    };
+
+    let font_checksum = checksum(face.font_data, false).wrapping_sub(expected);
+    let actual = 0xB1B0AFBAu32.wrapping_sub(font_checksum);
+    if actual != expected {
+        issues.push(ValidationIssue::ChecksumAdjustmentMismatch { expected, actual });
+    }
+}
+
+fn check_parsed_tables(face: &Face, issues: &mut Vec<ValidationIssue>) {
+    for table in face.rejected_tables() {
+        // Out of bounds tables are already reported.
+        if table.error != TableParsingError::OutOfBounds {
+            issues.push(ValidationIssue::MalformedTable(table.tag, table.error));
+        }
+    }
+}
+
+fn check_loca(face: &Face, issues: &mut Vec<ValidationIssue>) {
+    let loca = match face.loca {
+        Some(v) => v,
+        None => return,
+    };
+
+    let mut prev = 0;
+    for i in 0..loca.len() {
+        let offset = loca.offset(i).unwrap_or(0);
+        if i != 0 && offset < prev {
+            issues.push(ValidationIssue::UnorderedLocaOffsets(GlyphId(i - 1)));
+        }
+
+        prev = offset;
+    }
+
+    if let Some(glyf) = face.glyf {
+        if prev > glyf.len() {
+            issues.push(ValidationIssue::LocaOutOfBounds);
+        }
+    }
+}
+
+fn check_number_of_glyphs(face: &Face, issues: &mut Vec<ValidationIssue>) {
+    let number_of_glyphs = face.number_of_glyphs.get();
+
+    // `hmtx` can have less metrics than glyphs, but not more.
+    if let Some(n) = hhea::number_of_h_metrics(face.hhea) {
+        if face.hmtx.is_some() && n.get() > number_of_glyphs {
+            issues.push(ValidationIssue::NumberOfGlyphsMismatch {
+                tag: Tag::from_bytes(b"hmtx"),
+                number_of_glyphs: u32::from(n.get()),
+            });
+        }
+    }
+
+    if let Some(n) = face.post.and_then(|post| post.number_of_glyphs()) {
+        if n != number_of_glyphs {
+            issues.push(ValidationIssue::NumberOfGlyphsMismatch {
+                tag: Tag::from_bytes(b"post"),
+                number_of_glyphs: u32::from(n),
+            });
+        }
+    }
+
+    if let Some(ref metadata) = face.cff1 {
+        let n = cff1::number_of_glyphs(metadata);
+        if n != u32::from(number_of_glyphs) {
+            issues.push(ValidationIssue::NumberOfGlyphsMismatch {
+                tag: Tag::from_bytes(b"CFF "),
+                number_of_glyphs: n,
+            });
+        }
+    }
+}
+
+fn check_cmap(face: &Face, issues: &mut Vec<ValidationIssue>) {
+    // Only the Unicode range is checked, since code points are checked one by one.
+    const MAX_CODE_POINT: u32 = 0x10FFFF;
+    // Not defined in the spec. A single malformed subtable can produce
+    // a range per code point, which is not useful in a report.
+    const MAX_RANGES: usize = 16;
+
+    let number_of_glyphs = face.number_of_glyphs.get();
+    for (index, subtable) in face.character_mapping_subtables().enumerate() {
+        let issue = |(first_code_point, last_code_point)| ValidationIssue::CmapGlyphOutOfBounds {
+            subtable: index as u16,
+            first_code_point,
+            last_code_point,
+        };
+
+        let mut range: Option<(u32, u32)> = None;
+        let mut ranges_count = 0;
+        subtable.codepoints(|code_point| {
+            // Stop looking up glyphs once the limit is reached.
+            if code_point > MAX_CODE_POINT || ranges_count == MAX_RANGES {
+                return;
+            }
+
+            match subtable.glyph_index(code_point) {
+                Some(glyph_id) if glyph_id.0 >= number_of_glyphs => {}
+                _ => return,
+            }
+
+            match range {
+                Some((first, last)) if last + 1 == code_point => {
+                    range = Some((first, code_point));
+                }
+                Some(prev) => {
+                    issues.push(issue(prev));
+                    ranges_count += 1;
+                    range = Some((code_point, code_point));
+                }
+                None => range = Some((code_point, code_point)),
+            }
+        });
+
+        if let Some(range) = range {
+            if ranges_count < MAX_RANGES {
+                issues.push(issue(range));
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    fn head() -> Vec<u8> {
+        let mut data = vec![0; 54];
+        data[18..20].copy_from_slice(&1000u16.to_be_bytes()); // units per em
+        data
+    }
+
+    #[test]
+    fn valid() {
+        let mut data = convert(&[
+            TrueTypeMagic,
+            UInt16(3), // number of tables
+            UInt16(32), // search range
+            UInt16(1), // entry selector
+            UInt16(16), // range shift
+            Raw(b"head"), // tag
+            UInt32(0x000003E8), // checksum
+            UInt32(60), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(116), // offset
+            UInt32(36), // length
+            Raw(b"maxp"), // tag
+            UInt32(0x00015000), // checksum
+            UInt32(152), // offset
+            UInt32(6), // length
+        ]);
+        data.extend_from_slice(&head());
+        data.extend_from_slice(&[0; 2]); // padding
+        data.extend_from_slice(&[0; 36]); // hhea
+        data.extend_from_slice(&convert(&[
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ]));
+        data.extend_from_slice(&[0; 2]); // padding
+
+        // Set `checkSumAdjustment`.
+        let adjustment = 0xB1B0AFBAu32.wrapping_sub(checksum(&data, false));
+        data[68..72].copy_from_slice(&adjustment.to_be_bytes());
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        let report = face.validate();
+        assert_eq!(report.issues(), &[]);
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn invalid() {
+        let mut data = convert(&[
+            TrueTypeMagic,
+            UInt16(5), // number of tables
+            UInt16(64), // search range
+            UInt16(2), // entry selector
+            UInt16(16), // range shift
+            Raw(b"GSUB"), // tag
+            UInt32(0), // checksum
+            UInt32(1000), // offset
+            UInt32(10), // length
+            Raw(b"head"), // tag
+            UInt32(0x000003E8), // checksum
+            UInt32(92), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(146), // offset
+            UInt32(36), // length
+            Raw(b"maxp"), // tag
+            UInt32(0x00015000), // checksum
+            UInt32(182), // offset
+            UInt32(6), // length
+            Raw(b"post"), // tag
+            UInt32(0), // checksum
+            UInt32(182), // offset
+            UInt32(4), // length
+        ]);
+        data.extend_from_slice(&head());
+        data.extend_from_slice(&[0; 36]); // hhea
+        data.extend_from_slice(&convert(&[
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ]));
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        let report = face.validate();
+        assert_eq!(report.issues(), &[
+            ValidationIssue::TableOutOfBounds(Tag::from_bytes(b"GSUB")),
+            ValidationIssue::OverlappingTables(Tag::from_bytes(b"maxp"), Tag::from_bytes(b"post")),
+            ValidationIssue::ChecksumMismatch {
+                tag: Tag::from_bytes(b"post"),
+                expected: 0,
+                actual: 0x00005000,
+            },
+            ValidationIssue::ChecksumAdjustmentMismatch {
+                expected: 0,
+                actual: 0xB1B0AFBAu32.wrapping_sub(checksum(&data, false)),
+            },
+            ValidationIssue::MalformedTable(Tag::from_bytes(b"post"), TableParsingError::UnsupportedVersion),
+        ][..]);
+    }
+
+    fn cmap_issues(face: &Face) -> Vec<ValidationIssue> {
+        face.validate().issues().iter().cloned().filter(|issue| match issue {
+            ValidationIssue::CmapGlyphOutOfBounds { .. } => true,
+            _ => false,
+        }).collect()
+    }
+
+    #[test]
+    fn cmap_oversized_group() {
+        let mut data = convert(&[
+            TrueTypeMagic,
+            UInt16(4), // number of tables
+            UInt16(64), // search range
+            UInt16(2), // entry selector
+            UInt16(0), // range shift
+            Raw(b"cmap"), // tag
+            UInt32(0), // checksum
+            UInt32(76), // offset
+            UInt32(40), // length
+            Raw(b"head"), // tag
+            UInt32(0), // checksum
+            UInt32(116), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(172), // offset
+            UInt32(36), // length
+            Raw(b"maxp"), // tag
+            UInt32(0), // checksum
+            UInt32(208), // offset
+            UInt32(6), // length
+            // cmap
+            UInt16(0), // version
+            UInt16(1), // number of tables
+            UInt16(3), // platform ID
+            UInt16(10), // encoding ID
+            UInt32(12), // offset
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(28), // length
+            UInt32(0), // language
+            UInt32(1), // number of groups
+            UInt32(0), // start char code
+            UInt32(0xFFFFFFFF), // end char code
+            UInt32(0), // glyph ID
+        ]);
+        data.extend_from_slice(&head());
+        data.extend_from_slice(&[0; 2]); // padding
+        data.extend_from_slice(&[0; 36]); // hhea
+        data.extend_from_slice(&convert(&[
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ]));
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        // Code points past U+FFFF are mapped to glyph IDs that do not fit into u16.
+        assert_eq!(cmap_issues(&face), &[
+            ValidationIssue::CmapGlyphOutOfBounds {
+                subtable: 0,
+                first_code_point: 1,
+                last_code_point: 0xFFFF,
+            },
+        ]);
+    }
+
+    #[test]
+    fn cmap_too_many_ranges() {
+        let number_of_groups = 20;
+        let cmap_length = 16 + number_of_groups * 12;
+        let mut data = convert(&[
+            TrueTypeMagic,
+            UInt16(4), // number of tables
+            UInt16(64), // search range
+            UInt16(2), // entry selector
+            UInt16(0), // range shift
+            Raw(b"cmap"), // tag
+            UInt32(0), // checksum
+            UInt32(76), // offset
+            UInt32(12 + cmap_length), // length
+            Raw(b"head"), // tag
+            UInt32(0), // checksum
+            UInt32(88 + cmap_length), // offset
+            UInt32(54), // length
+            Raw(b"hhea"), // tag
+            UInt32(0), // checksum
+            UInt32(144 + cmap_length), // offset
+            UInt32(36), // length
+            Raw(b"maxp"), // tag
+            UInt32(0), // checksum
+            UInt32(180 + cmap_length), // offset
+            UInt32(6), // length
+            // cmap
+            UInt16(0), // version
+            UInt16(1), // number of tables
+            UInt16(3), // platform ID
+            UInt16(10), // encoding ID
+            UInt32(12), // offset
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(cmap_length), // length
+            UInt32(0), // language
+            UInt32(number_of_groups), // number of groups
+        ]);
+
+        // Every even code point is mapped to a missing glyph.
+        for i in 0..number_of_groups {
+            data.extend_from_slice(&convert(&[
+                UInt32(i * 2), // start char code
+                UInt32(i * 2), // end char code
+                UInt32(5), // glyph ID
+            ]));
+        }
+
+        data.extend_from_slice(&head());
+        data.extend_from_slice(&[0; 2]); // padding
+        data.extend_from_slice(&[0; 36]); // hhea
+        data.extend_from_slice(&convert(&[
+            UInt32(0x00005000), // version
+            UInt16(1), // number of glyphs
+        ]));
+
+        let face = Face::from_slice(&data, 0).unwrap();
+        let issues = cmap_issues(&face);
+        assert_eq!(issues.len(), 16);
+        assert_eq!(issues.last(), Some(&ValidationIssue::CmapGlyphOutOfBounds {
+            subtable: 0,
+            first_code_point: 30,
+            last_code_point: 30,
+        }));
+    }
+}
@@ -0,0 +1,470 @@
+//! A [WOFF 1.0](https://www.w3.org/TR/WOFF/) container decoder.
+//!
+//! Unlike the rest of the crate, the decoder allocates,
+//! since WOFF tables are usually zlib-compressed.
+//! The decoded data can be parsed via `Face::from_slice`.
+//!
+//! Available only with the `std` feature.
+
+use core::convert::TryFrom;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{FaceParsingError, Tag};
+use crate::inflate;
+use crate::parser::{Stream, FromData, LazyArray16};
+
+const SIGNATURE: u32 = 0x774F4646; // wOFF
+const HEADER_SIZE: usize = 44;
+pub(crate) const SFNT_HEADER_SIZE: usize = 12;
+pub(crate) const SFNT_TABLE_RECORD_SIZE: usize = 16;
+
+
+#[derive(Clone, Copy)]
+struct TableDirectoryEntry {
+    tag: Tag,
+    offset: u32,
+    compressed_length: u32,
+    original_length: u32,
+    original_checksum: u32,
+}
+
+impl FromData for TableDirectoryEntry {
+    const SIZE: usize = 20;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(TableDirectoryEntry {
+            tag: s.read::<Tag>()?,
+            offset: s.read::<u32>()?,
+            compressed_length: s.read::<u32>()?,
+            original_length: s.read::<u32>()?,
+            original_checksum: s.read::<u32>()?,
+        })
+    }
+}
+
+
+/// A WOFF font.
+#[derive(Clone, Copy)]
+pub struct Font<'a> {
+    data: &'a [u8],
+    flavor: u32,
+    major_version: u16,
+    minor_version: u16,
+    tables: LazyArray16<'a, TableDirectoryEntry>,
+    metadata: Option<(&'a [u8], u32)>,
+    private_data: Option<&'a [u8]>,
+}
+
+impl<'a> Font<'a> {
+    /// Parses a WOFF header and a table directory.
+    ///
+    /// Tables are not decompressed at this stage,
+    /// but their blocks must be inside the `data`.
+    ///
+    /// Returns `FaceParsingError::UnknownMagic` when data is not a WOFF font.
+    pub fn parse(data: &'a [u8]) -> Result<Self, FaceParsingError> {
+        let mut s = Stream::new(data);
+        let signature: u32 = s.read().ok_or(FaceParsingError::UnknownMagic)?;
+        if signature != SIGNATURE {
+            return Err(FaceParsingError::UnknownMagic);
+        }
+
+        let header = data.get(..HEADER_SIZE).ok_or(FaceParsingError::MalformedFont)?;
+        let mut s = Stream::new_at(header, 4).ok_or(FaceParsingError::MalformedFont)?;
+        let flavor: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let length: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let number_of_tables: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let reserved: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        s.skip::<u32>(); // totalSfntSize
+        let major_version: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let minor_version: u16 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let metadata_offset: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let metadata_length: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let metadata_original_length: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let private_offset: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+        let private_length: u32 = s.read().ok_or(FaceParsingError::MalformedFont)?;
+
+        if reserved != 0 || length as usize != data.len() || number_of_tables == 0 {
+            return Err(FaceParsingError::MalformedFont);
+        }
+
+        let mut s = Stream::new_at(data, HEADER_SIZE).ok_or(FaceParsingError::MalformedFont)?;
+        let tables = s.read_array16::<TableDirectoryEntry>(number_of_tables)
+            .ok_or(FaceParsingError::MalformedFont)?;
+
+        // Check table blocks upfront, so declared lengths can be trusted
+        // at least to the extent of the input data.
+        for table in tables {
+            block(data, table.offset, table.compressed_length)
+                .ok_or(FaceParsingError::MalformedFont)?;
+        }
+
+        let metadata = if metadata_length != 0 {
+            let data = block(data, metadata_offset, metadata_length)
+                .ok_or(FaceParsingError::MalformedFont)?;
+            Some((data, metadata_original_length))
+        } else {
+            None
+        };
+
+        let private_data = if private_length != 0 {
+            Some(block(data, private_offset, private_length).ok_or(FaceParsingError::MalformedFont)?)
+        } else {
+            None
+        };
+
+        Ok(Font {
+            data,
+            flavor,
+            major_version,
+            minor_version,
+            tables,
+            metadata,
+            private_data,
+        })
+    }
+
+    /// Returns the sfnt version of the decoded font.
+    ///
+    /// Usually `0x00010000` or `0x4F54544F` (`OTTO`).
+    #[inline]
+    pub fn flavor(&self) -> u32 {
+        self.flavor
+    }
+
+    /// Returns the font version as `(major, minor)`.
+    ///
+    /// Not related to the WOFF format version.
+    #[inline]
+    pub fn version(&self) -> (u16, u16) {
+        (self.major_version, self.minor_version)
+    }
+
+    /// Returns the number of tables.
+    #[inline]
+    pub fn number_of_tables(&self) -> u16 {
+        self.tables.len()
+    }
+
+    /// Decompresses the extended metadata XML.
+    ///
+    /// Returns `Ok(None)` when the font has no metadata.
+    pub fn metadata(&self) -> Result<Option<String>, FaceParsingError> {
+        let (data, original_length) = match self.metadata {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        if !is_valid_length(data.len() as u32, original_length) {
+            return Err(FaceParsingError::MalformedFont);
+        }
+
+        let xml = inflate::decompress_zlib(data, original_length as usize)
+            .ok_or(FaceParsingError::DecompressionFailed)?;
+        if xml.len() != original_length as usize {
+            return Err(FaceParsingError::DecompressionFailed);
+        }
+
+        String::from_utf8(xml).map(Some).map_err(|_| FaceParsingError::MalformedFont)
+    }
+
+    /// Returns the private data block.
+    #[inline]
+    pub fn private_data(&self) -> Option<&'a [u8]> {
+        self.private_data
+    }
+
+    /// Decodes the font into an sfnt buffer.
+    ///
+    /// Table lengths and checksums are verified.
+    pub fn to_sfnt(&self) -> Result<Vec<u8>, FaceParsingError> {
+        let number_of_tables = self.tables.len();
+
+        // Cannot overflow, since there are at most 65535 tables with a u32 length.
+        let mut size = (SFNT_HEADER_SIZE + usize::from(number_of_tables) * SFNT_TABLE_RECORD_SIZE) as u64;
+        for table in self.tables {
+            if table.compressed_length > table.original_length
+                || !is_valid_length(table.compressed_length, table.original_length)
+            {
+                return Err(FaceParsingError::MalformedFont);
+            }
+
+            size += (u64::from(table.original_length) + 3) & !3;
+        }
+
+        // Table offsets must fit into u32.
+        let size = u32::try_from(size).map_err(|_| FaceParsingError::MalformedFont)? as usize;
+
+        // Tables can overlap, so the sum of original lengths is still not trusted.
+        // The buffer will grow if needed.
+        let max_size = self.data.len().saturating_mul(inflate::MAX_COMPRESSION_RATIO);
+        let mut sfnt = Vec::with_capacity(size.min(max_size));
+        write_sfnt_header(&mut sfnt, self.flavor, number_of_tables);
+
+        let mut offset = SFNT_HEADER_SIZE + usize::from(number_of_tables) * SFNT_TABLE_RECORD_SIZE;
+        for table in self.tables {
+            sfnt.extend_from_slice(&table.tag.to_bytes());
+            sfnt.extend_from_slice(&table.original_checksum.to_be_bytes());
+            sfnt.extend_from_slice(&(offset as u32).to_be_bytes());
+            sfnt.extend_from_slice(&table.original_length.to_be_bytes());
+            offset += padded(table.original_length as usize);
+        }
+
+        for table in self.tables {
+            let data = block(self.data, table.offset, table.compressed_length)
+                .ok_or(FaceParsingError::MalformedFont)?;
+
+            let start = sfnt.len();
+            if table.compressed_length == table.original_length {
+                sfnt.extend_from_slice(data);
+            } else {
+                let original_length = table.original_length as usize;
+                let data = inflate::decompress_zlib(data, original_length)
+                    .ok_or(FaceParsingError::DecompressionFailed)?;
+                if data.len() != original_length {
+                    return Err(FaceParsingError::DecompressionFailed);
+                }
+
+                sfnt.extend_from_slice(&data);
+            }
+
+            let is_head = table.tag == Tag::from_bytes(b"head");
+            if checksum(&sfnt[start..], is_head) != table.original_checksum {
+                return Err(FaceParsingError::MalformedFont);
+            }
+
+            sfnt.resize(start + padded(table.original_length as usize), 0);
+        }
+
+        Ok(sfnt)
+    }
+}
+
+impl core::fmt::Debug for Font<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Font {{ ... }}")
+    }
+}
+
+/// Decodes a WOFF font into an sfnt buffer.
+///
+/// A shorthand for `Font::parse(data)?.to_sfnt()`.
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, FaceParsingError> {
+    Font::parse(data)?.to_sfnt()
+}
+
+/// Writes an sfnt offset table header. Table records are not included.
+pub(crate) fn write_sfnt_header(sfnt: &mut Vec<u8>, flavor: u32, number_of_tables: u16) {
+    sfnt.extend_from_slice(&flavor.to_be_bytes());
+    sfnt.extend_from_slice(&number_of_tables.to_be_bytes());
+
+    // The number of tables is not necessarily a power of two.
+    let mut entry_selector = 0u32;
+    while 1u32 << (entry_selector + 1) <= u32::from(number_of_tables) {
+        entry_selector += 1;
+    }
+
+    // Values are truncated just like in any other font with that many tables.
+    let search_range = (1u32 << entry_selector) * 16;
+    let range_shift = u32::from(number_of_tables) * 16 - search_range;
+    sfnt.extend_from_slice(&(search_range as u16).to_be_bytes());
+    sfnt.extend_from_slice(&(entry_selector as u16).to_be_bytes());
+    sfnt.extend_from_slice(&(range_shift as u16).to_be_bytes());
+}
+
+pub(crate) fn block(data: &[u8], offset: u32, length: u32) -> Option<&[u8]> {
+    let start = offset as usize;
+    let end = start.checked_add(length as usize)?;
+    data.get(start..end)
+}
+
+#[inline]
+pub(crate) fn padded(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Checks that a compressed block can actually be decompressed into `original_length` bytes.
+///
+/// Prevents preallocating a buffer for a declared length the data cannot expand to.
+fn is_valid_length(compressed_length: u32, original_length: u32) -> bool {
+    let max_length = (compressed_length as usize).saturating_mul(inflate::MAX_COMPRESSION_RATIO);
+    original_length as usize <= max_length
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/otff#calculating-checksums
+pub(crate) fn checksum(data: &[u8], is_head: bool) -> u32 {
+    let mut sum = 0u32;
+    for (i, chunk) in data.chunks(4).enumerate() {
+        // `head.checkSumAdjustment` is not a part of the checksum.
+        if is_head && i == 2 {
+            continue;
+        }
+
+        let mut bytes = [0u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        sum = sum.wrapping_add(u32::from_be_bytes(bytes));
+    }
+
+    sum
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    #[test]
+    fn basic() {
+        let data = convert(&[
+            UInt32(SIGNATURE), // signature
+            TrueTypeMagic, // flavor
+            UInt32(119), // length
+            UInt16(2), // number of tables
+            UInt16(0), // reserved
+            UInt32(80), // total sfnt size
+            UInt16(1), // major version
+            UInt16(2), // minor version
+            UInt32(100), // metadata offset
+            UInt32(15), // metadata length
+            UInt32(4), // metadata original length
+            UInt32(116), // private data offset
+            UInt32(3), // private data length
+            // Table [0]
+            Raw(b"abcd"), // tag
+            UInt32(84), // offset
+            UInt32(4), // compressed length
+            UInt32(4), // original length
+            UInt32(0x01020304), // checksum
+            // Table [1]
+            Raw(b"efgh"), // tag
+            UInt32(88), // offset
+            UInt32(11), // compressed length
+            UInt32(32), // original length
+            UInt32(0x0B0B0B08), // checksum
+            // Table [0] data
+            UInt32(0x01020304),
+            // Table [1] data: 32 `a`, zlib-compressed
+            Raw(&[0x78, 0xDA, 0x4B, 0x4C, 0xC4, 0x0F, 0x00, 0xC8, 0x30, 0x0C, 0x21]),
+            UInt8(0), // padding
+            // Metadata: `<m/>`, zlib-compressed
+            Raw(&[0x78, 0x01, 0x01, 0x04, 0x00, 0xFB, 0xFF, 0x3C,
+                  0x6D, 0x2F, 0x3E, 0x02, 0xD7, 0x01, 0x17]),
+            UInt8(0), // padding
+            // Private data
+            Raw(b"foo"),
+        ]);
+
+        let font = Font::parse(&data).unwrap();
+        assert_eq!(font.flavor(), 0x00010000);
+        assert_eq!(font.version(), (1, 2));
+        assert_eq!(font.number_of_tables(), 2);
+        assert_eq!(font.metadata().unwrap().unwrap(), "<m/>");
+        assert_eq!(font.private_data(), Some(&b"foo"[..]));
+
+        let sfnt = font.to_sfnt().unwrap();
+        assert_eq!(sfnt.len(), 12 + 2 * 16 + 4 + 32);
+        assert_eq!(&sfnt[..12], &[0, 1, 0, 0, 0, 2, 0, 32, 0, 1, 0, 0]);
+        assert_eq!(&sfnt[12..16], b"abcd");
+        assert_eq!(&sfnt[44..48], &[1, 2, 3, 4]);
+        assert_eq!(&sfnt[48..], &[b'a'; 32][..]);
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let data = convert(&[
+            UInt32(SIGNATURE), // signature
+            TrueTypeMagic, // flavor
+            UInt32(68), // length
+            UInt16(1), // number of tables
+            UInt16(0), // reserved
+            UInt32(32), // total sfnt size
+            UInt16(1), // major version
+            UInt16(0), // minor version
+            UInt32(0), // metadata offset
+            UInt32(0), // metadata length
+            UInt32(0), // metadata original length
+            UInt32(0), // private data offset
+            UInt32(0), // private data length
+            // Table [0]
+            Raw(b"abcd"), // tag
+            UInt32(64), // offset
+            UInt32(4), // compressed length
+            UInt32(4), // original length
+            UInt32(0), // checksum
+            // Table [0] data
+            UInt32(0x01020304),
+        ]);
+
+        assert_eq!(decode(&data).unwrap_err(), FaceParsingError::MalformedFont);
+    }
+
+    #[test]
+    fn oversized_original_length() {
+        let data = convert(&[
+            UInt32(SIGNATURE), // signature
+            TrueTypeMagic, // flavor
+            UInt32(75), // length
+            UInt16(1), // number of tables
+            UInt16(0), // reserved
+            UInt32(32), // total sfnt size
+            UInt16(1), // major version
+            UInt16(0), // minor version
+            UInt32(0), // metadata offset
+            UInt32(0), // metadata length
+            UInt32(0), // metadata original length
+            UInt32(0), // private data offset
+            UInt32(0), // private data length
+            // Table [0]
+            Raw(b"efgh"), // tag
+            UInt32(64), // offset
+            UInt32(11), // compressed length
+            UInt32(0xFFFFFFFF), // original length
+            UInt32(0x0B0B0B08), // checksum
+            // Table [0] data: 32 `a`, zlib-compressed
+            Raw(&[0x78, 0xDA, 0x4B, 0x4C, 0xC4, 0x0F, 0x00, 0xC8, 0x30, 0x0C, 0x21]),
+        ]);
+
+        assert_eq!(decode(&data).unwrap_err(), FaceParsingError::MalformedFont);
+    }
+
+    #[test]
+    fn table_out_of_bounds() {
+        let data = convert(&[
+            UInt32(SIGNATURE), // signature
+            TrueTypeMagic, // flavor
+            UInt32(68), // length
+            UInt16(1), // number of tables
+            UInt16(0), // reserved
+            UInt32(32), // total sfnt size
+            UInt16(1), // major version
+            UInt16(0), // minor version
+            UInt32(0), // metadata offset
+            UInt32(0), // metadata length
+            UInt32(0), // metadata original length
+            UInt32(0), // private data offset
+            UInt32(0), // private data length
+            // Table [0]
+            Raw(b"abcd"), // tag
+            UInt32(64), // offset
+            UInt32(4_000_000), // compressed length
+            UInt32(4_000_000_000), // original length
+            UInt32(0), // checksum
+            // Table [0] data
+            UInt32(0x01020304),
+        ]);
+
+        assert_eq!(Font::parse(&data).unwrap_err(), FaceParsingError::MalformedFont);
+        assert_eq!(decode(&data).unwrap_err(), FaceParsingError::MalformedFont);
+    }
+
+    #[test]
+    fn not_woff() {
+        assert_eq!(Font::parse(&[0x00, 0x01, 0x00, 0x00]).unwrap_err(),
+                   FaceParsingError::UnknownMagic);
+    }
+}
@@ -0,0 +1,1061 @@
+//! A [WOFF 2.0](https://www.w3.org/TR/WOFF2/) container decoder.
+//!
+//! Just like the WOFF 1.0 decoder, it allocates.
+//! Transformed `glyf`, `loca` and `hmtx` tables are reconstructed,
+//! so the decoded data can be parsed via `Face::from_slice`.
+//! Collections are decoded into a TrueType Collection.
+//!
+//! Available only with the `woff2` feature.
+
+use core::convert::TryFrom;
+use std::io::Read;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{FaceParsingError, Tag};
+use crate::parser::{Stream, NumFrom};
+use crate::woff::{self, SFNT_HEADER_SIZE, SFNT_TABLE_RECORD_SIZE};
+
+const SIGNATURE: u32 = 0x774F4632; // wOF2
+const COLLECTION_FLAVOR: u32 = 0x74746366; // ttcf
+const HEADER_SIZE: usize = 48;
+
+const GLYF: Tag = Tag::from_bytes(b"glyf");
+const HEAD: Tag = Tag::from_bytes(b"head");
+const HHEA: Tag = Tag::from_bytes(b"hhea");
+const HMTX: Tag = Tag::from_bytes(b"hmtx");
+const LOCA: Tag = Tag::from_bytes(b"loca");
+
+// https://www.w3.org/TR/WOFF2/#table_dir_format
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post",
+    b"cvt ", b"fpgm", b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT",
+    b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea",
+    b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH",
+    b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar",
+    b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop",
+    b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill",
+];
+
+
+#[derive(Clone, Copy)]
+struct TableEntry {
+    tag: Tag,
+    transformed: bool,
+    original_length: u32,
+    // A table range in the decompressed data.
+    offset: usize,
+    length: usize,
+}
+
+#[derive(Clone)]
+struct FontEntry {
+    flavor: u32,
+    tables: Vec<u16>,
+}
+
+
+/// A WOFF2 font.
+#[derive(Clone)]
+pub struct Font<'a> {
+    flavor: u32,
+    major_version: u16,
+    minor_version: u16,
+    tables: Vec<TableEntry>,
+    fonts: Vec<FontEntry>,
+    collection_version: Option<u32>,
+    compressed_data: &'a [u8],
+    metadata: Option<(&'a [u8], u32)>,
+    private_data: Option<&'a [u8]>,
+}
+
+impl<'a> Font<'a> {
+    /// Parses a WOFF2 header, a table directory and a collection directory.
+    ///
+    /// Tables are not decompressed at this stage.
+    ///
+    /// Returns `FaceParsingError::UnknownMagic` when data is not a WOFF2 font.
+    pub fn parse(data: &'a [u8]) -> Result<Self, FaceParsingError> {
+        let mut s = Stream::new(data);
+        let signature: u32 = s.read().ok_or(FaceParsingError::UnknownMagic)?;
+        if signature != SIGNATURE {
+            return Err(FaceParsingError::UnknownMagic);
+        }
+
+        Self::parse_impl(data).ok_or(FaceParsingError::MalformedFont)
+    }
+
+    fn parse_impl(data: &'a [u8]) -> Option<Self> {
+        let header = data.get(..HEADER_SIZE)?;
+        let mut s = Stream::new_at(header, 4)?;
+        let flavor: u32 = s.read()?;
+        let length: u32 = s.read()?;
+        let number_of_tables: u16 = s.read()?;
+        let reserved: u16 = s.read()?;
+        s.skip::<u32>(); // totalSfntSize
+        let total_compressed_size: u32 = s.read()?;
+        let major_version: u16 = s.read()?;
+        let minor_version: u16 = s.read()?;
+        let metadata_offset: u32 = s.read()?;
+        let metadata_length: u32 = s.read()?;
+        let metadata_original_length: u32 = s.read()?;
+        let private_offset: u32 = s.read()?;
+        let private_length: u32 = s.read()?;
+
+        if reserved != 0 || usize::num_from(length) != data.len() || number_of_tables == 0 {
+            return None;
+        }
+
+        let mut s = Stream::new_at(data, HEADER_SIZE)?;
+        let mut tables = Vec::with_capacity(usize::from(number_of_tables));
+        let mut offset = 0usize;
+        for _ in 0..number_of_tables {
+            let flags: u8 = s.read()?;
+            let tag = match flags & 0x3F {
+                63 => s.read::<Tag>()?,
+                index => Tag::from_bytes(KNOWN_TAGS[usize::from(index)]),
+            };
+
+            // `glyf` and `loca` are transformed by default,
+            // while other tables must opt-in.
+            let transform_version = flags >> 6;
+            let transformed = if tag == GLYF || tag == LOCA {
+                transform_version == 0
+            } else {
+                transform_version != 0
+            };
+
+            let original_length = read_base128(&mut s)?;
+            let length = if transformed { read_base128(&mut s)? } else { original_length };
+            let length = usize::num_from(length);
+
+            // The transformed `loca` table is reconstructed from `glyf` and has no data.
+            if transformed && tag == LOCA && length != 0 {
+                return None;
+            }
+
+            tables.push(TableEntry {
+                tag,
+                transformed,
+                original_length,
+                offset,
+                length,
+            });
+
+            offset = offset.checked_add(length)?;
+        }
+
+        let mut collection_version = None;
+        let mut fonts = Vec::new();
+        if flavor == COLLECTION_FLAVOR {
+            let version: u32 = s.read()?;
+            if version != 0x00010000 && version != 0x00020000 {
+                return None;
+            }
+
+            collection_version = Some(version);
+
+            let number_of_fonts = read_255_u16(&mut s)?;
+            for _ in 0..number_of_fonts {
+                let number_of_tables = read_255_u16(&mut s)?;
+                let flavor: u32 = s.read()?;
+                let mut indices = Vec::with_capacity(usize::from(number_of_tables));
+                for _ in 0..number_of_tables {
+                    let index = read_255_u16(&mut s)?;
+                    if usize::from(index) >= tables.len() {
+                        return None;
+                    }
+
+                    indices.push(index);
+                }
+
+                fonts.push(FontEntry { flavor, tables: indices });
+            }
+        } else {
+            fonts.push(FontEntry { flavor, tables: (0..number_of_tables).collect() });
+        }
+
+        let compressed_data = s.read_bytes(usize::num_from(total_compressed_size))?;
+
+        let metadata = if metadata_length != 0 {
+            let data = woff::block(data, metadata_offset, metadata_length)?;
+            Some((data, metadata_original_length))
+        } else {
+            None
+        };
+
+        let private_data = if private_length != 0 {
+            Some(woff::block(data, private_offset, private_length)?)
+        } else {
+            None
+        };
+
+        Some(Font {
+            flavor,
+            major_version,
+            minor_version,
+            tables,
+            fonts,
+            collection_version,
+            compressed_data,
+            metadata,
+            private_data,
+        })
+    }
+
+    /// Returns the sfnt version of the decoded font.
+    ///
+    /// Usually `0x00010000`, `0x4F54544F` (`OTTO`) or `0x74746366` (`ttcf`).
+    #[inline]
+    pub fn flavor(&self) -> u32 {
+        self.flavor
+    }
+
+    /// Returns the font version as `(major, minor)`.
+    ///
+    /// Not related to the WOFF2 format version.
+    #[inline]
+    pub fn version(&self) -> (u16, u16) {
+        (self.major_version, self.minor_version)
+    }
+
+    /// Returns the number of fonts.
+    ///
+    /// Always 1 for non-collection fonts.
+    #[inline]
+    pub fn number_of_fonts(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// Decompresses the extended metadata XML.
+    ///
+    /// Returns `Ok(None)` when the font has no metadata.
+    pub fn metadata(&self) -> Result<Option<String>, FaceParsingError> {
+        let (data, original_length) = match self.metadata {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let xml = decompress_brotli(data, usize::num_from(original_length))
+            .ok_or(FaceParsingError::DecompressionFailed)?;
+        String::from_utf8(xml).map(Some).map_err(|_| FaceParsingError::MalformedFont)
+    }
+
+    /// Returns the private data block.
+    #[inline]
+    pub fn private_data(&self) -> Option<&'a [u8]> {
+        self.private_data
+    }
+
+    /// Decodes the font into an sfnt buffer.
+    ///
+    /// Collections are decoded into a TrueType Collection
+    /// with shared tables stored only once.
+    /// Use `fonts_in_collection` and a face index to access individual fonts.
+    ///
+    /// Tables checksums are recalculated,
+    /// but `head.checkSumAdjustment` is left as is.
+    pub fn to_sfnt(&self) -> Result<Vec<u8>, FaceParsingError> {
+        let total_length = self.tables.last().map(|t| t.offset + t.length).unwrap_or(0);
+        let data = decompress_brotli(self.compressed_data, total_length)
+            .ok_or(FaceParsingError::DecompressionFailed)?;
+
+        let tables = self.reconstruct_tables(&data).ok_or(FaceParsingError::MalformedFont)?;
+        self.write_sfnt(&data, &tables).ok_or(FaceParsingError::MalformedFont)
+    }
+
+    // Returns reconstructed tables data. `None` indicates a non-transformed table.
+    fn reconstruct_tables(&self, data: &[u8]) -> Option<Vec<Option<Vec<u8>>>> {
+        let mut tables = vec![None; self.tables.len()];
+        let mut x_mins = vec![None; self.tables.len()];
+
+        for (index, table) in self.tables.iter().enumerate() {
+            if !table.transformed || table.tag == LOCA {
+                continue;
+            }
+
+            if table.tag == GLYF {
+                // The transformed `loca` must immediately follow the transformed `glyf`.
+                let loca = self.tables.get(index + 1)?;
+                if loca.tag != LOCA || !loca.transformed {
+                    return None;
+                }
+
+                let glyf = reconstruct_glyf(data.get(table.offset..table.offset + table.length)?)?;
+                if glyf.loca.len() != usize::num_from(loca.original_length) {
+                    return None;
+                }
+
+                tables[index] = Some(glyf.glyf);
+                tables[index + 1] = Some(glyf.loca);
+                x_mins[index] = Some(glyf.x_mins);
+            } else if table.tag != HMTX {
+                // Only `glyf`, `loca` and `hmtx` transforms are defined.
+                return None;
+            }
+        }
+
+        // `hmtx` depends on `glyf` and `hhea` of the same font.
+        for font in &self.fonts {
+            let find = |tag: Tag| {
+                font.tables.iter().map(|i| usize::from(*i)).find(|i| self.tables[*i].tag == tag)
+            };
+
+            let hmtx_index = match find(HMTX) {
+                Some(index) if self.tables[index].transformed && tables[index].is_none() => index,
+                _ => continue,
+            };
+
+            let x_mins = x_mins[find(GLYF)?].as_ref()?;
+            let hhea = self.tables[find(HHEA)?];
+            let hhea = data.get(hhea.offset..hhea.offset + hhea.length)?;
+            let number_of_h_metrics = crate::hhea::number_of_h_metrics(hhea)?.get();
+
+            let hmtx = self.tables[hmtx_index];
+            let hmtx = reconstruct_hmtx(
+                data.get(hmtx.offset..hmtx.offset + hmtx.length)?,
+                number_of_h_metrics,
+                x_mins,
+            )?;
+
+            if hmtx.len() != usize::num_from(self.tables[hmtx_index].original_length) {
+                return None;
+            }
+
+            tables[hmtx_index] = Some(hmtx);
+        }
+
+        // Make sure that all transformed tables were reconstructed.
+        for (table, reconstructed) in self.tables.iter().zip(tables.iter()) {
+            if table.transformed && reconstructed.is_none() {
+                return None;
+            }
+        }
+
+        Some(tables)
+    }
+
+    fn write_sfnt(&self, data: &[u8], reconstructed: &[Option<Vec<u8>>]) -> Option<Vec<u8>> {
+        let table_data = |index: usize| -> Option<&[u8]> {
+            match reconstructed[index] {
+                Some(ref data) => Some(data),
+                None => {
+                    let table = self.tables[index];
+                    data.get(table.offset..table.offset + table.length)
+                }
+            }
+        };
+
+        let mut headers_size = 0;
+        if let Some(version) = self.collection_version {
+            headers_size += 12 + self.fonts.len() * 4;
+            if version == 0x00020000 {
+                headers_size += 12;
+            }
+        }
+
+        for font in &self.fonts {
+            headers_size += SFNT_HEADER_SIZE + font.tables.len() * SFNT_TABLE_RECORD_SIZE;
+        }
+
+        // Shared tables are stored only once.
+        let mut offsets = vec![0u32; self.tables.len()];
+        let mut checksums = vec![0u32; self.tables.len()];
+        let mut order = Vec::with_capacity(self.tables.len());
+        let mut offset = headers_size;
+        for font in &self.fonts {
+            for index in font.tables.iter().map(|i| usize::from(*i)) {
+                if offsets[index] != 0 {
+                    continue;
+                }
+
+                let table = table_data(index)?;
+                offsets[index] = u32::try_from(offset).ok()?;
+                checksums[index] = woff::checksum(table, self.tables[index].tag == HEAD);
+                order.push(index);
+                offset = offset.checked_add(woff::padded(table.len()))?;
+            }
+        }
+
+        let mut sfnt = Vec::with_capacity(offset);
+
+        if let Some(version) = self.collection_version {
+            sfnt.extend_from_slice(&COLLECTION_FLAVOR.to_be_bytes());
+            sfnt.extend_from_slice(&version.to_be_bytes());
+            sfnt.extend_from_slice(&(self.fonts.len() as u32).to_be_bytes());
+
+            let mut font_offset = 12 + self.fonts.len() * 4;
+            if version == 0x00020000 {
+                font_offset += 12;
+            }
+
+            for font in &self.fonts {
+                sfnt.extend_from_slice(&(font_offset as u32).to_be_bytes());
+                font_offset += SFNT_HEADER_SIZE + font.tables.len() * SFNT_TABLE_RECORD_SIZE;
+            }
+
+            if version == 0x00020000 {
+                // WOFF2 doesn't preserve the digital signature.
+                sfnt.extend_from_slice(&[0; 12]); // ulDsigTag + ulDsigLength + ulDsigOffset
+            }
+        }
+
+        for font in &self.fonts {
+            woff::write_sfnt_header(&mut sfnt, font.flavor, font.tables.len() as u16);
+
+            // Table records must be sorted by tag.
+            let mut indices: Vec<usize> = font.tables.iter().map(|i| usize::from(*i)).collect();
+            indices.sort_by_key(|i| self.tables[*i].tag);
+
+            for index in indices {
+                let length = table_data(index)?.len() as u32;
+                sfnt.extend_from_slice(&self.tables[index].tag.to_bytes());
+                sfnt.extend_from_slice(&checksums[index].to_be_bytes());
+                sfnt.extend_from_slice(&offsets[index].to_be_bytes());
+                sfnt.extend_from_slice(&length.to_be_bytes());
+            }
+        }
+
+        for index in order {
+            let table = table_data(index)?;
+            sfnt.extend_from_slice(table);
+            sfnt.resize(woff::padded(sfnt.len()), 0);
+        }
+
+        Some(sfnt)
+    }
+}
+
+impl core::fmt::Debug for Font<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Font {{ ... }}")
+    }
+}
+
+/// Decodes a WOFF2 font into an sfnt buffer.
+///
+/// A shorthand for `Font::parse(data)?.to_sfnt()`.
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, FaceParsingError> {
+    Font::parse(data)?.to_sfnt()
+}
+
+
+fn decompress_brotli(data: &[u8], len: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let decoder = brotli_decompressor::Decompressor::new(data, 4096);
+    // Read one more byte than needed to detect an overlong stream.
+    decoder.take(len as u64 + 1).read_to_end(&mut out).ok()?;
+    if out.len() == len {
+        Some(out)
+    } else {
+        None
+    }
+}
+
+// https://www.w3.org/TR/WOFF2/#DataTypes
+fn read_base128(s: &mut Stream) -> Option<u32> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte: u8 = s.read()?;
+
+        // Leading zeros are not allowed.
+        if i == 0 && byte == 0x80 {
+            return None;
+        }
+
+        // Check for overflow.
+        if value & 0xFE000000 != 0 {
+            return None;
+        }
+
+        value = (value << 7) | u32::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+// https://www.w3.org/TR/WOFF2/#DataTypes
+fn read_255_u16(s: &mut Stream) -> Option<u16> {
+    const WORD_CODE: u8 = 253;
+    const ONE_MORE_BYTE_CODE2: u8 = 254;
+    const ONE_MORE_BYTE_CODE1: u8 = 255;
+    const LOWEST_U_CODE: u16 = 253;
+
+    let code: u8 = s.read()?;
+    match code {
+        WORD_CODE => s.read::<u16>(),
+        ONE_MORE_BYTE_CODE1 => Some(u16::from(s.read::<u8>()?) + LOWEST_U_CODE),
+        ONE_MORE_BYTE_CODE2 => Some(u16::from(s.read::<u8>()?) + LOWEST_U_CODE * 2),
+        _ => Some(u16::from(code)),
+    }
+}
+
+
+struct Glyf {
+    glyf: Vec<u8>,
+    loca: Vec<u8>,
+    x_mins: Vec<i16>,
+}
+
+// https://www.w3.org/TR/WOFF2/#glyf_table_format
+fn reconstruct_glyf(data: &[u8]) -> Option<Glyf> {
+    let mut s = Stream::new(data);
+    s.skip::<u16>(); // reserved
+    let option_flags: u16 = s.read()?;
+    let number_of_glyphs: u16 = s.read()?;
+    let index_format: u16 = s.read()?;
+
+    let mut sizes = [0usize; 7];
+    for size in &mut sizes {
+        *size = usize::num_from(s.read::<u32>()?);
+    }
+
+    let mut contours_stream = Stream::new(s.read_bytes(sizes[0])?);
+    let mut points_stream = Stream::new(s.read_bytes(sizes[1])?);
+    let mut flags_stream = Stream::new(s.read_bytes(sizes[2])?);
+    let mut glyphs_stream = Stream::new(s.read_bytes(sizes[3])?);
+    let mut composites_stream = Stream::new(s.read_bytes(sizes[4])?);
+    let bboxes_data = s.read_bytes(sizes[5])?;
+    let mut instructions_stream = Stream::new(s.read_bytes(sizes[6])?);
+
+    // One bit per glyph, padded to 4 bytes.
+    let bbox_bitmap_len = (usize::from(number_of_glyphs) + 31) >> 5 << 2;
+    let bbox_bitmap = bboxes_data.get(..bbox_bitmap_len)?;
+    let mut bboxes_stream = Stream::new(bboxes_data.get(bbox_bitmap_len..)?);
+
+    let overlap_bitmap = if option_flags & 1 != 0 {
+        Some(s.read_bytes((usize::from(number_of_glyphs) + 7) >> 3)?)
+    } else {
+        None
+    };
+
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(usize::from(number_of_glyphs) + 1);
+    let mut x_mins = Vec::with_capacity(usize::from(number_of_glyphs));
+    for glyph_id in 0..usize::from(number_of_glyphs) {
+        offsets.push(glyf.len());
+
+        let number_of_contours: i16 = contours_stream.read()?;
+        let has_bbox = is_bit_set(bbox_bitmap, glyph_id);
+        if number_of_contours == 0 {
+            // An empty glyph cannot have a bounding box.
+            if has_bbox {
+                return None;
+            }
+
+            x_mins.push(0);
+            continue;
+        }
+
+        let explicit_bbox = if has_bbox {
+            Some([
+                bboxes_stream.read::<i16>()?,
+                bboxes_stream.read::<i16>()?,
+                bboxes_stream.read::<i16>()?,
+                bboxes_stream.read::<i16>()?,
+            ])
+        } else {
+            None
+        };
+
+        if number_of_contours > 0 {
+            let has_overlap = overlap_bitmap.map(|b| is_bit_set(b, glyph_id)).unwrap_or(false);
+            let x_min = write_simple_glyph(
+                number_of_contours as u16,
+                explicit_bbox,
+                has_overlap,
+                &mut points_stream,
+                &mut flags_stream,
+                &mut glyphs_stream,
+                &mut instructions_stream,
+                &mut glyf,
+            )?;
+            x_mins.push(x_min);
+        } else if number_of_contours == -1 {
+            // Composite glyphs must have an explicit bounding box.
+            let bbox = explicit_bbox?;
+            let (components, has_instructions) = read_components(&mut composites_stream)?;
+
+            glyf.extend_from_slice(&number_of_contours.to_be_bytes());
+            for n in &bbox {
+                glyf.extend_from_slice(&n.to_be_bytes());
+            }
+
+            glyf.extend_from_slice(components);
+
+            if has_instructions {
+                let len = read_255_u16(&mut glyphs_stream)?;
+                glyf.extend_from_slice(&len.to_be_bytes());
+                glyf.extend_from_slice(instructions_stream.read_bytes(usize::from(len))?);
+            }
+
+            x_mins.push(bbox[0]);
+        } else {
+            return None;
+        }
+
+        glyf.resize(woff::padded(glyf.len()), 0);
+    }
+
+    offsets.push(glyf.len());
+
+    let mut loca = Vec::new();
+    for offset in offsets {
+        if index_format == 0 {
+            // Glyphs are padded, so offsets are always even.
+            let offset = u16::try_from(offset / 2).ok()?;
+            loca.extend_from_slice(&offset.to_be_bytes());
+        } else {
+            let offset = u32::try_from(offset).ok()?;
+            loca.extend_from_slice(&offset.to_be_bytes());
+        }
+    }
+
+    Some(Glyf { glyf, loca, x_mins })
+}
+
+// Returns glyph's xMin.
+#[allow(clippy::too_many_arguments)]
+fn write_simple_glyph(
+    number_of_contours: u16,
+    explicit_bbox: Option<[i16; 4]>,
+    has_overlap: bool,
+    points_stream: &mut Stream,
+    flags_stream: &mut Stream,
+    glyphs_stream: &mut Stream,
+    instructions_stream: &mut Stream,
+    glyf: &mut Vec<u8>,
+) -> Option<i16> {
+    const ON_CURVE_POINT: u8 = 0x01;
+    const X_SHORT_VECTOR: u8 = 0x02;
+    const Y_SHORT_VECTOR: u8 = 0x04;
+    const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+    const Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0x20;
+    const OVERLAP_SIMPLE: u8 = 0x40;
+
+    let mut end_points = Vec::with_capacity(usize::from(number_of_contours));
+    let mut number_of_points = 0u16;
+    for _ in 0..number_of_contours {
+        number_of_points = number_of_points.checked_add(read_255_u16(points_stream)?)?;
+        end_points.push(number_of_points.checked_sub(1)?);
+    }
+
+    let mut bbox = [i16::MAX, i16::MAX, i16::MIN, i16::MIN];
+    let mut flags = Vec::with_capacity(usize::from(number_of_points));
+    let mut x_coordinates = Vec::new();
+    let mut y_coordinates = Vec::new();
+    let mut x = 0i16;
+    let mut y = 0i16;
+    for (i, flag) in flags_stream.read_bytes(usize::from(number_of_points))?.iter().enumerate() {
+        let (dx, dy) = read_triplet(*flag & 0x7F, glyphs_stream)?;
+        let dx = i16::try_from(dx).ok()?;
+        let dy = i16::try_from(dy).ok()?;
+        x = x.checked_add(dx)?;
+        y = y.checked_add(dy)?;
+
+        bbox[0] = bbox[0].min(x);
+        bbox[1] = bbox[1].min(y);
+        bbox[2] = bbox[2].max(x);
+        bbox[3] = bbox[3].max(y);
+
+        let mut flag = if *flag & 0x80 == 0 { ON_CURVE_POINT } else { 0 };
+        if has_overlap && i == 0 {
+            flag |= OVERLAP_SIMPLE;
+        }
+
+        if dx == 0 {
+            flag |= X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR;
+        } else if dx > -256 && dx < 256 {
+            flag |= X_SHORT_VECTOR;
+            if dx > 0 {
+                flag |= X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR;
+                x_coordinates.push(dx as u8);
+            } else {
+                x_coordinates.push((-dx) as u8);
+            }
+        } else {
+            x_coordinates.extend_from_slice(&dx.to_be_bytes());
+        }
+
+        if dy == 0 {
+            flag |= Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR;
+        } else if dy > -256 && dy < 256 {
+            flag |= Y_SHORT_VECTOR;
+            if dy > 0 {
+                flag |= Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR;
+                y_coordinates.push(dy as u8);
+            } else {
+                y_coordinates.push((-dy) as u8);
+            }
+        } else {
+            y_coordinates.extend_from_slice(&dy.to_be_bytes());
+        }
+
+        flags.push(flag);
+    }
+
+    let bbox = explicit_bbox.unwrap_or(bbox);
+
+    let instructions_len = read_255_u16(glyphs_stream)?;
+    let instructions = instructions_stream.read_bytes(usize::from(instructions_len))?;
+
+    glyf.extend_from_slice(&number_of_contours.to_be_bytes());
+    for n in &bbox {
+        glyf.extend_from_slice(&n.to_be_bytes());
+    }
+
+    for n in end_points {
+        glyf.extend_from_slice(&n.to_be_bytes());
+    }
+
+    glyf.extend_from_slice(&instructions_len.to_be_bytes());
+    glyf.extend_from_slice(instructions);
+    glyf.extend_from_slice(&flags);
+    glyf.extend_from_slice(&x_coordinates);
+    glyf.extend_from_slice(&y_coordinates);
+
+    Some(bbox[0])
+}
+
+// https://www.w3.org/TR/WOFF2/#triplet_decoding
+fn read_triplet(flag: u8, s: &mut Stream) -> Option<(i32, i32)> {
+    fn with_sign(flag: i32, value: i32) -> i32 {
+        if flag & 1 != 0 { value } else { -value }
+    }
+
+    let flag = i32::from(flag);
+    let mut byte = || s.read::<u8>().map(i32::from);
+    let delta = if flag < 10 {
+        (0, with_sign(flag, ((flag & 14) << 7) + byte()?))
+    } else if flag < 20 {
+        (with_sign(flag, (((flag - 10) & 14) << 7) + byte()?), 0)
+    } else if flag < 84 {
+        let b0 = flag - 20;
+        let b1 = byte()?;
+        (
+            with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+            with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+        )
+    } else if flag < 120 {
+        let b0 = flag - 84;
+        let b1 = byte()?;
+        let b2 = byte()?;
+        (
+            with_sign(flag, 1 + ((b0 / 12) << 8) + b1),
+            with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2),
+        )
+    } else if flag < 124 {
+        let b1 = byte()?;
+        let b2 = byte()?;
+        let b3 = byte()?;
+        (
+            with_sign(flag, (b1 << 4) + (b2 >> 4)),
+            with_sign(flag >> 1, ((b2 & 0x0F) << 8) + b3),
+        )
+    } else {
+        let b1 = byte()?;
+        let b2 = byte()?;
+        let b3 = byte()?;
+        let b4 = byte()?;
+        (with_sign(flag, (b1 << 8) + b2), with_sign(flag >> 1, (b3 << 8) + b4))
+    };
+
+    Some(delta)
+}
+
+// Returns raw composite glyph components and a flag indicating that glyph has instructions.
+fn read_components<'a>(s: &mut Stream<'a>) -> Option<(&'a [u8], bool)> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+    const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+    let mut components = Stream::new(s.tail()?);
+    let mut has_instructions = false;
+    loop {
+        let flags: u16 = components.read()?;
+        components.skip::<u16>(); // glyph index
+
+        let mut len = if flags & ARG_1_AND_2_ARE_WORDS != 0 { 4 } else { 2 };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            len += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            len += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            len += 8;
+        }
+
+        components.advance_checked(len)?;
+        has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+
+    let data = s.read_bytes(components.offset())?;
+    Some((data, has_instructions))
+}
+
+// https://www.w3.org/TR/WOFF2/#hmtx_table_format
+fn reconstruct_hmtx(data: &[u8], number_of_h_metrics: u16, x_mins: &[i16]) -> Option<Vec<u8>> {
+    let number_of_glyphs = u16::try_from(x_mins.len()).ok()?;
+    if number_of_h_metrics > number_of_glyphs {
+        return None;
+    }
+
+    let mut s = Stream::new(data);
+    let flags: u8 = s.read()?;
+    let has_proportional_lsbs = flags & 0x01 == 0;
+    let has_monospaced_lsbs = flags & 0x02 == 0;
+
+    // Reserved bits must be unset and at least one of the arrays must be omitted.
+    if flags & 0xFC != 0 || (has_proportional_lsbs && has_monospaced_lsbs) {
+        return None;
+    }
+
+    let advances = s.read_array16::<u16>(number_of_h_metrics)?;
+    let proportional_lsbs = if has_proportional_lsbs {
+        Some(s.read_array16::<i16>(number_of_h_metrics)?)
+    } else {
+        None
+    };
+
+    let monospaced_lsbs = if has_monospaced_lsbs {
+        Some(s.read_array16::<i16>(number_of_glyphs - number_of_h_metrics)?)
+    } else {
+        None
+    };
+
+    let mut hmtx = Vec::with_capacity(usize::from(number_of_h_metrics) * 2 + x_mins.len() * 2);
+    for i in 0..number_of_h_metrics {
+        let lsb = match proportional_lsbs {
+            Some(lsbs) => lsbs.get(i)?,
+            None => x_mins[usize::from(i)],
+        };
+
+        hmtx.extend_from_slice(&advances.get(i)?.to_be_bytes());
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+
+    for i in number_of_h_metrics..number_of_glyphs {
+        let lsb = match monospaced_lsbs {
+            Some(lsbs) => lsbs.get(i - number_of_h_metrics)?,
+            None => x_mins[usize::from(i)],
+        };
+
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+
+    Some(hmtx)
+}
+
+#[inline]
+fn is_bit_set(bitmap: &[u8], index: usize) -> bool {
+    bitmap.get(index / 8).map(|b| b & (0x80 >> (index % 8)) != 0).unwrap_or(false)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Face, GlyphId, Rect};
+    use crate::writer::TtfType::*;
+    use crate::writer::convert;
+
+    // Wraps data into uncompressed Brotli meta-blocks.
+    fn brotli_uncompressed(data: &[u8]) -> Vec<u8> {
+        // WBITS: 16, ISLAST: 0, MNIBBLES: 4, MLEN - 1, ISUNCOMPRESSED: 1
+        let bits = (data.len() as u32 - 1) << 4 | 1 << 20;
+        let mut out = vec![bits as u8, (bits >> 8) as u8, (bits >> 16) as u8];
+        out.extend_from_slice(data);
+        out.push(0x03); // ISLAST: 1, ISLASTEMPTY: 1
+        out
+    }
+
+    fn head() -> Vec<u8> {
+        let mut data = convert(&[
+            UInt32(0x00010000), // version
+            UInt32(0), // font revision
+            UInt32(0), // checksum adjustment
+            UInt32(0x5F0F3CF5), // magic
+            UInt16(0), // flags
+            UInt16(1000), // units per em
+        ]);
+        data.resize(54, 0); // indexToLocFormat: short
+        data
+    }
+
+    fn hhea(number_of_h_metrics: u16) -> Vec<u8> {
+        let mut data = convert(&[UInt32(0x00010000)]); // version
+        data.resize(34, 0);
+        data.extend_from_slice(&number_of_h_metrics.to_be_bytes());
+        data
+    }
+
+    fn maxp(number_of_glyphs: u16) -> Vec<u8> {
+        convert(&[
+            UInt32(0x00005000), // version: 0.5
+            UInt16(number_of_glyphs),
+        ])
+    }
+
+    fn woff2(flavor: u32, directory: &[u8], tables: &[u8]) -> Vec<u8> {
+        let compressed = brotli_uncompressed(tables);
+        let mut data = convert(&[
+            UInt32(SIGNATURE), // signature
+            UInt32(flavor), // flavor
+            UInt32(0), // length, set later
+            UInt16(0), // number of tables, set later
+            UInt16(0), // reserved
+            UInt32(0), // total sfnt size
+            UInt32(compressed.len() as u32), // total compressed size
+            UInt16(1), // major version
+            UInt16(0), // minor version
+            UInt32(0), // metadata offset
+            UInt32(0), // metadata length
+            UInt32(0), // metadata original length
+            UInt32(0), // private data offset
+            UInt32(0), // private data length
+        ]);
+        data.extend_from_slice(directory);
+        data.extend_from_slice(&compressed);
+
+        let len = data.len() as u32;
+        data[8..12].copy_from_slice(&len.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn transformed_glyf_and_hmtx() {
+        let glyf = convert(&[
+            UInt16(0), // reserved
+            UInt16(0), // option flags
+            UInt16(2), // number of glyphs
+            UInt16(0), // index format: short
+            UInt32(4), // contours stream size
+            UInt32(1), // points stream size
+            UInt32(3), // flags stream size
+            UInt32(5), // glyphs stream size
+            UInt32(0), // composites stream size
+            UInt32(4), // bboxes stream size
+            UInt32(0), // instructions stream size
+            // Contours stream
+            Int16(0), // glyph 0: empty
+            Int16(1), // glyph 1: one contour
+            // Points stream
+            UInt8(3), // contour 0: 3 points
+            // Flags stream
+            UInt8(11), // dx: +1 byte
+            UInt8(11), // dx: +1 byte
+            UInt8(86), // dx: -1 byte, dy: +1 byte
+            // Glyphs stream
+            UInt8(10), // point 0: (10, 0)
+            UInt8(100), // point 1: (110, 0)
+            UInt8(49), // point 2: (60, 100)
+            UInt8(99),
+            UInt8(0), // instructions length
+            // Bboxes stream
+            UInt32(0), // bitmap, no explicit bboxes
+        ]);
+
+        let hmtx = convert(&[
+            UInt8(1), // flags: no proportional lsb
+            UInt16(500), // advance [0]
+            UInt16(600), // advance [1]
+        ]);
+
+        let mut tables = Vec::new();
+        tables.extend_from_slice(&glyf);
+        tables.extend_from_slice(&hmtx);
+        tables.extend_from_slice(&head());
+        tables.extend_from_slice(&hhea(2));
+        tables.extend_from_slice(&maxp(2));
+
+        let directory = convert(&[
+            UInt8(10), // glyf, transformed
+            UInt8(24), // original length: 24
+            UInt8(glyf.len() as u8), // transform length
+            UInt8(11), // loca, transformed
+            UInt8(6), // original length: 6
+            UInt8(0), // transform length
+            UInt8(3 | 0x40), // hmtx, transformed
+            UInt8(8), // original length: 8
+            UInt8(hmtx.len() as u8), // transform length
+            UInt8(1), // head
+            UInt8(54), // original length: 54
+            UInt8(2), // hhea
+            UInt8(36), // original length: 36
+            UInt8(4), // maxp
+            UInt8(6), // original length: 6
+        ]);
+
+        let mut data = woff2(0x00010000, &directory, &tables);
+        data[12..14].copy_from_slice(&6u16.to_be_bytes()); // number of tables
+
+        let sfnt = decode(&data).unwrap();
+        let face = Face::from_slice(&sfnt, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), 2);
+        assert_eq!(face.glyph_hor_advance(GlyphId(1)), Some(600));
+        assert_eq!(face.glyph_hor_side_bearing(GlyphId(1)), Some(10));
+        assert_eq!(face.glyph_bounding_box(GlyphId(1)),
+                   Some(Rect { x_min: 10, y_min: 0, x_max: 110, y_max: 100 }));
+        assert_eq!(face.glyph_bounding_box(GlyphId(0)), None);
+    }
+
+    #[test]
+    fn collection() {
+        let mut tables = Vec::new();
+        tables.extend_from_slice(&head());
+        tables.extend_from_slice(&hhea(1));
+        tables.extend_from_slice(&maxp(1));
+        tables.extend_from_slice(&maxp(2));
+
+        let directory = convert(&[
+            UInt8(1), // head
+            UInt8(54), // original length: 54
+            UInt8(2), // hhea
+            UInt8(36), // original length: 36
+            UInt8(4), // maxp
+            UInt8(6), // original length: 6
+            UInt8(4), // maxp
+            UInt8(6), // original length: 6
+            // Collection directory
+            UInt32(0x00020000), // version
+            UInt8(2), // number of fonts
+            // Font [0]
+            UInt8(3), // number of tables
+            TrueTypeMagic, // flavor
+            UInt8(0), UInt8(1), UInt8(2), // table indices
+            // Font [1]
+            UInt8(3), // number of tables
+            TrueTypeMagic, // flavor
+            UInt8(0), UInt8(1), UInt8(3), // table indices
+        ]);
+
+        let mut data = woff2(COLLECTION_FLAVOR, &directory, &tables);
+        data[12..14].copy_from_slice(&4u16.to_be_bytes()); // number of tables
+
+        let font = Font::parse(&data).unwrap();
+        assert_eq!(font.number_of_fonts(), 2);
+
+        let sfnt = font.to_sfnt().unwrap();
+        assert_eq!(crate::fonts_in_collection(&sfnt), Some(2));
+        assert_eq!(Face::from_slice(&sfnt, 0).unwrap().number_of_glyphs(), 1);
+        assert_eq!(Face::from_slice(&sfnt, 1).unwrap().number_of_glyphs(), 2);
+        // Shared tables are stored once.
+        assert_eq!(sfnt.len(), 12 + 2 * 4 + 12 + 2 * (12 + 3 * 16) + 56 + 36 + 8 + 8);
+    }
+
+    #[test]
+    fn not_woff2() {
+        assert_eq!(Font::parse(&[0x77, 0x4F, 0x46, 0x46]).unwrap_err(),
+                   FaceParsingError::UnknownMagic);
+    }
+}